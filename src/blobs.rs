@@ -0,0 +1,75 @@
+// src/blobs.rs
+use crate::schema::AppStateData;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Lower-case hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Blobs are fanned out by the first two hex digits: `<dir>/ab/abcdef...`.
+/// Fails on anything but a lower-case hex SHA-256, e.g. a hand-edited `data.json`.
+pub fn blob_path(dir: &Path, sha256: &str) -> io::Result<PathBuf> {
+    let valid = sha256.len() == 64
+        && sha256
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("not a SHA-256: {:?}", sha256),
+        ));
+    }
+    Ok(dir.join(&sha256[..2]).join(sha256))
+}
+
+/// Writes `bytes` under their hash unless an intact copy is already stored.
+/// The blob is written to a temporary file and renamed into place, so a crash
+/// never leaves a partial blob under its final name.
+pub fn store_blob(dir: &Path, bytes: &[u8]) -> io::Result<String> {
+    let sha256 = sha256_hex(bytes);
+    let path = blob_path(dir, &sha256)?;
+    // Rewrites blobs torn by a crash before writes were atomic
+    let intact = fs::read(&path).is_ok_and(|stored| sha256_hex(&stored) == sha256);
+    if !intact {
+        let parent = path.parent().unwrap_or(dir);
+        fs::create_dir_all(parent)?;
+        let temp = parent.join(format!(".{}.{}.tmp", sha256, Uuid::new_v4()));
+        if let Err(err) = fs::write(&temp, bytes).and_then(|()| fs::rename(&temp, &path)) {
+            let _ = fs::remove_file(&temp);
+            return Err(err);
+        }
+    }
+    Ok(sha256)
+}
+
+/// Deletes every blob in `candidates` that no stored task references any more.
+pub fn remove_orphaned_blobs<I>(data: &AppStateData, dir: &Path, candidates: I)
+where
+    I: IntoIterator<Item = String>,
+{
+    let referenced: HashSet<&str> = data
+        .users
+        .values()
+        .flat_map(|user| user.tasks.values())
+        .chain(data.workspaces.values().flat_map(|ws| ws.tasks.values()))
+        .flat_map(|task| task.attachments.iter().map(|a| a.sha256.as_str()))
+        .collect();
+
+    for sha256 in candidates {
+        if referenced.contains(sha256.as_str()) {
+            continue;
+        }
+        match blob_path(dir, &sha256).and_then(fs::remove_file) {
+            Ok(()) => info!("Removed orphaned blob: {}", sha256),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => warn!("Failed to remove blob {}: {}", sha256, err),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// src/board.rs
+use crate::schema::{Status, Task};
+use crate::workflow::Workflow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BoardColumn {
+    pub status: Status,
+    pub terminal: bool,
+    pub wip_limit: Option<usize>,
+    pub tasks: Vec<Task>,
+}
+
+/// Tasks grouped into one column per workflow state, in workflow order
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Board {
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    TaskNotFound(Uuid),
+    UnknownStatus(Status),
+    TransitionNotAllowed { from: Status, to: Status },
+    WipLimitReached { status: Status, limit: usize },
+}
+
+/// Ids of the tasks in `status`, in board order. Ties on rank (e.g. data written
+/// before ranks existed) fall back to due date so the order stays stable.
+fn column(tasks: &HashMap<Uuid, Task>, status: &Status) -> Vec<Uuid> {
+    let mut column: Vec<&Task> = tasks
+        .values()
+        .filter(|task| task.status == *status)
+        .collect();
+    column.sort_by_key(|task| (task.rank, task.due_date, task.id));
+    column.into_iter().map(|task| task.id).collect()
+}
+
+fn renumber(tasks: &mut HashMap<Uuid, Task>, order: &[Uuid]) {
+    for (rank, id) in order.iter().enumerate() {
+        if let Some(task) = tasks.get_mut(id) {
+            task.rank = rank as u32;
+        }
+    }
+}
+
+pub fn build_board(tasks: &HashMap<Uuid, Task>, workflow: &Workflow) -> Board {
+    let columns = workflow
+        .states
+        .iter()
+        .map(|state| BoardColumn {
+            status: state.status.clone(),
+            terminal: state.terminal,
+            wip_limit: state.wip_limit,
+            tasks: column(tasks, &state.status)
+                .iter()
+                .filter_map(|id| tasks.get(id).cloned())
+                .collect(),
+        })
+        .collect();
+    Board { columns }
+}
+
+/// Rank that places a task at the bottom of the `status` column
+pub fn next_rank(tasks: &HashMap<Uuid, Task>, status: &Status) -> u32 {
+    tasks
+        .values()
+        .filter(|task| task.status == *status)
+        .map(|task| task.rank + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Fails when one more task in `status` would exceed its WIP limit
+pub fn check_wip_limit(
+    tasks: &HashMap<Uuid, Task>,
+    workflow: &Workflow,
+    status: &Status,
+) -> Result<(), MoveError> {
+    match workflow.wip_limit(status) {
+        Some(limit) if tasks.values().filter(|t| t.status == *status).count() >= limit => {
+            Err(MoveError::WipLimitReached {
+                status: status.clone(),
+                limit,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Moves `task_id` into `status` at `position` (clamped to the column length),
+/// validating the workflow transition and the target column's WIP limit first.
+/// Nothing is modified unless every check passes.
+pub fn move_task(
+    tasks: &mut HashMap<Uuid, Task>,
+    workflow: &Workflow,
+    task_id: Uuid,
+    status: Status,
+    position: usize,
+) -> Result<(), MoveError> {
+    let current = match tasks.get(&task_id) {
+        Some(task) => task.status.clone(),
+        None => return Err(MoveError::TaskNotFound(task_id)),
+    };
+    if !workflow.has_state(&status) {
+        return Err(MoveError::UnknownStatus(status));
+    }
+    if !workflow.can_transition(&current, &status) {
+        return Err(MoveError::TransitionNotAllowed {
+            from: current,
+            to: status,
+        });
+    }
+    let changed = current != status;
+    if changed {
+        check_wip_limit(tasks, workflow, &status)?;
+    }
+
+    let mut target: Vec<Uuid> = column(tasks, &status)
+        .into_iter()
+        .filter(|id| *id != task_id)
+        .collect();
+    target.insert(position.min(target.len()), task_id);
+
+    if let Some(task) = tasks.get_mut(&task_id) {
+        task.status = status;
+    }
+    renumber(tasks, &target);
+    if changed {
+        // Close the gap left in the source column
+        let source = column(tasks, &current);
+        renumber(tasks, &source);
+    }
+    Ok(())
+}
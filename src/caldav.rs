@@ -0,0 +1,449 @@
+// src/caldav.rs
+use crate::{
+    ical::render_calendar,
+    schema::{AppStateData, Task, User},
+};
+use actix_web::{
+    http::{header, Method, StatusCode},
+    HttpRequest, HttpResponse,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use uuid::Uuid;
+
+pub const DAV_NS: &str = "DAV:";
+pub const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+pub const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+
+/// Value of the `DAV` header advertised on every CalDAV resource
+pub const DAV_COMPLIANCE: &str = "1, 3, calendar-access";
+
+/// A property as (namespace, local name)
+pub type PropName = (String, String);
+
+/// A CalDAV resource of one user
+pub enum Resource<'a> {
+    /// `/caldav/`, where clients discover their principal
+    Root(&'a User),
+    /// `/caldav/{userId}/`, the principal and calendar home
+    Home(&'a User),
+    /// `/caldav/{userId}/tasks/`, the calendar holding the tasks
+    Calendar(&'a User),
+    /// `/caldav/{userId}/tasks/{taskId}.ics`
+    Task(&'a User, &'a Task),
+}
+
+pub fn home_href(user_id: Uuid) -> String {
+    format!("/caldav/{}/", user_id)
+}
+
+pub fn calendar_href(user_id: Uuid) -> String {
+    format!("/caldav/{}/tasks/", user_id)
+}
+
+pub fn task_href(user_id: Uuid, task_id: Uuid) -> String {
+    format!("/caldav/{}/tasks/{}.ics", user_id, task_id)
+}
+
+/// Strong ETag of a task resource; versions are unique across tasks
+pub fn etag(task: &Task) -> String {
+    format!("\"{}\"", task.version)
+}
+
+/// Changes whenever a task of the calendar is created, changed or deleted
+pub fn ctag(user: &User) -> String {
+    let latest = user.tasks.values().map(|task| task.version).max();
+    format!("\"{}-{}\"", latest.unwrap_or(0), user.tasks.len())
+}
+
+/// The single-task calendar served for a task resource
+pub fn task_calendar(user: &User, task: &Task) -> String {
+    render_calendar(
+        &user.name,
+        std::slice::from_ref(task),
+        &user.workflow(),
+        user.tz(),
+        false,
+    )
+}
+
+/// Compares two secrets in time independent of where they first differ
+fn secrets_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// The user whose CalDAV token is the password of the request's Basic
+/// credentials. The user name is not checked.
+pub fn authenticate(data: &AppStateData, req: &HttpRequest) -> Option<Uuid> {
+    let credentials = req
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(credentials.trim()).ok()?).ok()?;
+    let (_, password) = decoded.split_once(':')?;
+    if password.is_empty() {
+        return None;
+    }
+    data.users
+        .values()
+        .find(|user| {
+            user.caldav_token
+                .as_deref()
+                .is_some_and(|token| secrets_match(token, password))
+        })
+        .map(|user| user.id)
+}
+
+/// A WebDAV extension method such as PROPFIND
+pub fn dav_method(name: &str) -> Method {
+    Method::from_bytes(name.as_bytes()).expect("valid method name")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AuthError {
+    /// No credentials, or no user with that token
+    Unauthorized,
+    /// The credentials belong to another user than the path's
+    Forbidden,
+}
+
+/// The authenticated user, checked against the `{userId}` of the path if any
+pub fn authorize(
+    data: &AppStateData,
+    req: &HttpRequest,
+    user_id: Option<Uuid>,
+) -> Result<Uuid, AuthError> {
+    match (authenticate(data, req), user_id) {
+        (None, _) => Err(AuthError::Unauthorized),
+        (Some(authenticated), Some(user_id)) if authenticated != user_id => {
+            Err(AuthError::Forbidden)
+        }
+        (Some(authenticated), _) => Ok(authenticated),
+    }
+}
+
+/// 0 or 1; `infinity`, the default, is served as 1
+pub fn depth(req: &HttpRequest) -> u8 {
+    match req
+        .headers()
+        .get("Depth")
+        .and_then(|value| value.to_str().ok())
+    {
+        Some("0") => 0,
+        _ => 1,
+    }
+}
+
+/// Whether the request's If-Match and If-None-Match headers hold for a
+/// resource with `current` ETag, `None` if it doesn't exist
+pub fn preconditions_hold(req: &HttpRequest, current: Option<&str>) -> bool {
+    let matches = |name: header::HeaderName| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .any(|tag| current.is_some_and(|current| tag == "*" || tag == current))
+            })
+    };
+    matches(header::IF_MATCH) != Some(false) && matches(header::IF_NONE_MATCH) != Some(true)
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn prop(ns: &str, name: &str) -> PropName {
+    (ns.to_string(), name.to_string())
+}
+
+fn href(path: &str) -> String {
+    format!("<d:href>{}</d:href>", escape(path))
+}
+
+impl Resource<'_> {
+    pub fn href(&self) -> String {
+        match self {
+            Resource::Root(_) => "/caldav/".to_string(),
+            Resource::Home(user) => home_href(user.id),
+            Resource::Calendar(user) => calendar_href(user.id),
+            Resource::Task(user, task) => task_href(user.id, task.id),
+        }
+    }
+
+    /// Properties returned for `allprop` or an empty PROPFIND body
+    pub fn all_props(&self) -> Vec<PropName> {
+        let mut props = vec![prop(DAV_NS, "resourcetype"), prop(DAV_NS, "displayname")];
+        match self {
+            Resource::Root(_) | Resource::Home(_) => {
+                props.push(prop(DAV_NS, "current-user-principal"));
+                props.push(prop(CALDAV_NS, "calendar-home-set"));
+            }
+            Resource::Calendar(_) => {
+                props.push(prop(CALDAV_NS, "supported-calendar-component-set"));
+                props.push(prop(CALENDARSERVER_NS, "getctag"));
+                props.push(prop(DAV_NS, "getetag"));
+            }
+            Resource::Task(..) => {
+                props.push(prop(DAV_NS, "getetag"));
+                props.push(prop(DAV_NS, "getcontenttype"));
+            }
+        }
+        props
+    }
+
+    /// Inner XML of a property, `None` if this resource doesn't have it
+    pub fn prop(&self, (ns, name): &PropName) -> Option<String> {
+        let user = match self {
+            Resource::Root(user)
+            | Resource::Home(user)
+            | Resource::Calendar(user)
+            | Resource::Task(user, _) => user,
+        };
+        let value = match (self, ns.as_str(), name.as_str()) {
+            (Resource::Calendar(_), DAV_NS, "resourcetype") => {
+                "<d:collection/><c:calendar/>".to_string()
+            }
+            (Resource::Task(..), DAV_NS, "resourcetype") => String::new(),
+            (_, DAV_NS, "resourcetype") => "<d:collection/>".to_string(),
+            (Resource::Task(_, task), DAV_NS, "displayname") => escape(&task.title),
+            (_, DAV_NS, "displayname") => {
+                escape(user.display_name.as_deref().unwrap_or(&user.name))
+            }
+            (_, DAV_NS, "current-user-principal") | (_, DAV_NS, "principal-URL") => {
+                href(&home_href(user.id))
+            }
+            (Resource::Root(_) | Resource::Home(_), CALDAV_NS, "calendar-home-set") => {
+                href(&home_href(user.id))
+            }
+            (Resource::Calendar(_), CALDAV_NS, "supported-calendar-component-set") => {
+                "<c:comp name=\"VTODO\"/>".to_string()
+            }
+            (Resource::Calendar(_), CALENDARSERVER_NS, "getctag")
+            | (Resource::Calendar(_), DAV_NS, "getetag") => escape(&ctag(user)),
+            (Resource::Task(_, task), DAV_NS, "getetag") => escape(&etag(task)),
+            (Resource::Task(..), DAV_NS, "getcontenttype") => {
+                "text/calendar; charset=utf-8; component=VTODO".to_string()
+            }
+            (Resource::Task(_, task), CALDAV_NS, "calendar-data") => {
+                escape(&task_calendar(user, task))
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// One `<d:response>` of a multistatus, with a propstat each for found
+    /// and missing properties
+    pub fn response(&self, props: &[PropName]) -> String {
+        let mut found = String::new();
+        let mut missing = String::new();
+        for name in props {
+            match self.prop(name) {
+                Some(value) => found.push_str(&element(name, &value)),
+                None => missing.push_str(&element(name, "")),
+            }
+        }
+        let mut out = format!("<d:response>{}", href(&self.href()));
+        for (props, status) in [(found, "200 OK"), (missing, "404 Not Found")] {
+            if !props.is_empty() {
+                out.push_str(&format!(
+                    "<d:propstat><d:prop>{}</d:prop><d:status>HTTP/1.1 {}</d:status></d:propstat>",
+                    props, status
+                ));
+            }
+        }
+        out.push_str("</d:response>");
+        out
+    }
+}
+
+fn element((ns, name): &PropName, value: &str) -> String {
+    let prefix = match ns.as_str() {
+        DAV_NS => "d",
+        CALDAV_NS => "c",
+        CALENDARSERVER_NS => "cs",
+        _ => {
+            return match value.is_empty() {
+                true => format!("<{} xmlns=\"{}\"/>", name, escape(ns)),
+                false => format!("<{0} xmlns=\"{1}\">{2}</{0}>", name, escape(ns), value),
+            }
+        }
+    };
+    match value.is_empty() {
+        true => format!("<{}:{}/>", prefix, name),
+        false => format!("<{0}:{1}>{2}</{0}:{1}>", prefix, name, value),
+    }
+}
+
+/// A `<d:response>` for an href that doesn't exist
+pub fn missing(path: &str) -> String {
+    format!(
+        "<d:response>{}<d:status>HTTP/1.1 404 Not Found</d:status></d:response>",
+        href(path)
+    )
+}
+
+/// A 207 Multi-Status of `responses`
+pub fn multistatus(responses: &[String]) -> HttpResponse {
+    HttpResponse::build(StatusCode::MULTI_STATUS)
+        .content_type("application/xml; charset=utf-8")
+        .body(format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <d:multistatus xmlns:d=\"{}\" xmlns:c=\"{}\" xmlns:cs=\"{}\">{}</d:multistatus>\n",
+            DAV_NS,
+            CALDAV_NS,
+            CALENDARSERVER_NS,
+            responses.concat()
+        ))
+}
+
+fn prop_names(node: roxmltree::Node) -> Vec<PropName> {
+    node.children()
+        .filter(|child| child.is_element())
+        .map(|child| {
+            let tag = child.tag_name();
+            (
+                tag.namespace().unwrap_or("").to_string(),
+                tag.name().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn find_dav<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.descendants().find(|child| {
+        child.tag_name().namespace() == Some(DAV_NS) && child.tag_name().name() == name
+    })
+}
+
+/// The properties a PROPFIND asks for; `None` for `allprop` or an empty body
+pub fn parse_propfind(body: &str) -> Result<Option<Vec<PropName>>, String> {
+    if body.trim().is_empty() {
+        return Ok(None);
+    }
+    let doc = roxmltree::Document::parse(body).map_err(|err| format!("Invalid XML: {}", err))?;
+    Ok(find_dav(doc.root(), "prop").map(prop_names))
+}
+
+/// A REPORT request against the task calendar
+#[derive(Debug, PartialEq)]
+pub enum Report {
+    /// `calendar-query`; `todos` is false when the filter selects another component
+    Query { props: Vec<PropName>, todos: bool },
+    /// `calendar-multiget` of the listed hrefs
+    Multiget {
+        props: Vec<PropName>,
+        hrefs: Vec<String>,
+    },
+}
+
+pub fn parse_report(body: &str) -> Result<Report, String> {
+    let doc = roxmltree::Document::parse(body).map_err(|err| format!("Invalid XML: {}", err))?;
+    let root = doc.root_element();
+    if root.tag_name().namespace() != Some(CALDAV_NS) {
+        return Err(format!("Unsupported report: {}", root.tag_name().name()));
+    }
+    let props = find_dav(root, "prop").map(prop_names).unwrap_or_default();
+    match root.tag_name().name() {
+        "calendar-query" => {
+            // the component filter nested in the VCALENDAR one, if any
+            let todos = root
+                .descendants()
+                .filter(|node| node.tag_name().name() == "comp-filter")
+                .filter_map(|node| node.attribute("name"))
+                .filter(|name| !name.eq_ignore_ascii_case("VCALENDAR"))
+                .all(|name| name.eq_ignore_ascii_case("VTODO"));
+            Ok(Report::Query { props, todos })
+        }
+        "calendar-multiget" => {
+            let hrefs = root
+                .descendants()
+                .filter(|node| {
+                    node.tag_name().namespace() == Some(DAV_NS) && node.tag_name().name() == "href"
+                })
+                .filter_map(|node| node.text())
+                .map(|text| text.trim().to_string())
+                .collect();
+            Ok(Report::Multiget { props, hrefs })
+        }
+        other => Err(format!("Unsupported report: {}", other)),
+    }
+}
+
+/// The task id of a resource href such as `/caldav/{userId}/tasks/{taskId}.ics`
+pub fn task_id_of(href: &str) -> Option<Uuid> {
+    let name = href.rsplit('/').next()?.strip_suffix(".ics")?;
+    Uuid::parse_str(name).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_requests() {
+        let propfind = r#"<?xml version="1.0"?>
+            <d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+              <d:prop><d:getetag/><cs:getctag/><x:color xmlns:x="urn:example"/></d:prop>
+            </d:propfind>"#;
+        assert_eq!(
+            parse_propfind(propfind).unwrap().unwrap(),
+            vec![
+                prop(DAV_NS, "getetag"),
+                prop(CALENDARSERVER_NS, "getctag"),
+                prop("urn:example", "color")
+            ]
+        );
+        assert_eq!(parse_propfind("").unwrap(), None);
+        let allprop = r#"<propfind xmlns="DAV:"><allprop/></propfind>"#;
+        assert_eq!(parse_propfind(allprop).unwrap(), None);
+
+        let query = r#"<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+              <d:prop><d:getetag/></d:prop>
+              <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT"/></c:comp-filter></c:filter>
+            </c:calendar-query>"#;
+        assert_eq!(
+            parse_report(query).unwrap(),
+            Report::Query {
+                props: vec![prop(DAV_NS, "getetag")],
+                todos: false
+            }
+        );
+
+        let id = Uuid::new_v4();
+        let multiget = format!(
+            r#"<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+              <d:prop><c:calendar-data/></d:prop>
+              <d:href>/caldav/x/tasks/{}.ics</d:href>
+            </c:calendar-multiget>"#,
+            id
+        );
+        match parse_report(&multiget).unwrap() {
+            Report::Multiget { hrefs, .. } => {
+                assert_eq!(
+                    hrefs
+                        .iter()
+                        .map(|href| task_id_of(href))
+                        .collect::<Vec<_>>(),
+                    vec![Some(id)]
+                )
+            }
+            report => panic!("unexpected report: {:?}", report),
+        }
+        assert!(parse_report(r#"<d:sync-collection xmlns:d="DAV:"/>"#).is_err());
+    }
+}
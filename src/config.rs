@@ -0,0 +1,101 @@
+// src/config.rs
+use crate::schema::DATA_FILE;
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_DATA_DIR: &str = ".";
+const DEFAULT_ATTACHMENT_QUOTA: u64 = 100 * 1024 * 1024;
+const DEFAULT_REMINDER_INTERVAL_SECS: u64 = 30;
+const DEFAULT_WEBHOOK_INTERVAL_SECS: u64 = 5;
+const DEFAULT_IDEMPOTENCY_WINDOW_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_SMTP_HOST: &str = "localhost";
+const DEFAULT_SMTP_PORT: u16 = 25;
+const DEFAULT_SMTP_FROM: &str = "task-manager@localhost";
+
+/// Runtime settings, read from the environment at startup
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory holding on-disk state: `data.json` and attachment blobs
+    pub data_dir: PathBuf,
+    /// Maximum number of attachment bytes a single user may store
+    pub attachment_quota: u64,
+    /// How often the reminder scheduler wakes up
+    pub reminder_interval: Duration,
+    /// How often queued webhook deliveries are attempted
+    pub webhook_interval: Duration,
+    /// How long a response is replayed for a repeated `Idempotency-Key`
+    pub idempotency_window: Duration,
+    /// Unauthenticated, plain-text SMTP relay used for email reminders
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_from: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            attachment_quota: DEFAULT_ATTACHMENT_QUOTA,
+            reminder_interval: Duration::from_secs(DEFAULT_REMINDER_INTERVAL_SECS),
+            webhook_interval: Duration::from_secs(DEFAULT_WEBHOOK_INTERVAL_SECS),
+            idempotency_window: Duration::from_secs(DEFAULT_IDEMPOTENCY_WINDOW_SECS),
+            smtp_host: DEFAULT_SMTP_HOST.to_string(),
+            smtp_port: DEFAULT_SMTP_PORT,
+            smtp_from: DEFAULT_SMTP_FROM.to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads `TASK_MANAGER_DATA_DIR`, `TASK_MANAGER_ATTACHMENT_QUOTA`,
+    /// `TASK_MANAGER_{REMINDER,WEBHOOK}_INTERVAL`, `TASK_MANAGER_IDEMPOTENCY_WINDOW` (seconds)
+    /// and `TASK_MANAGER_SMTP_{HOST,PORT,FROM}`,
+    /// falling back to the defaults for unset or unparsable values.
+    pub fn from_env() -> Self {
+        let default = Config::default();
+        Config {
+            data_dir: env::var("TASK_MANAGER_DATA_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(default.data_dir),
+            attachment_quota: env::var("TASK_MANAGER_ATTACHMENT_QUOTA")
+                .ok()
+                .and_then(|quota| quota.parse().ok())
+                .unwrap_or(default.attachment_quota),
+            reminder_interval: env::var("TASK_MANAGER_REMINDER_INTERVAL")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.reminder_interval),
+            webhook_interval: env::var("TASK_MANAGER_WEBHOOK_INTERVAL")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.webhook_interval),
+            idempotency_window: env::var("TASK_MANAGER_IDEMPOTENCY_WINDOW")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.idempotency_window),
+            smtp_host: env::var("TASK_MANAGER_SMTP_HOST").unwrap_or(default.smtp_host),
+            smtp_port: env::var("TASK_MANAGER_SMTP_PORT")
+                .ok()
+                .and_then(|port| port.parse().ok())
+                .unwrap_or(default.smtp_port),
+            smtp_from: env::var("TASK_MANAGER_SMTP_FROM").unwrap_or(default.smtp_from),
+        }
+    }
+
+    /// JSON file holding the application state
+    pub fn data_file(&self) -> PathBuf {
+        self.data_dir.join(DATA_FILE)
+    }
+
+    /// Content-addressed store for attachment blobs
+    pub fn blob_dir(&self) -> PathBuf {
+        self.data_dir.join("blobs")
+    }
+}
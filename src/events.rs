@@ -0,0 +1,146 @@
+// src/events.rs
+use crate::schema::{AppStateData, Task};
+use async_graphql::Enum;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Events kept for clients resuming after a reconnect
+pub const EVENT_LOG_LIMIT: usize = 1000;
+const BUS_CAPACITY: usize = 256;
+
+/// Whose task list an event belongs to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventScope {
+    User(Uuid),
+    Workspace(Uuid),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// A task mutation, numbered in the order it happened
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TaskEvent {
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    pub scope: EventScope,
+    pub kind: ChangeKind,
+    pub task_id: Uuid,
+    /// The task after the change; `None` for deletions
+    pub task: Option<Task>,
+}
+
+/// In-process fan-out of task events to connected clients
+#[derive(Debug, Clone)]
+pub struct EventBus(broadcast::Sender<TaskEvent>);
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus(broadcast::channel(BUS_CAPACITY).0)
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.0.subscribe()
+    }
+}
+
+impl AppStateData {
+    /// Where `task_id` lives, if it still exists
+    pub fn task_scope(&self, task_id: Uuid) -> Option<(EventScope, &Task)> {
+        self.users
+            .values()
+            .find_map(|user| {
+                user.tasks
+                    .get(&task_id)
+                    .map(|task| (EventScope::User(user.id), task))
+            })
+            .or_else(|| {
+                self.workspaces.values().find_map(|workspace| {
+                    workspace
+                        .tasks
+                        .get(&task_id)
+                        .map(|task| (EventScope::Workspace(workspace.id), task))
+                })
+            })
+    }
+
+    /// Records that `task_id` was created or changed and pushes it to listeners.
+    /// Call after the mutation, while still holding the state lock.
+    /// The task's version becomes the new event's id.
+    pub fn publish_task(&mut self, kind: ChangeKind, task_id: Uuid) {
+        let version = self.last_event_id + 1;
+        let now = Utc::now();
+        let Some(task) = self.all_tasks_mut().find(|task| task.id == task_id) else {
+            return;
+        };
+        task.version = version;
+        task.updated_at = now;
+        if let Some((scope, task)) = self.task_scope(task_id) {
+            let task = task.clone();
+            self.publish(scope, kind, task_id, Some(task));
+        }
+    }
+
+    /// The task as it was right after the change that gave it `version`,
+    /// if that event is still in the log
+    pub fn task_at_version(&self, task_id: Uuid, version: u64) -> Option<&Task> {
+        self.event_log
+            .iter()
+            .find(|event| event.id == version && event.task_id == task_id)
+            .and_then(|event| event.task.as_ref())
+    }
+
+    /// Records that `task_id` was removed from `scope`
+    pub fn publish_deleted(&mut self, scope: EventScope, task_id: Uuid) {
+        self.publish(scope, ChangeKind::Deleted, task_id, None);
+    }
+
+    fn publish(&mut self, scope: EventScope, kind: ChangeKind, task_id: Uuid, task: Option<Task>) {
+        self.last_event_id += 1;
+        let event = TaskEvent {
+            id: self.last_event_id,
+            at: Utc::now(),
+            scope,
+            kind,
+            task_id,
+            task,
+        };
+        self.event_log.push_back(event.clone());
+        while self.event_log.len() > EVENT_LOG_LIMIT {
+            self.event_log.pop_front();
+        }
+        // no listeners is fine
+        let _ = self.event_bus.0.send(event);
+    }
+
+    /// Events in `scope` after `last_event_id`, or `None` when some of them
+    /// have already been dropped from the log and the client must start over.
+    pub fn events_since(&self, scope: EventScope, last_event_id: u64) -> Option<Vec<TaskEvent>> {
+        let oldest = self
+            .event_log
+            .front()
+            .map_or(self.last_event_id.saturating_add(1), |event| event.id);
+        // `last_event_id` comes straight from clients, so it may be anything up to u64::MAX
+        if last_event_id < oldest.saturating_sub(1) || last_event_id > self.last_event_id {
+            return None;
+        }
+        Some(
+            self.event_log
+                .iter()
+                .filter(|event| event.id > last_event_id && event.scope == scope)
+                .cloned()
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,765 @@
+// src/graphql.rs
+use crate::{
+    events::{ChangeKind, EventScope, TaskEvent},
+    routes::{
+        task_routes::{
+            change_status, insert_task, list_task::TaskListItem, remove_task, reschedule, NewTask,
+            RescheduleTask, TaskList, UpdateTask,
+        },
+        user_routes::{insert_user, remove_user, NewUser},
+    },
+    schema::{save_data, AppStateData, Status, Task, User},
+    workflow::Workflow,
+    AppState,
+};
+use actix_web::{body::MessageBody, web, HttpResponse};
+use async_graphql::{
+    Context, Error, ErrorExtensions, InputObject, InputValueError, InputValueResult, Object,
+    Result, Scalar, ScalarType, Schema, SimpleObject, Subscription, Value,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use futures_util::{stream, Stream};
+use log::error;
+use std::sync::MutexGuard;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+pub type TaskSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+/// The schema served at `/graphql`, resolving against `app_state`
+pub fn build_schema(app_state: web::Data<AppState>) -> TaskSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(app_state)
+        .finish()
+}
+
+// Workflow states are open-ended, so a status travels as its name like in REST
+#[Scalar(name = "Status")]
+impl ScalarType for Status {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(name) => Ok(Status::from(name)),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+fn app_state<'a>(ctx: &Context<'a>) -> &'a web::Data<AppState> {
+    ctx.data_unchecked()
+}
+
+fn lock(app_state: &AppState) -> Result<MutexGuard<'_, AppStateData>> {
+    app_state.data.lock().map_err(|_| {
+        error!("Failed to acquire lock on the state data");
+        Error::new("Internal Server Error")
+    })
+}
+
+/// The error response the REST route gives, as a GraphQL error with the
+/// HTTP status in its `status` extension
+fn rejected(err: impl Into<HttpResponse>) -> Error {
+    let resp: HttpResponse = err.into();
+    let status = resp.status().as_u16();
+    let message = match resp.into_body().try_into_bytes() {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(_) => String::new(),
+    };
+    Error::new(message).extend_with(|_, extensions| extensions.set("status", status))
+}
+
+fn user_not_found() -> Error {
+    rejected(HttpResponse::NotFound().body("User not found"))
+}
+
+/// A task as `/tasks/list` shows it, with its comment count and overdue flag
+pub struct TaskObject(TaskListItem);
+
+#[Object(name = "Task")]
+impl TaskObject {
+    async fn id(&self) -> Uuid {
+        self.0.task.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.task.title
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.task.description
+    }
+
+    async fn due_date(&self) -> NaiveDate {
+        self.0.task.due_date
+    }
+
+    /// `null` for an all-day task
+    async fn due_time(&self) -> Option<NaiveTime> {
+        self.0.task.due_time
+    }
+
+    /// `null` follows the owner's profile zone
+    async fn timezone(&self) -> Option<&str> {
+        self.0.task.timezone.as_deref()
+    }
+
+    async fn status(&self) -> &Status {
+        &self.0.task.status
+    }
+
+    async fn assignee(&self) -> Option<Uuid> {
+        self.0.task.assignee
+    }
+
+    async fn watchers(&self) -> Vec<Uuid> {
+        let mut watchers: Vec<Uuid> = self.0.task.watchers.iter().copied().collect();
+        watchers.sort();
+        watchers
+    }
+
+    /// Position within its board column, lowest first
+    async fn rank(&self) -> u32 {
+        self.0.task.rank
+    }
+
+    async fn project_id(&self) -> Option<Uuid> {
+        self.0.task.project_id
+    }
+
+    /// `A` (highest) to `Z`
+    async fn priority(&self) -> Option<char> {
+        self.0.task.priority
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.0.task.tags
+    }
+
+    /// Id of the last change event for this task
+    async fn version(&self) -> u64 {
+        self.0.task.version
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.task.updated_at
+    }
+
+    async fn comment_count(&self) -> usize {
+        self.0.comment_count
+    }
+
+    /// Judged in the owner's time zone
+    async fn overdue(&self) -> bool {
+        self.0.overdue
+    }
+}
+
+/// Which tasks to return; every given condition must hold
+#[derive(InputObject, Default)]
+pub struct TaskFilter {
+    /// Any of these statuses
+    status: Option<Vec<Status>>,
+    tag: Option<String>,
+    priority: Option<char>,
+    project_id: Option<Uuid>,
+    /// Due on or after this date
+    due_from: Option<NaiveDate>,
+    /// Due on or before this date
+    due_to: Option<NaiveDate>,
+    overdue: Option<bool>,
+    /// Case-insensitive text in the title or description
+    search: Option<String>,
+}
+
+impl TaskFilter {
+    fn matches(&self, item: &TaskListItem) -> bool {
+        let task = &item.task;
+        let search = self.search.as_deref().map(str::to_lowercase);
+        self.status
+            .as_ref()
+            .is_none_or(|statuses| statuses.contains(&task.status))
+            && self.tag.as_ref().is_none_or(|tag| task.tags.contains(tag))
+            && self
+                .priority
+                .is_none_or(|priority| task.priority == Some(priority))
+            && self.project_id.is_none_or(|id| task.project_id == Some(id))
+            && self.due_from.is_none_or(|from| task.due_date >= from)
+            && self.due_to.is_none_or(|to| task.due_date <= to)
+            && self.overdue.is_none_or(|overdue| item.overdue == overdue)
+            && search.is_none_or(|search| {
+                task.title.to_lowercase().contains(&search)
+                    || task.description.to_lowercase().contains(&search)
+            })
+    }
+}
+
+/// Tasks of a workflow state
+#[derive(SimpleObject)]
+pub struct StatusCount {
+    status: Status,
+    count: usize,
+}
+
+/// A user as of the query, tasks included
+pub struct UserObject {
+    user: User,
+    workflow: Workflow,
+    /// Ordered by due date and time
+    tasks: Vec<TaskListItem>,
+}
+
+impl UserObject {
+    fn new(data: &AppStateData, user: &User) -> Self {
+        let workflow = user.workflow();
+        let mut tasks =
+            TaskList::from_tasks(user.tasks.values(), &data.comments, &workflow, user.tz()).tasks;
+        tasks.sort_by_key(|item| (item.task.due_date, item.task.due_time, item.task.id));
+        UserObject {
+            user: user.clone(),
+            workflow,
+            tasks,
+        }
+    }
+
+    fn filtered(&self, filter: Option<TaskFilter>) -> impl Iterator<Item = &TaskListItem> {
+        let filter = filter.unwrap_or_default();
+        self.tasks.iter().filter(move |item| filter.matches(item))
+    }
+
+    fn task_objects(&self, filter: Option<TaskFilter>) -> Vec<TaskObject> {
+        self.filtered(filter)
+            .map(|item| {
+                TaskObject(TaskListItem {
+                    task: item.task.clone(),
+                    comment_count: item.comment_count,
+                    overdue: item.overdue,
+                })
+            })
+            .collect()
+    }
+}
+
+#[Object(name = "User")]
+impl UserObject {
+    async fn id(&self) -> Uuid {
+        self.user.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.user.name
+    }
+
+    async fn email(&self) -> Option<&str> {
+        self.user.email.as_deref()
+    }
+
+    async fn display_name(&self) -> Option<&str> {
+        self.user.display_name.as_deref()
+    }
+
+    async fn timezone(&self) -> Option<&str> {
+        self.user.timezone.as_deref()
+    }
+
+    /// The user's tasks by due date
+    async fn tasks(&self, filter: Option<TaskFilter>) -> Vec<TaskObject> {
+        self.task_objects(filter)
+    }
+
+    async fn task_count(&self, filter: Option<TaskFilter>) -> usize {
+        self.filtered(filter).count()
+    }
+
+    /// Tasks per state, in workflow order. States that left the workflow
+    /// but still hold tasks come last.
+    async fn status_counts(&self) -> Vec<StatusCount> {
+        let mut statuses: Vec<Status> = self
+            .workflow
+            .states
+            .iter()
+            .map(|state| state.status.clone())
+            .collect();
+        for item in &self.tasks {
+            if !statuses.contains(&item.task.status) {
+                statuses.push(item.task.status.clone());
+            }
+        }
+        statuses
+            .into_iter()
+            .map(|status| StatusCount {
+                count: self
+                    .tasks
+                    .iter()
+                    .filter(|item| item.task.status == status)
+                    .count(),
+                status,
+            })
+            .collect()
+    }
+}
+
+/// `task_id` of `user_id` as the API shows it
+fn find_task(data: &AppStateData, user_id: Uuid, task_id: Uuid) -> Option<TaskObject> {
+    let user = data.users.get(&user_id)?;
+    let task = user.tasks.get(&task_id)?;
+    Some(task_object(data, user, task))
+}
+
+fn task_object(data: &AppStateData, user: &User, task: &Task) -> TaskObject {
+    let tasks = TaskList::from_tasks(
+        [task].into_iter(),
+        &data.comments,
+        &user.workflow(),
+        user.tz(),
+    );
+    TaskObject(
+        tasks
+            .tasks
+            .into_iter()
+            .next()
+            .expect("one task in, one out"),
+    )
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Every user, by name
+    async fn users(&self, ctx: &Context<'_>) -> Result<Vec<UserObject>> {
+        let data = lock(app_state(ctx))?;
+        let mut users: Vec<UserObject> = data
+            .users
+            .values()
+            .map(|user| UserObject::new(&data, user))
+            .collect();
+        users.sort_by(|a, b| a.user.name.cmp(&b.user.name));
+        Ok(users)
+    }
+
+    async fn user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<UserObject>> {
+        let data = lock(app_state(ctx))?;
+        Ok(data.users.get(&id).map(|user| UserObject::new(&data, user)))
+    }
+
+    /// A user's tasks by due date
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        filter: Option<TaskFilter>,
+    ) -> Result<Vec<TaskObject>> {
+        let data = lock(app_state(ctx))?;
+        let user = data.users.get(&user_id).ok_or_else(user_not_found)?;
+        Ok(UserObject::new(&data, user).task_objects(filter))
+    }
+
+    async fn task(&self, ctx: &Context<'_>, user_id: Uuid, id: Uuid) -> Result<Option<TaskObject>> {
+        let data = lock(app_state(ctx))?;
+        if !data.users.contains_key(&user_id) {
+            return Err(user_not_found());
+        }
+        Ok(find_task(&data, user_id, id))
+    }
+}
+
+#[derive(InputObject)]
+pub struct NewUserInput {
+    /// Client-chosen id; generated when omitted
+    id: Option<Uuid>,
+    name: String,
+    email: Option<String>,
+    display_name: Option<String>,
+    timezone: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct NewTaskInput {
+    /// Client-chosen id; generated when omitted
+    id: Option<Uuid>,
+    title: String,
+    #[graphql(default)]
+    description: String,
+    due_date: NaiveDate,
+    /// Omit for an all-day task
+    due_time: Option<NaiveTime>,
+    /// Defaults to the user's profile zone
+    timezone: Option<String>,
+    project_id: Option<Uuid>,
+    /// `A` (highest) to `Z`
+    priority: Option<char>,
+    #[graphql(default)]
+    tags: Vec<String>,
+}
+
+/// Mutations run the same code as their REST routes and fail with the same
+/// messages, the HTTP status going into the error's `status` extension.
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Like `POST /users/create`
+    async fn create_user(&self, ctx: &Context<'_>, input: NewUserInput) -> Result<UserObject> {
+        let mut data = lock(app_state(ctx))?;
+        let req = NewUser {
+            id: input.id,
+            name: input.name,
+            email: input.email,
+            display_name: input.display_name,
+            timezone: input.timezone,
+        };
+        let user_id = insert_user(&mut data, &req).map_err(rejected)?;
+        save_data(&data);
+        let user = &data.users[&user_id];
+        Ok(UserObject::new(&data, user))
+    }
+
+    /// Like `DELETE /users/delete`; returns the id of the deleted user
+    async fn delete_user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Uuid> {
+        let app_state = app_state(ctx);
+        let mut data = lock(app_state)?;
+        if !remove_user(&mut data, &app_state.config.blob_dir(), id) {
+            return Err(user_not_found());
+        }
+        save_data(&data);
+        Ok(id)
+    }
+
+    /// Like `POST /users/{userId}/tasks/create`
+    async fn create_task(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        input: NewTaskInput,
+    ) -> Result<TaskObject> {
+        let mut data = lock(app_state(ctx))?;
+        let req = NewTask {
+            id: input.id,
+            title: input.title,
+            description: input.description,
+            due_date: input.due_date,
+            due_time: input.due_time,
+            timezone: input.timezone,
+            // new tasks start in the first state of the user's workflow
+            status: Status::ToDo,
+            project_id: input.project_id,
+            priority: input.priority,
+            tags: input.tags,
+        };
+        let task_id = insert_task(&mut data, user_id, &req).map_err(rejected)?;
+        save_data(&data);
+        find_task(&data, user_id, task_id).ok_or_else(|| Error::new("Task vanished"))
+    }
+
+    /// Like `PUT /users/{userId}/tasks/update`: moves a task to another status
+    async fn update_task(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        id: Uuid,
+        status: Status,
+    ) -> Result<TaskObject> {
+        let mut data = lock(app_state(ctx))?;
+        let task_id =
+            change_status(&mut data, user_id, &UpdateTask { id, status }).map_err(rejected)?;
+        save_data(&data);
+        find_task(&data, user_id, task_id).ok_or_else(|| Error::new("Task vanished"))
+    }
+
+    /// Like `PUT /users/{userId}/tasks/reschedule`
+    async fn reschedule_task(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        id: Uuid,
+        due_date: NaiveDate,
+        due_time: Option<NaiveTime>,
+        timezone: Option<String>,
+    ) -> Result<TaskObject> {
+        let mut data = lock(app_state(ctx))?;
+        let req = RescheduleTask {
+            id,
+            due_date,
+            due_time,
+            timezone,
+        };
+        let task_id = reschedule(&mut data, user_id, req).map_err(rejected)?;
+        save_data(&data);
+        find_task(&data, user_id, task_id).ok_or_else(|| Error::new("Task vanished"))
+    }
+
+    /// Like `DELETE /users/{userId}/tasks/delete`; returns the id of the deleted task
+    async fn delete_task(&self, ctx: &Context<'_>, user_id: Uuid, id: Uuid) -> Result<Uuid> {
+        let app_state = app_state(ctx);
+        let mut data = lock(app_state)?;
+        remove_task(&mut data, &app_state.config.blob_dir(), user_id, id).map_err(rejected)?;
+        save_data(&data);
+        Ok(id)
+    }
+}
+
+/// A change to one of the user's tasks
+#[derive(SimpleObject)]
+pub struct TaskChange {
+    /// Id of the change event, as in `/tasks/changes`
+    event_id: u64,
+    at: DateTime<Utc>,
+    kind: ChangeKind,
+    task_id: Uuid,
+    /// The task after the change; `null` for deletions
+    task: Option<TaskObject>,
+}
+
+impl TaskChange {
+    fn new(app_state: &AppState, user_id: Uuid, event: TaskEvent) -> Self {
+        let task = event.task.and_then(|task| {
+            let data = app_state.data.lock().ok()?;
+            let user = data.users.get(&user_id)?;
+            Some(task_object(&data, user, &task))
+        });
+        TaskChange {
+            event_id: event.id,
+            at: event.at,
+            kind: event.kind,
+            task_id: event.task_id,
+            task,
+        }
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Changes to a user's tasks from now on, made through either API
+    async fn task_changes(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+    ) -> Result<impl Stream<Item = TaskChange>> {
+        let app_state = app_state(ctx).clone();
+        let events = {
+            let data = lock(&app_state)?;
+            if !data.users.contains_key(&user_id) {
+                return Err(user_not_found());
+            }
+            data.event_bus.subscribe()
+        };
+        let scope = EventScope::User(user_id);
+        Ok(stream::unfold(events, move |mut events| {
+            let app_state = app_state.clone();
+            async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) if event.scope == scope => {
+                            return Some((TaskChange::new(&app_state, user_id, event), events));
+                        }
+                        Ok(_) => {}
+                        // Too slow to keep up; the client resubscribes and refetches
+                        Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use async_graphql::{Request, Variables};
+    use serde_json::{json, Value as Json};
+
+    /// The response as it would go over the wire
+    async fn run(schema: &TaskSchema, query: &str, variables: Json) -> Json {
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        serde_json::to_value(schema.execute(request).await).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_user_with_filtered_tasks_and_counts() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let schema = build_schema(app_state.clone());
+
+        let created = run(
+            &schema,
+            r#"mutation($userId: UUID!) {
+                createTask(userId: $userId, input: {
+                    title: "Write report", dueDate: "2999-01-01", tags: ["work"], priority: "A"
+                }) { id status overdue }
+            }"#,
+            json!({ "userId": user_id }),
+        )
+        .await;
+        let task = &created["data"]["createTask"];
+        assert_eq!(task["status"], "ToDo");
+        assert_eq!(task["overdue"], false);
+        let task_id = task["id"].clone();
+
+        let updated = run(
+            &schema,
+            r#"mutation($userId: UUID!, $id: UUID!) {
+                updateTask(userId: $userId, id: $id, status: "InProgress") { status }
+            }"#,
+            json!({ "userId": user_id, "id": task_id }),
+        )
+        .await;
+        assert_eq!(updated["data"]["updateTask"]["status"], "InProgress");
+
+        // one round trip for the user, some of their tasks and counts
+        let resp = run(
+            &schema,
+            r#"query($id: UUID!) {
+                user(id: $id) {
+                    name
+                    work: tasks(filter: { tag: "work" }) { title status priority }
+                    overdue: taskCount(filter: { overdue: true })
+                    all: taskCount
+                    statusCounts { status count }
+                }
+            }"#,
+            json!({ "id": user_id }),
+        )
+        .await;
+        assert_eq!(
+            resp["data"]["user"],
+            json!({
+                "name": "test-user",
+                "work": [{ "title": "Write report", "status": "InProgress", "priority": "A" }],
+                "overdue": 1,
+                "all": 2,
+                "statusCounts": [
+                    { "status": "ToDo", "count": 1 },
+                    { "status": "InProgress", "count": 1 },
+                    { "status": "Done", "count": 0 },
+                ],
+            })
+        );
+
+        let resp = run(
+            &schema,
+            r#"query($userId: UUID!) {
+                tasks(userId: $userId, filter: { status: ["ToDo"], search: "SAMPLE" }) { id }
+            }"#,
+            json!({ "userId": user_id }),
+        )
+        .await;
+        assert_eq!(resp["data"]["tasks"], json!([{ "id": test_task_id }]));
+
+        let resp = run(
+            &schema,
+            r#"query($id: UUID!) { user(id: $id) { name } }"#,
+            json!({ "id": Uuid::nil() }),
+        )
+        .await;
+        assert_eq!(resp["data"]["user"], Json::Null);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_mutations_follow_rest_rules() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let schema = build_schema(app_state.clone());
+        let variables = json!({ "userId": user_id, "id": test_task_id });
+
+        // failures carry the REST message and status
+        let cases = [
+            (
+                r#"mutation($userId: UUID!, $id: UUID!) {
+                    updateTask(userId: $userId, id: $id, status: "Blocked") { id }
+                }"#,
+                "Unknown status: Blocked",
+                400,
+            ),
+            (
+                r#"mutation($userId: UUID!, $id: UUID!) {
+                    rescheduleTask(userId: $userId, id: $id, dueDate: "2024-07-01", timezone: "Mars/Olympus_Mons") { id }
+                }"#,
+                "Unknown time zone: Mars/Olympus_Mons",
+                400,
+            ),
+            (
+                r#"mutation($userId: UUID!) {
+                    deleteTask(userId: $userId, id: "00000000-0000-0000-0000-000000000001")
+                }"#,
+                "Task doesn't exist",
+                404,
+            ),
+            (
+                r#"mutation { createUser(input: { name: "  " }) { id } }"#,
+                "Username can't be empty",
+                400,
+            ),
+        ];
+        for (query, message, status) in cases {
+            let resp = run(&schema, query, variables.clone()).await;
+            assert_eq!(resp["errors"][0]["message"], message, "{}", query);
+            assert_eq!(
+                resp["errors"][0]["extensions"]["status"], status,
+                "{}",
+                query
+            );
+        }
+
+        let resp = run(
+            &schema,
+            r#"mutation($userId: UUID!, $id: UUID!) {
+                rescheduleTask(userId: $userId, id: $id, dueDate: "2024-07-01", dueTime: "17:00:00", timezone: "Europe/Berlin") {
+                    dueDate dueTime timezone
+                }
+            }"#,
+            variables.clone(),
+        )
+        .await;
+        assert_eq!(
+            resp["data"]["rescheduleTask"],
+            json!({ "dueDate": "2024-07-01", "dueTime": "17:00:00", "timezone": "Europe/Berlin" })
+        );
+
+        let resp = run(
+            &schema,
+            r#"mutation($userId: UUID!, $id: UUID!) { deleteTask(userId: $userId, id: $id) }"#,
+            variables.clone(),
+        )
+        .await;
+        assert_eq!(resp["data"]["deleteTask"], json!(test_task_id));
+        if let Ok(state_data) = app_state.data.lock() {
+            assert!(state_data.users[&user_id].tasks.is_empty());
+        };
+
+        let name = format!("graphql-user-{}", Uuid::new_v4());
+        let resp = run(
+            &schema,
+            r#"mutation($name: String!) {
+                createUser(input: { name: $name, timezone: "Europe/Berlin" }) { id name timezone taskCount }
+            }"#,
+            json!({ "name": name }),
+        )
+        .await;
+        let created = &resp["data"]["createUser"];
+        assert_eq!(created["name"], json!(name));
+        assert_eq!(created["taskCount"], 0);
+        let resp = run(
+            &schema,
+            r#"mutation($id: UUID!) { deleteUser(id: $id) }"#,
+            json!({ "id": created["id"] }),
+        )
+        .await;
+        assert_eq!(resp["data"]["deleteUser"], created["id"]);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,369 @@
+// src/ical.rs
+use crate::{
+    schema::{parse_timezone, Status, Task},
+    transfer::Rows,
+    workflow::Workflow,
+};
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+const PRODID: &str = "-//task-manager//Tasks//EN";
+/// Content lines are folded after this many octets (RFC 5545 §3.1)
+const FOLD_AT: usize = 75;
+
+/// One `NAME;PARAM=VALUE:value` content line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The properties of one component such as a VTODO
+pub type Component = Vec<Property>;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Appends `line` to `out`, folded and CRLF-terminated
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > FOLD_AT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// RFC 5545 STATUS of a VTODO for `status`
+pub fn status_to_ical(status: &Status, workflow: &Workflow) -> &'static str {
+    if workflow.is_terminal(status) {
+        "COMPLETED"
+    } else if workflow
+        .states
+        .first()
+        .is_some_and(|state| state.status == *status)
+    {
+        "NEEDS-ACTION"
+    } else {
+        "IN-PROCESS"
+    }
+}
+
+/// The workflow state closest to a VTODO STATUS
+pub fn status_from_ical(value: &str, workflow: &Workflow) -> Option<Status> {
+    let initial = workflow.states.first().map(|state| state.status.clone());
+    match value.to_ascii_uppercase().as_str() {
+        "NEEDS-ACTION" => initial,
+        "IN-PROCESS" => workflow
+            .states
+            .iter()
+            .skip(1)
+            .find(|state| !state.terminal)
+            .map(|state| state.status.clone())
+            .or(initial),
+        "COMPLETED" | "CANCELLED" => workflow
+            .states
+            .iter()
+            .find(|state| state.terminal)
+            .map(|state| state.status.clone()),
+        _ => None,
+    }
+}
+
+/// A VCALENDAR with one VTODO per task and, with `events`, an all-day VEVENT
+/// on each due date. Timed tasks are due in their own zone or `default_tz`.
+pub fn render_calendar(
+    name: &str,
+    tasks: &[Task],
+    workflow: &Workflow,
+    default_tz: Tz,
+    events: bool,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for task in tasks {
+        lines.extend(todo_lines(task, workflow, default_tz));
+        if events {
+            lines.extend(event_lines(task));
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        push_line(&mut out, &line);
+    }
+    out
+}
+
+fn todo_lines(task: &Task, workflow: &Workflow, default_tz: Tz) -> Vec<String> {
+    let due = match task.due_time {
+        None => format!("DUE;VALUE=DATE:{}", format_date(task.due_date)),
+        Some(time) => {
+            let tz = task
+                .timezone
+                .as_deref()
+                .and_then(parse_timezone)
+                .unwrap_or(default_tz);
+            let local = task.due_date.and_time(time).format("%Y%m%dT%H%M%S");
+            if tz == Tz::UTC {
+                format!("DUE:{}Z", local)
+            } else {
+                format!("DUE;TZID={}:{}", tz.name(), local)
+            }
+        }
+    };
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", task.id),
+        format!("DTSTAMP:{}", format_utc(task.updated_at)),
+        format!("LAST-MODIFIED:{}", format_utc(task.updated_at)),
+        format!("SUMMARY:{}", escape(&task.title)),
+    ];
+    if !task.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&task.description)));
+    }
+    lines.push(due);
+    lines.push(format!("STATUS:{}", status_to_ical(&task.status, workflow)));
+    lines.push(format!("SEQUENCE:{}", task.version));
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+fn event_lines(task: &Task) -> Vec<String> {
+    let end = task
+        .due_date
+        .checked_add_days(Days::new(1))
+        .unwrap_or(task.due_date);
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-due", task.id),
+        format!("DTSTAMP:{}", format_utc(task.updated_at)),
+        format!("SUMMARY:{}", escape(&task.title)),
+        format!("DTSTART;VALUE=DATE:{}", format_date(task.due_date)),
+        format!("DTEND;VALUE=DATE:{}", format_date(end)),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+/// Unfolded content lines of an iCalendar document
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_line(line: &str) -> Option<Property> {
+    // the value starts at the first colon outside a quoted parameter
+    let mut quoted = false;
+    let split = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            quoted = !quoted;
+        }
+        *c == ':' && !quoted
+    })?;
+    let (head, value) = (&line[..split.0], &line[split.0 + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Every component named `kind` (e.g. `VTODO`) in `text`, at any depth
+pub fn parse_components(text: &str, kind: &str) -> Result<Vec<Component>, String> {
+    let lines = unfold(text);
+    if !lines
+        .first()
+        .is_some_and(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err("Not an iCalendar document".to_string());
+    }
+    let mut components = Vec::new();
+    let mut current: Option<Component> = None;
+    let mut depth = 0;
+    for line in &lines {
+        let Some(property) = parse_line(line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" if property.value.eq_ignore_ascii_case(kind) && current.is_none() => {
+                current = Some(Vec::new());
+                depth = 0;
+            }
+            "BEGIN" if current.is_some() => depth += 1,
+            "END" if current.is_some() && depth > 0 => depth -= 1,
+            "END" if property.value.eq_ignore_ascii_case(kind) => {
+                components.extend(current.take());
+            }
+            _ => {
+                // properties of nested components such as VALARM are skipped
+                if let (Some(component), 0) = (current.as_mut(), depth) {
+                    component.push(property);
+                }
+            }
+        }
+    }
+    Ok(components)
+}
+
+fn find<'a>(component: &'a Component, name: &str) -> Option<&'a Property> {
+    component.iter().find(|property| property.name == name)
+}
+
+/// VTODOs as import rows for `transfer::import_row`. UIDs that are UUIDs
+/// become task ids; DUE becomes the due date, time and zone.
+pub fn todo_rows(text: &str, workflow: &Workflow) -> Result<Rows, String> {
+    let todos = parse_components(text, "VTODO")?;
+    Ok(todos
+        .iter()
+        .map(|todo| {
+            let mut fields = Map::new();
+            if let Some(uid) = find(todo, "UID").and_then(|uid| Uuid::parse_str(&uid.value).ok()) {
+                fields.insert("id".into(), Value::String(uid.to_string()));
+            }
+            if let Some(summary) = find(todo, "SUMMARY") {
+                fields.insert("title".into(), Value::String(unescape(&summary.value)));
+            }
+            if let Some(description) = find(todo, "DESCRIPTION") {
+                fields.insert(
+                    "description".into(),
+                    Value::String(unescape(&description.value)),
+                );
+            }
+            if let Some(status) = find(todo, "STATUS") {
+                let status = status_from_ical(&status.value, workflow)
+                    .ok_or_else(|| format!("Unknown STATUS: {}", status.value))?;
+                fields.insert("status".into(), Value::String(status.to_string()));
+            }
+            if let Some(due) = find(todo, "DUE") {
+                let value = due.value.trim();
+                if due.param("VALUE") == Some("DATE") || value.len() == 8 {
+                    let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+                        .map_err(|_| format!("Invalid DUE: {}", value))?;
+                    fields.insert("due_date".into(), Value::String(date.to_string()));
+                } else {
+                    let (local, utc) = match value.strip_suffix('Z') {
+                        Some(local) => (local, true),
+                        None => (value, false),
+                    };
+                    let time = NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S")
+                        .map_err(|_| format!("Invalid DUE: {}", value))?;
+                    fields.insert("due_date".into(), Value::String(time.date().to_string()));
+                    fields.insert("due_time".into(), Value::String(time.time().to_string()));
+                    let timezone = if utc { Some("UTC") } else { due.param("TZID") };
+                    if let Some(timezone) = timezone {
+                        fields.insert("timezone".into(), Value::String(timezone.to_string()));
+                    }
+                }
+            }
+            Ok(fields)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn test_round_trip() {
+        let workflow = Workflow::default();
+        let mut task = Task::new(
+            "Call, then; write \\ back",
+            &"long line ".repeat(20),
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        );
+        task.status = Status::Done;
+        task.due_time = NaiveTime::from_hms_opt(17, 30, 0);
+        task.timezone = Some("Europe/Berlin".to_string());
+
+        let ics = render_calendar("Tasks", &[task.clone()], &workflow, Tz::UTC, true);
+        assert!(ics.split("\r\n").all(|line| line.len() <= FOLD_AT));
+        assert!(ics.contains("DUE;TZID=Europe/Berlin:20240301T173000\r\n"));
+        assert!(ics.contains("STATUS:COMPLETED\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240301\r\n"));
+
+        let rows = todo_rows(&ics, &workflow).unwrap();
+        assert_eq!(rows.len(), 1);
+        let row = rows[0].as_ref().unwrap();
+        assert_eq!(row["id"], task.id.to_string());
+        assert_eq!(row["title"], task.title);
+        assert_eq!(row["description"], task.description);
+        assert_eq!(row["status"], "Done");
+        assert_eq!(row["due_date"], "2024-03-01");
+        assert_eq!(row["due_time"], "17:30:00");
+        assert_eq!(row["timezone"], "Europe/Berlin");
+    }
+}
@@ -0,0 +1,517 @@
+// src/idempotency.rs
+use crate::AppState;
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::{header, Method},
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures_util::StreamExt;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+/// Set on responses served from the idempotency cache
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+const MAX_KEY_LEN: usize = 255;
+/// Largest keyed body read into memory for the fingerprint
+const MAX_BODY_LEN: usize = 1024 * 1024;
+/// Response headers not worth replaying: set per response or by the server
+const SKIPPED_HEADERS: [header::HeaderName; 5] = [
+    header::CONTENT_TYPE,
+    header::CONTENT_LENGTH,
+    header::DATE,
+    header::CONNECTION,
+    header::TRANSFER_ENCODING,
+];
+
+/// A response kept for replay under its `Idempotency-Key`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StoredResponse {
+    /// Hash of the method, path and body of the first request
+    pub fingerprint: String,
+    pub status: u16,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Other response headers, e.g. `ETag` or `Location`
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What to do with an incoming keyed request
+#[derive(Debug, PartialEq)]
+pub enum Lookup {
+    /// First use of the key: handle it, then `store` or `release` the key
+    Miss,
+    Replay(StoredResponse),
+    /// The key was used for a different request
+    Mismatch,
+    /// The first request with this key hasn't finished yet
+    InFlight,
+}
+
+/// Keys are scoped to the first two path segments, the user or the resource
+/// the route belongs to, so clients of different users can't collide
+pub fn scoped_key(path: &str, key: &str) -> String {
+    let end = path
+        .match_indices('/')
+        .nth(2)
+        .map_or(path.len(), |(index, _)| index);
+    format!("{} {}", &path[..end], key)
+}
+
+/// `hex(sha256(method, path and query, body))`
+pub fn fingerprint(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(path);
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+impl crate::schema::AppStateData {
+    /// Looks `key` up, dropping entries older than `window`. A miss reserves
+    /// the key until `store_idempotent` or `release_idempotent` is called.
+    pub fn lookup_idempotent(
+        &mut self,
+        key: &str,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+        window: TimeDelta,
+    ) -> Lookup {
+        self.idempotency_keys
+            .retain(|_, stored| now - stored.created_at < window);
+        if let Some(stored) = self.idempotency_keys.get(key) {
+            return if stored.fingerprint == fingerprint {
+                Lookup::Replay(stored.clone())
+            } else {
+                Lookup::Mismatch
+            };
+        }
+        if !self.idempotency_in_flight.insert(key.to_string()) {
+            return Lookup::InFlight;
+        }
+        Lookup::Miss
+    }
+
+    pub fn store_idempotent(&mut self, key: &str, response: StoredResponse) {
+        self.idempotency_in_flight.remove(key);
+        self.idempotency_keys.insert(key.to_string(), response);
+    }
+
+    /// Frees a reserved key without caching, so the request may be retried
+    pub fn release_idempotent(&mut self, key: &str) {
+        self.idempotency_in_flight.remove(key);
+    }
+}
+
+fn replay(stored: StoredResponse) -> HttpResponse {
+    let status = actix_web::http::StatusCode::from_u16(stored.status)
+        .unwrap_or(actix_web::http::StatusCode::OK);
+    let mut builder = HttpResponse::build(status);
+    builder.insert_header((REPLAYED_HEADER, "true"));
+    if let Some(content_type) = stored.content_type {
+        builder.insert_header((header::CONTENT_TYPE, content_type));
+    }
+    for (name, value) in stored.headers {
+        builder.append_header((name, value));
+    }
+    builder.body(stored.body)
+}
+
+/// A key reserved by `lookup_idempotent`. Unless a response gets stored under
+/// it, dropping the reservation frees the key again, so a failed or abandoned
+/// request doesn't leave it in flight forever.
+struct Reservation {
+    state: web::Data<AppState>,
+    key: String,
+    stored: bool,
+}
+
+impl Reservation {
+    /// Keeps the response in memory; it reaches data.json with the next save,
+    /// rather than rewriting the file for every keyed request
+    fn store(mut self, response: StoredResponse) {
+        if let Ok(mut data) = self.state.data.lock() {
+            data.store_idempotent(&self.key, response);
+            self.stored = true;
+        }
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if self.stored {
+            return;
+        }
+        if let Ok(mut data) = self.state.data.lock() {
+            data.release_idempotent(&self.key);
+        }
+    }
+}
+
+/// Middleware making POST, PUT and DELETE requests that carry an `Idempotency-Key`
+/// safe to retry. The first response (unless it is a 5xx) is kept and replayed
+/// for the same key and request until the configured window passes; reusing the key
+/// for a different request of the same scope answers 422. Multipart uploads pass through untouched,
+/// other keyed bodies over `MAX_BODY_LEN` answer 413.
+pub async fn idempotency(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let mutating = matches!(*req.method(), Method::POST | Method::PUT | Method::DELETE);
+    let key = req
+        .headers()
+        .get(IDEMPOTENCY_HEADER)
+        .map(|key| key.to_str().map(str::to_string));
+    let state = req.app_data::<web::Data<AppState>>().cloned();
+    // Uploads can be far larger than what's sensible to buffer for a fingerprint
+    let multipart = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/"));
+    let (key, state) = match (key, state) {
+        (Some(Ok(key)), Some(state)) if mutating && !multipart => (key, state),
+        (Some(Err(_)), _) if mutating => {
+            let res = HttpResponse::BadRequest().body("Invalid Idempotency-Key");
+            return Ok(req.into_response(res));
+        }
+        _ => {
+            return next
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_boxed_body)
+        }
+    };
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        let res = HttpResponse::BadRequest().body(format!(
+            "Idempotency-Key must be 1 to {} characters",
+            MAX_KEY_LEN
+        ));
+        return Ok(req.into_response(res));
+    }
+
+    // The body is part of the fingerprint, so read it and hand it back afterwards
+    let too_large = || {
+        HttpResponse::PayloadTooLarge().body(format!(
+            "Requests with an Idempotency-Key are limited to {} bytes",
+            MAX_BODY_LEN
+        ))
+    };
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared.is_some_and(|len| len > MAX_BODY_LEN) {
+        return Ok(req.into_response(too_large()));
+    }
+    let mut payload = req.take_payload();
+    let mut bytes = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        bytes.extend_from_slice(&chunk?);
+        if bytes.len() > MAX_BODY_LEN {
+            return Ok(req.into_response(too_large()));
+        }
+    }
+    let bytes = bytes.freeze();
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or(req.path(), |path| path.as_str())
+        .to_string();
+    let fingerprint = fingerprint(req.method(), &path, &bytes);
+    req.set_payload(Payload::from(bytes));
+    let key = scoped_key(req.path(), &key);
+
+    let window = TimeDelta::from_std(state.config.idempotency_window).unwrap_or(TimeDelta::MAX);
+    let lookup = match state.data.lock() {
+        Ok(mut data) => data.lookup_idempotent(&key, &fingerprint, Utc::now(), window),
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            let res = HttpResponse::InternalServerError().body("Internal Server Error");
+            return Ok(req.into_response(res));
+        }
+    };
+    match lookup {
+        Lookup::Miss => {}
+        Lookup::Replay(stored) => return Ok(req.into_response(replay(stored))),
+        Lookup::Mismatch => {
+            warn!("Idempotency-Key {} reused for a different request", key);
+            let res = HttpResponse::UnprocessableEntity()
+                .body("Idempotency-Key was already used for a different request");
+            return Ok(req.into_response(res));
+        }
+        Lookup::InFlight => {
+            let res = HttpResponse::Conflict()
+                .body("A request with this Idempotency-Key is still in progress");
+            return Ok(req.into_response(res));
+        }
+    }
+
+    let reservation = Reservation {
+        state,
+        key,
+        stored: false,
+    };
+    let res = next.call(req).await?;
+    let status = res.status();
+    let content_type = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let headers = res
+        .headers()
+        .iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let (req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let body = match body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(_) => {
+            error!(
+                "Failed to read the response for Idempotency-Key {}",
+                reservation.key
+            );
+            let res = HttpResponse::InternalServerError().body("Internal Server Error");
+            return Ok(ServiceResponse::new(req, res));
+        }
+    };
+
+    // Server errors are worth retrying, and only text bodies are kept
+    if let Ok(text) = std::str::from_utf8(&body) {
+        if !status.is_server_error() {
+            reservation.store(StoredResponse {
+                fingerprint,
+                status: status.as_u16(),
+                content_type,
+                headers,
+                body: text.to_string(),
+                created_at: Utc::now(),
+            });
+        }
+    }
+    Ok(ServiceResponse::new(
+        req,
+        res.set_body(body).map_into_boxed_body(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{
+        create_test_user_and_task, init_app_state, init_app_state_with, temp_config,
+    };
+    use crate::schema::save_data;
+    use crate::{create_task, NewTask, Status};
+    use actix_web::{dev::Service, http::StatusCode, middleware::from_fn, test, App};
+    use chrono::NaiveDate;
+    use futures_util::FutureExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_idempotency() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let (other_id, _) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .wrap(from_fn(idempotency))
+                .route("/users/{user_id}/tasks/create", web::post().to(create_task)),
+        )
+        .await;
+
+        let new_task = |title: &str| NewTask {
+            id: None,
+            title: title.to_string(),
+            description: "retried".to_string(),
+            due_date: NaiveDate::from_ymd_opt(2000, 1, 2).unwrap(),
+            due_time: None,
+            timezone: None,
+            status: Status::ToDo,
+            project_id: None,
+            priority: None,
+            tags: Vec::new(),
+        };
+        let key = Uuid::new_v4().to_string();
+        let create_for = |user_id: Uuid, task: &NewTask, key: Option<&str>| {
+            let mut req = test::TestRequest::post()
+                .uri(&format!("/users/{}/tasks/create", user_id))
+                .set_json(task);
+            if let Some(key) = key {
+                req = req.insert_header((IDEMPOTENCY_HEADER, key));
+            }
+            req.to_request()
+        };
+        let create = |task: &NewTask, key: Option<&str>| create_for(user_id, task, key);
+
+        let resp = test::call_service(&app, create(&new_task("once"), Some(&key))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+        let first: Uuid = test::read_body_json(resp).await;
+
+        // a retry gets the original answer and creates nothing
+        let resp = test::call_service(&app, create(&new_task("once"), Some(&key))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        let replayed: Uuid = test::read_body_json(resp).await;
+        assert_eq!(replayed, first);
+
+        let resp = test::call_service(&app, create(&new_task("other"), Some(&key))).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // another user's client may pick the same key
+        let resp =
+            test::call_service(&app, create_for(other_id, &new_task("other"), Some(&key))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+
+        // requests without a key are handled as before
+        let resp = test::call_service(&app, create(&new_task("once"), None)).await;
+        let unkeyed: Uuid = test::read_body_json(resp).await;
+        assert_ne!(unkeyed, first);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert_eq!(state_data.users[&user_id].tasks.len(), 3);
+            assert_eq!(state_data.users[&other_id].tasks.len(), 2);
+            assert!(state_data.idempotency_in_flight.is_empty());
+
+            // entries expire after the window
+            let scoped = scoped_key(&format!("/users/{}/tasks/create", user_id), &key);
+            assert!(state_data.idempotency_keys.contains_key(&scoped));
+            let later = Utc::now() + TimeDelta::days(2);
+            let lookup = state_data.lookup_idempotent(&scoped, "other", later, TimeDelta::days(1));
+            assert_eq!(lookup, Lookup::Miss);
+            state_data.release_idempotent(&scoped);
+
+            state_data.users.remove(&user_id);
+            state_data.users.remove(&other_id);
+            save_data(&state_data);
+        };
+    }
+
+    /// Stands in for a handler answering with an `ETag`, e.g. a CalDAV PUT
+    async fn put_with_etag(calls: web::Data<AtomicUsize>) -> HttpResponse {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        HttpResponse::Created()
+            .insert_header((header::ETAG, format!("\"v{}\"", call)))
+            .finish()
+    }
+
+    #[actix_web::test]
+    async fn test_replay_keeps_headers() {
+        let app_state = init_app_state_with(temp_config());
+        let calls = web::Data::new(AtomicUsize::new(0));
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .app_data(calls.clone())
+                .wrap(from_fn(idempotency))
+                .route("/etag", web::put().to(put_with_etag)),
+        )
+        .await;
+
+        let key = Uuid::new_v4().to_string();
+        let put = || {
+            test::TestRequest::put()
+                .uri("/etag")
+                .insert_header((IDEMPOTENCY_HEADER, key.as_str()))
+                .set_payload("BEGIN:VCALENDAR")
+                .to_request()
+        };
+        let resp = test::call_service(&app, put()).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), "\"v0\"");
+
+        let resp = test::call_service(&app, put()).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), "\"v0\"");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_abandoned_request_releases_key() {
+        let app_state = init_app_state_with(temp_config());
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .wrap(from_fn(idempotency))
+                .route(
+                    "/slow",
+                    web::post().to(std::future::pending::<HttpResponse>),
+                ),
+        )
+        .await;
+
+        // the client goes away while the handler is still running
+        let req = test::TestRequest::post()
+            .uri("/slow")
+            .insert_header((IDEMPOTENCY_HEADER, "abandoned"))
+            .to_request();
+        assert!(app.call(req).now_or_never().is_none());
+
+        if let Ok(state_data) = app_state.data.lock() {
+            assert!(state_data.idempotency_in_flight.is_empty());
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_body_limit() {
+        let app_state = init_app_state_with(temp_config());
+        let calls = web::Data::new(AtomicUsize::new(0));
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .app_data(calls.clone())
+                .wrap(from_fn(idempotency))
+                .route("/etag", web::put().to(put_with_etag)),
+        )
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri("/etag")
+            .insert_header((IDEMPOTENCY_HEADER, "too-large"))
+            .set_payload(vec![b'x'; MAX_BODY_LEN + 1])
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // multipart uploads are neither buffered nor replayed
+        for _ in 0..2 {
+            let req = test::TestRequest::put()
+                .uri("/etag")
+                .insert_header((IDEMPOTENCY_HEADER, "upload"))
+                .insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=b"))
+                .set_payload(vec![b'x'; MAX_BODY_LEN + 1])
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        if let Ok(state_data) = app_state.data.lock() {
+            assert!(state_data.idempotency_keys.is_empty());
+            assert!(state_data.idempotency_in_flight.is_empty());
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+}
@@ -1,35 +1,82 @@
 // src/lib.rs
+pub mod blobs;
+pub mod board;
+pub mod caldav;
+pub mod config;
+pub mod events;
+pub mod graphql;
+pub mod ical;
+pub mod idempotency;
+pub mod openapi;
+pub mod quickadd;
+pub mod reminders;
 pub mod routes;
 pub mod schema;
+pub mod scheduler;
 pub mod server;
+pub mod sync;
+pub mod todotxt;
+pub mod transfer;
+pub mod webhooks;
+pub mod workflow;
 
+pub use routes::attachment_routes::*;
+pub use routes::board_routes::*;
+pub use routes::caldav_routes::*;
+pub use routes::calendar_routes::*;
+pub use routes::comment_routes::*;
+pub use routes::event_routes::*;
+pub use routes::graphql_routes::*;
+pub use routes::project_routes::*;
+pub use routes::reminder_routes::*;
+pub use routes::sync_routes::*;
 pub use routes::task_routes::*;
+pub use routes::transfer_routes::*;
 pub use routes::user_routes::*;
+pub use routes::webhook_routes::*;
+pub use routes::workflow_routes::*;
+pub use routes::workspace_routes::*;
+pub use config::Config;
 pub use schema::*;
 pub use server::*;
+pub use workflow::*;
 
 
 // AppState
 use std::sync::Mutex;
 pub struct AppState {
     pub data: Mutex<AppStateData>,
+    pub config: Config,
 }
 
 #[cfg(test)]
 pub mod test_utils {
-    use super::{load_data, save_data, User, Task};
-    use crate::AppState;
+    use super::{load_data_from, save_data, User, Task};
+    use crate::{AppState, Config};
     use actix_web::web;
     use chrono::NaiveDate;
     use std::sync::Mutex;
     use uuid::Uuid;
 
     pub fn init_app_state() -> web::Data<AppState> {
+        init_app_state_with(Config::default())
+    }
+
+    pub fn init_app_state_with(config: Config) -> web::Data<AppState> {
         web::Data::new(AppState {
-            data: Mutex::new(load_data()),
+            data: Mutex::new(load_data_from(&config.data_file())),
+            config,
         })
     }
 
+    /// Config rooted in a fresh directory under the system temp dir
+    pub fn temp_config() -> Config {
+        Config {
+            data_dir: std::env::temp_dir().join(format!("task-manager-test-{}", Uuid::new_v4())),
+            ..Config::default()
+        }
+    }
+
     pub fn create_test_user_and_task(app_state: &web::Data<AppState>) -> (Uuid, Uuid) {
         // Create a test user and a sample task
         let mut test_user = User::new("test-user");
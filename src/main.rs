@@ -1,5 +1,5 @@
 // module imports
-use task_manager::{load_data, start_service, AppState};
+use task_manager::{load_data_from, start_service, AppState, Config};
 
 // extern crate imports
 use actix_web::web;
@@ -12,8 +12,10 @@ async fn main() -> anyhow::Result<()> {
     env_logger::init_from_env(Env::default().default_filter_or("info"));
 
     // load data
+    let config = Config::from_env();
     let app_state: web::Data<_> = web::Data::new(AppState {
-        data: Mutex::new(load_data()),
+        data: Mutex::new(load_data_from(&config.data_file())),
+        config,
     });
 
     let _ = start_service(app_state)
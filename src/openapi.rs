@@ -0,0 +1,444 @@
+// src/openapi.rs
+use crate::routes::{
+    attachment_routes, board_routes, calendar_routes, comment_routes, event_routes, project_routes,
+    reminder_routes, sync_routes, task_routes, transfer_routes, user_routes, webhook_routes,
+    workflow_routes, workspace_routes,
+};
+use utoipa::openapi::{path::Operation, OpenApi as Document};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+/// The OpenAPI document of the REST API. Schemas come from the request and
+/// response types, operations from the `#[utoipa::path]` on each handler;
+/// the tests below keep both in line with the routes in `server.rs`.
+/// CalDAV is left out, as OpenAPI can't describe WebDAV methods.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Task Manager API"),
+    paths(
+        calendar_routes::task_feed::task_feed,
+        user_routes::create_user::create_user,
+        user_routes::delete_user::delete_user,
+        user_routes::list_users::list_users,
+        user_routes::get_user::get_user,
+        user_routes::rename_user::rename_user,
+        user_routes::update_user::update_user,
+        workflow_routes::get_workflow::get_workflow,
+        workflow_routes::set_workflow::set_workflow,
+        board_routes::get_board::get_board,
+        board_routes::move_task::move_task,
+        event_routes::list_changes::list_changes,
+        sync_routes::sync_tasks::sync_tasks,
+        calendar_routes::create_feed_token::create_feed_token,
+        calendar_routes::revoke_feed_token::revoke_feed_token,
+        calendar_routes::import_ics::import_ics,
+        calendar_routes::create_caldav_token::create_caldav_token,
+        calendar_routes::revoke_caldav_token::revoke_caldav_token,
+        task_routes::create_task::create_task,
+        task_routes::quick_add::quick_add,
+        task_routes::bulk_tasks::bulk_tasks,
+        transfer_routes::export_tasks::export_tasks,
+        transfer_routes::import_tasks::import_tasks,
+        task_routes::list_task::list_task,
+        task_routes::get_task::get_task,
+        task_routes::update_task::update_task,
+        task_routes::delete_task::delete_task,
+        task_routes::reschedule_task::reschedule_task,
+        task_routes::agenda::agenda,
+        event_routes::task_socket::task_socket,
+        event_routes::task_stream::task_stream,
+        task_routes::assign_task::assign_task,
+        task_routes::unassign_task::unassign_task,
+        task_routes::watch_task::watch_task,
+        task_routes::unwatch_task::unwatch_task,
+        task_routes::list_assigned::list_assigned,
+        comment_routes::create_comment::create_comment,
+        comment_routes::list_comments::list_comments,
+        comment_routes::edit_comment::edit_comment,
+        comment_routes::delete_comment::delete_comment,
+        reminder_routes::create_reminder::create_reminder,
+        reminder_routes::list_reminders::list_reminders,
+        reminder_routes::delete_reminder::delete_reminder,
+        attachment_routes::upload_attachment::upload_attachment,
+        attachment_routes::delete_attachment::delete_attachment,
+        attachment_routes::download_attachment::download_attachment,
+        project_routes::create_project::create_project,
+        project_routes::list_projects::list_projects,
+        project_routes::update_project::update_project,
+        project_routes::delete_project::delete_project,
+        project_routes::move_to_project::move_to_project,
+        project_routes::get_project::get_project,
+        project_routes::project_summary::project_summary,
+        webhook_routes::create_webhook::create_webhook,
+        webhook_routes::list_webhooks::list_webhooks,
+        webhook_routes::delete_webhook::delete_webhook,
+        webhook_routes::list_dead_letters::list_dead_letters,
+        webhook_routes::retry_dead_letter::retry_dead_letter,
+        webhook_routes::list_deliveries::list_deliveries,
+        workspace_routes::create_workspace::create_workspace,
+        workspace_routes::list_workspaces::list_workspaces,
+        workspace_routes::migrate_tasks::migrate_tasks,
+        event_routes::workspace_socket::workspace_socket,
+        workflow_routes::get_workspace_workflow::get_workspace_workflow,
+        workflow_routes::set_workspace_workflow::set_workspace_workflow,
+        board_routes::get_workspace_board::get_workspace_board,
+        board_routes::move_workspace_task::move_workspace_task,
+        workspace_routes::add_member::add_member,
+        workspace_routes::remove_member::remove_member,
+        workspace_routes::create_workspace_task::create_workspace_task,
+        workspace_routes::list_workspace_tasks::list_workspace_tasks,
+        workspace_routes::update_workspace_task::update_workspace_task,
+        workspace_routes::delete_workspace_task::delete_workspace_task,
+    ),
+    modifiers(&Summaries),
+    tags(
+        (name = "users"),
+        (name = "tasks", description = "A user's personal tasks"),
+        (name = "comments"),
+        (name = "reminders"),
+        (name = "attachments"),
+        (name = "projects", description = "Groups of a user's personal tasks"),
+        (name = "workflows", description = "The states a task may take and the moves between them"),
+        (name = "boards", description = "Tasks as kanban columns, one per workflow state"),
+        (name = "events", description = "Task changes as they happen"),
+        (name = "sync", description = "Offline clients catching up"),
+        (name = "transfer", description = "CSV, JSON, todo.txt and Markdown export and import"),
+        (name = "calendar", description = "iCalendar feed and import, CalDAV access"),
+        (name = "webhooks"),
+        (name = "workspaces", description = "Task lists shared between users"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Turns the handlers' doc comments, `API endpoint to ...` followed by a
+/// `URL:` line, into a summary and description
+struct Summaries;
+
+impl Modify for Summaries {
+    fn modify(&self, openapi: &mut Document) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                summarize(operation);
+            }
+        }
+    }
+}
+
+fn summarize(operation: &mut Operation) {
+    let Some(docs) = operation.summary.take() else {
+        return;
+    };
+    let text = docs
+        .lines()
+        .take_while(|line| !line.starts_with("URL:"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = text.strip_prefix("API endpoint ").unwrap_or(&text);
+    let text = text.strip_prefix("to ").unwrap_or(text);
+    let mut chars = text.chars();
+    let text: String = chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default();
+    match text.split_once(". ") {
+        Some((summary, description)) => {
+            operation.summary = Some(summary.to_string());
+            operation.description = Some(description.to_string());
+        }
+        None => operation.summary = Some(text.trim_end_matches('.').to_string()),
+    }
+}
+
+/// Serves the document at `/openapi.json` and Swagger UI for it at `/docs/`
+pub fn docs() -> SwaggerUi {
+    SwaggerUi::new("/docs/{_:.*}").url("/openapi.json", ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::{
+        task_routes::{DeleteTask, GetTask, NewTask, TaskList, UpdateTask},
+        user_routes::{DeleteUser, NewUser},
+    };
+    use crate::schema::{Status, Task};
+    use crate::workflow::Workflow;
+    use actix_web::{http::StatusCode, test::TestRequest, App};
+    use chrono::NaiveDate;
+    use chrono_tz::Tz;
+    use serde::Serialize;
+    use serde_json::Value;
+    use std::collections::{BTreeSet, HashMap};
+    use uuid::Uuid;
+
+    /// Route prefixes the document leaves out on purpose; GraphQL describes
+    /// itself through introspection
+    const UNDOCUMENTED: [&str; 3] = ["/.well-known", "/caldav", "/graphql"];
+
+    /// `(method, path)` of each route `server.rs` registers, scope prefixes included.
+    /// Panics on a `.route(` it can't read, so a route can't slip past the check.
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        let source = include_str!("server.rs");
+        let mut routes = BTreeSet::new();
+        // open scopes, with the bracket depth of the `.service(` they sit in
+        let mut scopes: Vec<(usize, &str)> = Vec::new();
+        let mut depth = 0;
+        for (i, c) in source.char_indices() {
+            let rest = &source[i..];
+            if let Some(rest) = rest.strip_prefix("web::scope(") {
+                let prefix = rest
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.split_once('"'))
+                    .map(|(prefix, _)| prefix)
+                    .unwrap_or_else(|| panic!("unreadable scope: {}", line_at(rest)));
+                scopes.push((depth, prefix));
+            } else if let Some(rest) = rest.strip_prefix(".route(") {
+                let (path, args) = rest
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.split_once('"'))
+                    .unwrap_or_else(|| panic!("unreadable route: {}", line_at(rest)));
+                let path = format!(
+                    "{}{}",
+                    scopes.iter().map(|(_, prefix)| *prefix).collect::<String>(),
+                    path
+                );
+                if UNDOCUMENTED.iter().any(|prefix| path.starts_with(prefix)) {
+                    continue;
+                }
+                let method = args
+                    .trim_start_matches([',', ' ', '\n'])
+                    .strip_prefix("web::")
+                    .and_then(|rest| rest.split_once("()"))
+                    .map(|(method, _)| method)
+                    .filter(|method| ["get", "post", "put", "delete"].contains(method))
+                    .unwrap_or_else(|| panic!("unreadable method for {}: {}", path, line_at(args)));
+                routes.insert((method.to_string(), path));
+            }
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    scopes.retain(|(open, _)| *open <= depth);
+                }
+                _ => {}
+            }
+        }
+        routes
+    }
+
+    fn line_at(source: &str) -> &str {
+        source.lines().next().unwrap_or_default()
+    }
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    #[test]
+    fn test_spec_covers_routes() {
+        let registered = registered_routes();
+        assert!(registered.contains(&(
+            "post".to_string(),
+            "/users/{userId}/tasks/create".to_string()
+        )));
+
+        let spec = spec();
+        let mut documented = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                documented.insert((method.clone(), path.clone()));
+            }
+        }
+        let missing: Vec<_> = registered.difference(&documented).collect();
+        assert!(
+            missing.is_empty(),
+            "routes without #[utoipa::path]: {:?}",
+            missing
+        );
+        let stale: Vec<_> = documented.difference(&registered).collect();
+        assert!(stale.is_empty(), "documented but not routed: {:?}", stale);
+    }
+
+    #[test]
+    fn test_spec_status_codes() {
+        let spec = spec();
+        let documented = |method: &str, path: &str| -> BTreeSet<String> {
+            spec["paths"][path][method]["responses"]
+                .as_object()
+                .unwrap_or_else(|| panic!("{} {} is not documented", method, path))
+                .keys()
+                .cloned()
+                .collect()
+        };
+        // what the handlers answer, success and errors alike
+        let expected = [
+            (
+                "put",
+                "/users/{userId}/workflow",
+                &["200", "400", "404", "409"][..],
+            ),
+            (
+                "put",
+                "/users/{userId}/workspaces/{workspaceId}/workflow",
+                &["200", "400", "403", "404", "409"],
+            ),
+            (
+                "post",
+                "/users/{userId}/workspaces/migrate",
+                &["200", "404", "409"],
+            ),
+        ];
+        for (method, path, codes) in expected {
+            let codes: BTreeSet<String> = codes.iter().map(|code| code.to_string()).collect();
+            assert_eq!(documented(method, path), codes, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn test_spec_path_parameters() {
+        let spec = spec();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            let segments: BTreeSet<&str> = path
+                .split('{')
+                .skip(1)
+                .filter_map(|rest| rest.split_once('}'))
+                .map(|(name, _)| name)
+                .collect();
+            for (method, operation) in item.as_object().unwrap() {
+                let params: BTreeSet<&str> = operation["parameters"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|param| param["in"] == "path")
+                    .map(|param| param["name"].as_str().unwrap())
+                    .collect();
+                assert_eq!(params, segments, "{} {}", method, path);
+            }
+        }
+    }
+
+    /// Property names of a component schema, following `$ref` and `allOf`
+    fn properties<'a>(spec: &'a Value, schema: &'a Value) -> BTreeSet<&'a str> {
+        if let Some(name) = schema["$ref"].as_str() {
+            let name = name.trim_start_matches("#/components/schemas/");
+            return properties(spec, &spec["components"]["schemas"][name]);
+        }
+        let mut names: BTreeSet<&str> = schema["properties"]
+            .as_object()
+            .into_iter()
+            .flat_map(|properties| properties.keys().map(String::as_str))
+            .collect();
+        for part in schema["allOf"].as_array().into_iter().flatten() {
+            names.extend(properties(spec, part));
+        }
+        names
+    }
+
+    /// The schema named `name` describes exactly the fields `value` serializes to
+    fn assert_schema(spec: &Value, name: &str, value: impl Serialize) {
+        let value = serde_json::to_value(value).unwrap();
+        let fields: BTreeSet<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let schema = &spec["components"]["schemas"][name];
+        assert_eq!(properties(spec, schema), fields, "{}", name);
+        for required in schema["required"].as_array().into_iter().flatten() {
+            assert!(fields.contains(required.as_str().unwrap()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_spec_schemas_match_serde() {
+        let spec = spec();
+        let due_date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let task = Task::new("title", "description", due_date);
+        let id = task.id;
+
+        assert_schema(&spec, "Task", &task);
+        assert_schema(
+            &spec,
+            "NewTask",
+            NewTask {
+                id: None,
+                title: "title".to_string(),
+                description: String::new(),
+                due_date,
+                due_time: None,
+                timezone: None,
+                status: Status::ToDo,
+                project_id: None,
+                priority: None,
+                tags: Vec::new(),
+            },
+        );
+        assert_schema(
+            &spec,
+            "UpdateTask",
+            UpdateTask {
+                id,
+                status: Status::Done,
+            },
+        );
+        assert_schema(&spec, "GetTask", GetTask { id });
+        assert_schema(&spec, "DeleteTask", DeleteTask { id });
+        let list = TaskList::from_tasks(
+            [&task].into_iter(),
+            &HashMap::new(),
+            &Workflow::default(),
+            Tz::UTC,
+        );
+        assert_schema(&spec, "TaskList", &list);
+        assert_schema(&spec, "TaskListItem", &list.tasks[0]);
+        assert_schema(
+            &spec,
+            "NewUser",
+            NewUser {
+                id: None,
+                name: "name".to_string(),
+                email: None,
+                display_name: None,
+                timezone: None,
+            },
+        );
+        assert_schema(&spec, "DeleteUser", DeleteUser { id: Uuid::now_v7() });
+
+        // statuses are plain strings, custom workflow states included
+        assert_eq!(spec["components"]["schemas"]["Status"]["type"], "string");
+        assert_eq!(
+            serde_json::to_value(Status::Custom("Review".to_string())).unwrap(),
+            "Review"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_docs() {
+        let app = actix_web::test::init_service(App::new().service(docs())).await;
+
+        let req = TestRequest::get().uri("/openapi.json").to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let served: Value = actix_web::test::read_body_json(resp).await;
+        assert_eq!(served, spec());
+        let create = &served["paths"]["/users/{userId}/tasks/create"]["post"];
+        assert_eq!(create["summary"], "Create new task");
+        assert_eq!(
+            create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/NewTask"
+        );
+
+        let req = TestRequest::get().uri("/docs/").to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&page).contains("swagger"));
+    }
+}
@@ -0,0 +1,288 @@
+// src/quickadd.rs
+use crate::{routes::task_routes::NewTask, schema::Status};
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Weekday};
+
+/// The word lowercased, without trailing punctuation
+fn bare(word: &str) -> String {
+    word.trim_end_matches([',', '.', ';', ':', '!', '?'])
+        .to_lowercase()
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" => Weekday::Tue,
+        "wednesday" | "wed" => Weekday::Wed,
+        "thursday" | "thu" | "thur" | "thurs" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" | "sat" => Weekday::Sat,
+        "sunday" | "sun" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    MONTHS
+        .iter()
+        .position(|name| word.len() >= 3 && (*name == word || name[..3] == *word))
+        .or_else(|| (word == "sept").then_some(8))
+        .map(|index| index as u32 + 1)
+}
+
+/// `5`, `5th`, `21st`
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !["", "st", "nd", "rd", "th"].contains(&suffix) {
+        return None;
+    }
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+/// `3`, `a`, `an`
+fn amount(word: &str) -> Option<u32> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        _ => word.parse().ok(),
+    }
+}
+
+/// The first `weekday` after `today`; a week ahead when it's today
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Days::new(if ahead == 0 { 7 } else { u64::from(ahead) })
+}
+
+/// The next `month`/`day` from `today` on, this year or next
+fn next_date(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    match date < today {
+        true => NaiveDate::from_ymd_opt(today.year() + 1, month, day),
+        false => Some(date),
+    }
+}
+
+/// A date phrase starting at `words[i]`, with the number of words it spans
+fn date_at(words: &[&str], i: usize, today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let word = |k: usize| words.get(i + k).map(|word| bare(word)).unwrap_or_default();
+    let first = word(0);
+    match first.as_str() {
+        "today" => return Some((1, today)),
+        "tomorrow" | "tmrw" => return Some((1, today + Days::new(1))),
+        "on" | "by" | "due" => {
+            return date_at(words, i + 1, today).map(|(count, date)| (count + 1, date))
+        }
+        "next" | "this" => {
+            return match word(1).as_str() {
+                "week" if first == "next" => Some((2, today + Days::new(7))),
+                "month" if first == "next" => Some((2, today.checked_add_months(Months::new(1))?)),
+                other => weekday(other).map(|weekday| (2, next_weekday(today, weekday))),
+            }
+        }
+        "in" => {
+            let count = amount(&word(1))?;
+            let date = match word(2).as_str() {
+                "day" | "days" => today.checked_add_days(Days::new(count.into()))?,
+                "week" | "weeks" => today.checked_add_days(Days::new(u64::from(count) * 7))?,
+                "month" | "months" => today.checked_add_months(Months::new(count))?,
+                _ => return None,
+            };
+            return Some((3, date));
+        }
+        _ => {}
+    }
+    if let Some(weekday) = weekday(&first) {
+        return Some((1, next_weekday(today, weekday)));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&first, "%Y-%m-%d") {
+        return Some((1, date));
+    }
+    // `mar 5` or `5 mar`
+    if let (Some(month), Some(day)) = (month(&first), day_of_month(&word(1))) {
+        return Some((2, next_date(today, month, day)?));
+    }
+    let (day, month) = (day_of_month(&first)?, month(&word(1))?);
+    Some((2, next_date(today, month, day)?))
+}
+
+/// `5pm`, `9:30am`, `17:30`, `noon`; a bare hour only after `at`
+fn time_of_day(word: &str, after_at: bool) -> Option<NaiveTime> {
+    if word == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+    let (clock, offset) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(0)),
+        (_, Some(clock)) => (clock, Some(12)),
+        _ => (word, None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None if offset.is_some() || after_at => (clock.parse().ok()?, 0),
+        None => return None,
+    };
+    let hour: u32 = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn time_at(words: &[&str], i: usize) -> Option<(usize, NaiveTime)> {
+    let first = bare(words[i]);
+    match (first.as_str(), words.get(i + 1)) {
+        ("at", Some(next)) => time_of_day(&bare(next), true).map(|time| (2, time)),
+        _ => time_of_day(&first, false).map(|time| (1, time)),
+    }
+}
+
+/// `!high`, `!medium`, `!low`, `!1` to `!3`, or a letter `!a` to `!z`
+fn priority(word: &str) -> Option<char> {
+    match word.to_lowercase().as_str() {
+        "high" | "1" => Some('A'),
+        "medium" | "med" | "2" => Some('B'),
+        "low" | "3" => Some('C'),
+        letter if letter.len() == 1 => letter
+            .chars()
+            .next()
+            .filter(char::is_ascii_alphabetic)
+            .map(|letter| letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// Parses quick-add text such as `Ship release notes next friday #docs !high`.
+/// The first date and time phrases found become the due date and time, counted
+/// from `today`; without a date the task is due today. `#tag`s and a `!priority`
+/// are taken out, the remaining words form the title.
+pub fn parse(text: &str, today: NaiveDate) -> Result<NewTask, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut title = Vec::new();
+    let mut task = NewTask {
+        id: None,
+        title: String::new(),
+        description: String::new(),
+        due_date: today,
+        due_time: None,
+        timezone: None,
+        status: Status::ToDo,
+        project_id: None,
+        priority: None,
+        tags: Vec::new(),
+    };
+    let mut dated = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        if !dated {
+            if let Some((count, date)) = date_at(&words, i, today) {
+                task.due_date = date;
+                dated = true;
+                i += count;
+                continue;
+            }
+        }
+        if task.due_time.is_none() {
+            if let Some((count, time)) = time_at(&words, i) {
+                task.due_time = Some(time);
+                i += count;
+                continue;
+            }
+        }
+        let tag = word
+            .strip_prefix('#')
+            .map(|tag| tag.trim_end_matches([',', '.', ';', ':', '!', '?']))
+            // `#123` is more likely an issue number
+            .filter(|tag| !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit()));
+        if let Some(tag) = tag {
+            if !task.tags.iter().any(|known| known == tag) {
+                task.tags.push(tag.to_string());
+            }
+        } else if let Some(priority) = word
+            .strip_prefix('!')
+            .and_then(priority)
+            .filter(|_| task.priority.is_none())
+        {
+            task.priority = Some(priority);
+        } else {
+            title.push(word);
+        }
+        i += 1;
+    }
+
+    task.title = title.join(" ");
+    if task.title.is_empty() {
+        return Err("Title can't be empty".to_string());
+    }
+    Ok(task)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        // a Wednesday
+        let today = date(2024, 3, 6);
+
+        let task = parse("Ship release notes next friday #docs !high", today).unwrap();
+        assert_eq!(task.title, "Ship release notes");
+        assert_eq!(task.due_date, date(2024, 3, 8));
+        assert_eq!(task.due_time, None);
+        assert_eq!(task.tags, vec!["docs"]);
+        assert_eq!(task.priority, Some('A'));
+
+        let cases = [
+            ("Buy milk", date(2024, 3, 6)),
+            ("Buy milk tomorrow", date(2024, 3, 7)),
+            ("Standup wednesday", date(2024, 3, 13)),
+            ("Call mom on Sun", date(2024, 3, 10)),
+            ("Renew passport in 3 weeks", date(2024, 3, 27)),
+            ("Pay rent in a month", date(2024, 4, 6)),
+            ("Plan offsite next week", date(2024, 3, 13)),
+            ("Dentist mar 5th", date(2025, 3, 5)),
+            ("Taxes by 15 April", date(2024, 4, 15)),
+            ("Launch 2024-12-01", date(2024, 12, 1)),
+        ];
+        for (text, due_date) in cases {
+            let task = parse(text, today).unwrap();
+            assert_eq!(task.due_date, due_date, "{}", text);
+            assert!(!task.title.is_empty(), "{}", text);
+        }
+
+        let task = parse("Review PR #123 at 5pm friday !b #code, #code", today).unwrap();
+        assert_eq!(task.title, "Review PR #123");
+        assert_eq!(task.due_time, NaiveTime::from_hms_opt(17, 0, 0));
+        assert_eq!(task.due_date, date(2024, 3, 8));
+        assert_eq!(task.priority, Some('B'));
+        assert_eq!(task.tags, vec!["code"]);
+
+        // only the first date counts, numbers without a clock stay in the title
+        let task = parse("Buy 3 eggs today for sunday brunch at 9:30", today).unwrap();
+        assert_eq!(task.title, "Buy 3 eggs for sunday brunch");
+        assert_eq!(task.due_time, NaiveTime::from_hms_opt(9, 30, 0));
+
+        assert!(parse("tomorrow #home !low", today).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+// src/reminders.rs
+use crate::events::EventScope;
+use crate::schema::{AppStateData, Task};
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Failed deliveries are given up after this many attempts
+pub const MAX_ATTEMPTS: u32 = 5;
+const RETRY_BASE_SECS: i64 = 30;
+
+/// When a reminder goes off
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderTrigger {
+    /// At a fixed instant
+    At(DateTime<Utc>),
+    /// This many minutes before the task falls due; follows reschedules
+    BeforeDue(u32),
+}
+
+/// Where a reminder is delivered
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Channel {
+    /// Written to the server log
+    Log,
+    /// POSTed as JSON
+    Webhook { url: String },
+    /// Mailed through the configured SMTP relay
+    Email { to: String },
+}
+
+impl Channel {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Channel::Log => Ok(()),
+            Channel::Webhook { url } => {
+                if url.starts_with("http://") || url.starts_with("https://") {
+                    Ok(())
+                } else {
+                    Err(format!("Webhook url must be http(s): {}", url))
+                }
+            }
+            Channel::Email { to } => {
+                if crate::schema::is_valid_email(to) {
+                    Ok(())
+                } else {
+                    Err(format!("Invalid email: {}", to))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Reminder {
+    pub id: Uuid,
+    pub trigger: ReminderTrigger,
+    pub channel: Channel,
+}
+
+impl Reminder {
+    pub fn new(trigger: ReminderTrigger, channel: Channel) -> Self {
+        Reminder {
+            id: Uuid::new_v4(),
+            trigger,
+            channel,
+        }
+    }
+
+    /// The instant this reminder goes off for `task`
+    pub fn fire_at(&self, task: &Task, default_tz: Tz) -> DateTime<Utc> {
+        match &self.trigger {
+            ReminderTrigger::At(at) => *at,
+            ReminderTrigger::BeforeDue(minutes) => {
+                task.due_at(default_tz) - TimeDelta::minutes(i64::from(*minutes))
+            }
+        }
+    }
+}
+
+/// A reminder that has gone off and still awaits delivery
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReminderJob {
+    pub id: Uuid,
+    /// Owner of the task; for workspace tasks, the workspace's owner
+    pub user_id: Uuid,
+    /// Set for workspace tasks; with `user_id`, the scope the task was found in
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    pub task_id: Uuid,
+    pub reminder_id: Uuid,
+    pub channel: Channel,
+    pub title: String,
+    pub due_at: DateTime<Utc>,
+    pub fire_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub next_attempt: DateTime<Utc>,
+}
+
+impl ReminderJob {
+    pub fn message(&self) -> String {
+        format!(
+            "Reminder: \"{}\" is due at {}",
+            self.title,
+            self.due_at.to_rfc3339()
+        )
+    }
+}
+
+/// Queues a job for every reminder whose time has come. Each reminder fires once
+/// per fire time, so a restart or a second tick never duplicates it; rescheduling
+/// the task arms it again. Finished tasks are skipped. Workspace tasks follow
+/// the workspace's workflow and their owner's time zone.
+pub fn enqueue_due(data: &mut AppStateData, now: DateTime<Utc>) -> usize {
+    let personal = data
+        .users
+        .values()
+        .map(|user| (user.id, None, user.tz(), user.workflow(), &user.tasks));
+    let shared = data.workspaces.values().filter_map(|workspace| {
+        let owner = data.users.get(&workspace.owner()?)?;
+        Some((
+            owner.id,
+            Some(workspace.id),
+            owner.tz(),
+            workspace.workflow(),
+            &workspace.tasks,
+        ))
+    });
+
+    let mut jobs = Vec::new();
+    let mut live = std::collections::HashSet::new();
+    for (user_id, workspace_id, tz, workflow, tasks) in personal.chain(shared) {
+        for task in tasks.values() {
+            for reminder in &task.reminders {
+                live.insert(reminder.id);
+                let fire_at = reminder.fire_at(task, tz);
+                if fire_at > now
+                    || workflow.is_terminal(&task.status)
+                    || data.reminders_fired.get(&reminder.id) == Some(&fire_at)
+                {
+                    continue;
+                }
+                jobs.push(ReminderJob {
+                    id: Uuid::new_v4(),
+                    user_id,
+                    workspace_id,
+                    task_id: task.id,
+                    reminder_id: reminder.id,
+                    channel: reminder.channel.clone(),
+                    title: task.title.clone(),
+                    due_at: task.due_at(tz),
+                    fire_at,
+                    attempts: 0,
+                    next_attempt: now,
+                });
+            }
+        }
+    }
+    data.reminders_fired
+        .retain(|reminder_id, _| live.contains(reminder_id));
+
+    let queued = jobs.len();
+    for job in jobs {
+        data.reminders_fired.insert(job.reminder_id, job.fire_at);
+        data.reminder_jobs.push(job);
+    }
+    queued
+}
+
+/// Jobs ready for a delivery attempt. Jobs whose task or reminder has since
+/// been deleted are dropped; jobs whose task moved, e.g. into a workspace,
+/// follow it to its new scope.
+pub fn take_ready(data: &mut AppStateData, now: DateTime<Utc>) -> Vec<ReminderJob> {
+    let scopes: HashMap<Uuid, EventScope> = data
+        .reminder_jobs
+        .iter()
+        .filter_map(|job| {
+            let (scope, task) = data.task_scope(job.task_id)?;
+            task.reminders
+                .iter()
+                .any(|r| r.id == job.reminder_id)
+                .then_some((job.id, scope))
+        })
+        .collect();
+    data.reminder_jobs
+        .retain_mut(|job| match scopes.get(&job.id) {
+            Some(EventScope::Workspace(workspace_id)) => {
+                job.workspace_id = Some(*workspace_id);
+                true
+            }
+            Some(EventScope::User(user_id)) => {
+                job.user_id = *user_id;
+                job.workspace_id = None;
+                true
+            }
+            None => false,
+        });
+    data.reminder_jobs
+        .iter()
+        .filter(|job| job.next_attempt <= now)
+        .cloned()
+        .collect()
+}
+
+/// Settles a delivery attempt: success removes the job, failure backs it off
+/// exponentially until `MAX_ATTEMPTS` is reached.
+pub fn record_attempt(
+    data: &mut AppStateData,
+    job_id: Uuid,
+    outcome: Result<(), String>,
+    now: DateTime<Utc>,
+) {
+    let Some(index) = data.reminder_jobs.iter().position(|job| job.id == job_id) else {
+        return;
+    };
+    let err = match outcome {
+        Ok(()) => {
+            data.reminder_jobs.remove(index);
+            return;
+        }
+        Err(err) => err,
+    };
+
+    let job = &mut data.reminder_jobs[index];
+    job.attempts += 1;
+    if job.attempts >= MAX_ATTEMPTS {
+        error!(
+            "Giving up on reminder {} for Task-Id: {} after {} attempts: {}",
+            job.reminder_id, job.task_id, job.attempts, err
+        );
+        data.reminder_jobs.remove(index);
+    } else {
+        let delay = RETRY_BASE_SECS << (job.attempts - 1);
+        warn!(
+            "Reminder {} delivery failed ({}), retrying in {}s",
+            job.reminder_id, err, delay
+        );
+        job.next_attempt = now + TimeDelta::seconds(delay);
+    }
+}
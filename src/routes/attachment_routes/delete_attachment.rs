@@ -0,0 +1,132 @@
+use crate::{blobs::remove_orphaned_blobs, events::ChangeKind, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeleteAttachment {
+    pub id: Uuid,
+}
+
+/// API endpoint to remove an attachment from a task
+/// URL: "/users/{userid}/tasks/{taskid}/attachments/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/tasks/{taskId}/attachments/delete",
+    tag = "attachments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = DeleteAttachment,
+    responses(
+        (status = 200, description = "Id of the removed attachment", body = Uuid),
+        (status = 404, description = "User, task or attachment not found"),
+    )
+)]
+pub async fn delete_attachment(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<DeleteAttachment>,
+) -> impl Responder {
+    let mut data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    let task = match data
+        .users
+        .get_mut(&user_id)
+        .and_then(|user| user.tasks.get_mut(&task_id))
+    {
+        Some(task) => task,
+        None => return HttpResponse::NotFound().body("Task Doesn't exists"),
+    };
+
+    match task.attachments.iter().position(|a| a.id == req.id) {
+        Some(index) => {
+            let removed = task.attachments.remove(index);
+            remove_orphaned_blobs(&data, &state_data.config.blob_dir(), [removed.sha256]);
+            data.publish_task(ChangeKind::Updated, task_id);
+            save_data(&data);
+            info!("Attachment: {} removed from Task-Id: {}", req.id, task_id);
+            HttpResponse::Ok().json(req.id)
+        }
+        None => HttpResponse::NotFound().body("Attachment doesn't exist"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blobs::{blob_path, store_blob};
+    use crate::schema::Attachment;
+    use crate::test_utils::{create_test_user_and_task, init_app_state_with, temp_config};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::Utc;
+
+    #[actix_web::test]
+    async fn test_delete_attachment_removes_orphaned_blob() {
+        let app_state = init_app_state_with(temp_config());
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let blob_dir = app_state.config.blob_dir();
+        let sha256 = store_blob(&blob_dir, b"screenshot").expect("store blob");
+
+        // the same blob attached twice: the first removal must keep it on disk
+        let attachments: Vec<Attachment> = (0..2)
+            .map(|_| Attachment {
+                id: Uuid::new_v4(),
+                name: "screen.png".to_string(),
+                size: 10,
+                mime_type: "image/png".to_string(),
+                sha256: sha256.clone(),
+                uploaded_at: Utc::now(),
+            })
+            .collect();
+        let ids: Vec<Uuid> = attachments.iter().map(|a| a.id).collect();
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(task) = state_data
+                .users
+                .get_mut(&user_id)
+                .and_then(|user| user.tasks.get_mut(&test_task_id))
+            {
+                task.attachments = attachments;
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/attachments/delete",
+            web::delete().to(delete_attachment),
+        ))
+        .await;
+
+        let uri = format!(
+            "/users/{}/tasks/{}/attachments/delete",
+            user_id, test_task_id
+        );
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteAttachment { id: ids[0] })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(blob_path(&blob_dir, &sha256).unwrap().exists());
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteAttachment { id: ids[1] })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!blob_path(&blob_dir, &sha256).unwrap().exists());
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod delete_attachment;
+pub mod download_attachment;
+pub mod upload_attachment;
+
+pub use delete_attachment::{delete_attachment, DeleteAttachment};
+pub use download_attachment::download_attachment;
+pub use upload_attachment::upload_attachment;
@@ -0,0 +1,389 @@
+use crate::{
+    blobs::{blob_path, remove_orphaned_blobs, store_blob},
+    events::ChangeKind,
+    schema::{save_data, AppStateData, Attachment},
+    AppState,
+};
+use actix_files::file_extension_to_mime;
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use futures_util::StreamExt;
+use log::{error, info, warn};
+use std::path::Path;
+use uuid::Uuid;
+
+/// A file read from the multipart body, not yet stored
+struct Upload {
+    name: String,
+    mime_type: String,
+    bytes: Vec<u8>,
+}
+
+/// API endpoint to attach one or more files to a task (multipart/form-data)
+/// URL: "/users/{userid}/tasks/{taskid}/attachments/upload"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/{taskId}/attachments/upload",
+    tag = "attachments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body(content_type = "multipart/form-data", description = "One or more files, each in its own part"),
+    responses(
+        (status = 200, description = "Ids of the new attachments", body = Vec<Uuid>),
+        (status = 400, description = "Malformed upload or no files in it"),
+        (status = 404, description = "User or task not found"),
+        (status = 413, description = "Attachment quota exceeded"),
+    )
+)]
+pub async fn upload_attachment(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    mut payload: Multipart,
+) -> impl Responder {
+    let (user_id, task_id) = path.into_inner();
+
+    // Work out the remaining quota up front so oversized uploads stop early
+    let remaining = match state_data.data.lock() {
+        Ok(data) => match data.users.get(&user_id) {
+            Some(user) if user.tasks.contains_key(&task_id) => state_data
+                .config
+                .attachment_quota
+                .saturating_sub(data.attachment_usage(user_id)),
+            Some(_) => return HttpResponse::NotFound().body("Task Doesn't exists"),
+            None => return HttpResponse::NotFound().body("User not found"),
+        },
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let mut uploads = Vec::new();
+    let mut total: u64 = 0;
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(err) => return HttpResponse::BadRequest().body(format!("Invalid upload: {}", err)),
+        };
+        // Only fields carrying a filename are attachments
+        let name = match field.content_disposition().and_then(|cd| cd.get_filename()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let mime_type = match field.content_type() {
+            Some(mime) => mime.to_string(),
+            None => {
+                let ext = Path::new(&name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or_default();
+                file_extension_to_mime(ext).to_string()
+            }
+        };
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    return HttpResponse::BadRequest().body(format!("Invalid upload: {}", err))
+                }
+            };
+            total += chunk.len() as u64;
+            if total > remaining {
+                warn!("UserID: {} exceeded the attachment quota", user_id);
+                return HttpResponse::PayloadTooLarge().body("Attachment quota exceeded");
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        uploads.push(Upload {
+            name,
+            mime_type,
+            bytes,
+        });
+    }
+    if uploads.is_empty() {
+        return HttpResponse::BadRequest().body("No files in upload");
+    }
+
+    // Write the blobs before touching the task so metadata never points at missing files
+    let blob_dir = state_data.config.blob_dir();
+    let mut stored = Vec::new();
+    for upload in uploads {
+        let sha256 = match store_blob(&blob_dir, &upload.bytes) {
+            Ok(sha256) => sha256,
+            Err(err) => {
+                error!("Failed to store attachment blob: {}", err);
+                return HttpResponse::InternalServerError().body("Internal Server Error");
+            }
+        };
+        let attachment = Attachment {
+            id: Uuid::new_v4(),
+            name: upload.name,
+            size: upload.bytes.len() as u64,
+            mime_type: upload.mime_type,
+            sha256,
+            uploaded_at: Utc::now(),
+        };
+        stored.push((attachment, upload.bytes));
+    }
+
+    let mut data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    match attach(
+        &mut data,
+        &blob_dir,
+        state_data.config.attachment_quota,
+        user_id,
+        task_id,
+        stored,
+    ) {
+        Ok(ids) => {
+            save_data(&data);
+            info!("Attached {} file(s) to Task-Id: {}", ids.len(), task_id);
+            HttpResponse::Ok().json(ids)
+        }
+        Err((err, attachments)) => {
+            // Drop the blobs nothing ended up referencing
+            remove_orphaned_blobs(&data, &blob_dir, attachments.into_iter().map(|a| a.sha256));
+            err.into()
+        }
+    }
+}
+
+/// Why stored blobs couldn't be attached to their task
+#[derive(Debug, PartialEq)]
+pub enum AttachError {
+    TaskNotFound,
+    QuotaExceeded,
+    /// A blob swept in the meantime couldn't be written again
+    StorageFailed,
+}
+
+impl From<AttachError> for HttpResponse {
+    fn from(err: AttachError) -> Self {
+        match err {
+            AttachError::TaskNotFound => HttpResponse::NotFound().body("Task Doesn't exists"),
+            AttachError::QuotaExceeded => {
+                HttpResponse::PayloadTooLarge().body("Attachment quota exceeded")
+            }
+            AttachError::StorageFailed => {
+                HttpResponse::InternalServerError().body("Internal Server Error")
+            }
+        }
+    }
+}
+
+/// Adds the stored attachments to the task, re-checking the quota against the
+/// current usage since other uploads may have landed while this one was streaming.
+/// Blobs are stored outside the lock, so a delete that ran in between may have
+/// swept an identical one; those are written again here.
+/// On failure the attachments are handed back so their blobs can be removed.
+fn attach(
+    data: &mut AppStateData,
+    blob_dir: &Path,
+    quota: u64,
+    user_id: Uuid,
+    task_id: Uuid,
+    stored: Vec<(Attachment, Vec<u8>)>,
+) -> Result<Vec<Uuid>, (AttachError, Vec<Attachment>)> {
+    let size: u64 = stored.iter().map(|(attachment, _)| attachment.size).sum();
+    let (attachments, bytes): (Vec<Attachment>, Vec<Vec<u8>>) = stored.into_iter().unzip();
+    if data.attachment_usage(user_id).saturating_add(size) > quota {
+        warn!("UserID: {} exceeded the attachment quota", user_id);
+        return Err((AttachError::QuotaExceeded, attachments));
+    }
+    let task = data
+        .users
+        .get_mut(&user_id)
+        .and_then(|user| user.tasks.get_mut(&task_id));
+    let Some(task) = task else {
+        // The task vanished while the upload was streaming
+        return Err((AttachError::TaskNotFound, attachments));
+    };
+    for (attachment, bytes) in attachments.iter().zip(&bytes) {
+        let present = blob_path(blob_dir, &attachment.sha256).is_ok_and(|path| path.is_file());
+        if !present {
+            if let Err(err) = store_blob(blob_dir, bytes) {
+                error!("Failed to store attachment blob: {}", err);
+                return Err((AttachError::StorageFailed, attachments));
+            }
+        }
+    }
+    let ids: Vec<Uuid> = attachments.iter().map(|attachment| attachment.id).collect();
+    task.attachments.extend(attachments);
+    data.publish_task(ChangeKind::Updated, task_id);
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blobs::sha256_hex;
+    use crate::test_utils::{create_test_user_and_task, init_app_state_with, temp_config};
+    use actix_web::{http::header, http::StatusCode, test, App};
+
+    fn multipart_body(boundary: &str, filename: &str, content: &str) -> String {
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{f}\"\r\nContent-Type: text/plain\r\n\r\n{c}\r\n--{b}--\r\n",
+            b = boundary,
+            f = filename,
+            c = content
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_upload_attachment() {
+        let app_state = init_app_state_with(temp_config());
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/attachments/upload",
+            web::post().to(upload_attachment),
+        ))
+        .await;
+
+        let boundary = "test-boundary";
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/{}/attachments/upload",
+                user_id, test_task_id
+            ))
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(multipart_body(boundary, "build.log", "all green"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let ids: Vec<Uuid> = test::read_body_json(resp).await;
+        assert_eq!(ids.len(), 1);
+
+        let sha256 = sha256_hex(b"all green");
+        assert!(blob_path(&app_state.config.blob_dir(), &sha256)
+            .unwrap()
+            .exists());
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let attachment = state_data
+                .users
+                .get(&user_id)
+                .and_then(|user| user.tasks.get(&test_task_id))
+                .and_then(|task| task.attachments.first().cloned());
+            let attachment = attachment.expect("attachment missing");
+            assert_eq!(attachment.name, "build.log");
+            assert_eq!(attachment.size, 9);
+            assert_eq!(attachment.mime_type, "text/plain");
+            assert_eq!(attachment.sha256, sha256);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_upload_attachment_over_quota() {
+        let mut config = temp_config();
+        config.attachment_quota = 4;
+        let app_state = init_app_state_with(config);
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/attachments/upload",
+            web::post().to(upload_attachment),
+        ))
+        .await;
+
+        let boundary = "test-boundary";
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/{}/attachments/upload",
+                user_id, test_task_id
+            ))
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(multipart_body(boundary, "big.log", "way too large"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_attach_rechecks_quota() {
+        let mut config = temp_config();
+        config.attachment_quota = 12;
+        let app_state = init_app_state_with(config);
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let blob_dir = app_state.config.blob_dir();
+
+        let stored = |content: &[u8]| {
+            let attachment = Attachment {
+                id: Uuid::new_v4(),
+                name: "build.log".to_string(),
+                size: content.len() as u64,
+                mime_type: "text/plain".to_string(),
+                sha256: store_blob(&blob_dir, content).expect("failed to store blob"),
+                uploaded_at: Utc::now(),
+            };
+            (attachment, content.to_vec())
+        };
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            // both fit the quota on their own, but not together
+            let first = stored(b"first upload");
+            let second = stored(b"second");
+            let quota = app_state.config.attachment_quota;
+            let second_path = blob_path(&blob_dir, &second.0.sha256).unwrap();
+
+            // a concurrent delete swept the blob after it was stored
+            std::fs::remove_file(blob_path(&blob_dir, &first.0.sha256).unwrap()).unwrap();
+            let first_sha = first.0.sha256.clone();
+            assert!(attach(
+                &mut state_data,
+                &blob_dir,
+                quota,
+                user_id,
+                test_task_id,
+                vec![first]
+            )
+            .is_ok());
+            assert!(blob_path(&blob_dir, &first_sha).unwrap().exists());
+
+            let (err, rejected) = attach(
+                &mut state_data,
+                &blob_dir,
+                quota,
+                user_id,
+                test_task_id,
+                vec![second],
+            )
+            .expect_err("quota should be exceeded");
+            assert_eq!(err, AttachError::QuotaExceeded);
+            remove_orphaned_blobs(
+                &state_data,
+                &blob_dir,
+                rejected.into_iter().map(|a| a.sha256),
+            );
+            assert!(!second_path.exists());
+            assert_eq!(state_data.attachment_usage(user_id), 12);
+
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    board::{build_board, Board},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to fetch a user's tasks as a kanban board
+/// URL: "/users/{userid}/board"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/board",
+    tag = "boards",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = Board),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn get_board(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    match state_data.users.get(&user_id) {
+        Some(user) => {
+            info!("Building board for user ID: {}", user_id);
+            HttpResponse::Ok().json(build_board(&user.tasks, &user.workflow()))
+        }
+        None => HttpResponse::NotFound().body("User not found"),
+    }
+}
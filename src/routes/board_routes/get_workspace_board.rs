@@ -0,0 +1,47 @@
+use crate::{
+    board::{build_board, Board},
+    routes::workspace_routes::authorize,
+    schema::Role,
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint to fetch a workspace's tasks as a kanban board
+/// URL: "/users/{userid}/workspaces/{workspaceid}/board"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/workspaces/{workspaceId}/board",
+    tag = "boards",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    responses(
+        (status = 200, body = Board),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_workspace_board(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Viewer) {
+        return err.into();
+    }
+
+    match state_data.workspaces.get(&workspace_id) {
+        Some(workspace) => {
+            HttpResponse::Ok().json(build_board(&workspace.tasks, &workspace.workflow()))
+        }
+        None => HttpResponse::NotFound().body("Workspace not found"),
+    }
+}
@@ -0,0 +1,31 @@
+pub mod get_board;
+pub mod get_workspace_board;
+pub mod move_task;
+pub mod move_workspace_task;
+
+pub use get_board::get_board;
+pub use get_workspace_board::get_workspace_board;
+pub use move_task::{move_task, MoveTask};
+pub use move_workspace_task::move_workspace_task;
+
+use crate::board::MoveError;
+use actix_web::HttpResponse;
+
+impl From<MoveError> for HttpResponse {
+    fn from(err: MoveError) -> Self {
+        match err {
+            MoveError::TaskNotFound(task_id) => {
+                HttpResponse::NotFound().body(format!("Task-id: {} doesn't exist", task_id))
+            }
+            MoveError::UnknownStatus(status) => {
+                HttpResponse::BadRequest().body(format!("Unknown status: {}", status))
+            }
+            MoveError::TransitionNotAllowed { from, to } => HttpResponse::Conflict()
+                .body(format!("Transition from {} to {} is not allowed", from, to)),
+            MoveError::WipLimitReached { status, limit } => HttpResponse::Conflict().body(format!(
+                "Column {} is at its WIP limit of {}",
+                status, limit
+            )),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{board, events::ChangeKind, schema::save_data, schema::Status, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct MoveTask {
+    pub id: Uuid,
+    pub status: Status,
+    /// Zero-based index in the target column; larger values append
+    pub position: usize,
+}
+
+/// API endpoint to move a task to another column and/or position on the board
+/// URL: "/users/{userid}/board/move"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/board/move",
+    tag = "boards",
+    params(("userId" = Uuid, Path)),
+    request_body = MoveTask,
+    responses(
+        (status = 200, description = "Id of the moved task", body = Uuid),
+        (status = 400, description = "Status not in the workflow"),
+        (status = 409, description = "Transition not allowed or WIP limit reached"),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn move_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<MoveTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user = match state_data.users.get_mut(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let MoveTask {
+        id: task_id,
+        status,
+        position,
+    } = req.into_inner();
+    let workflow = user.workflow();
+    if let Err(err) = board::move_task(
+        &mut user.tasks,
+        &workflow,
+        task_id,
+        status.clone(),
+        position,
+    ) {
+        return err.into();
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+
+    info!(
+        "Task-Id: {} moved to {} at position {}",
+        task_id, status, position
+    );
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::Board;
+    use crate::routes::board_routes::get_board;
+    use crate::schema::Task;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::Workflow;
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::NaiveDate;
+
+    #[actix_web::test]
+    async fn test_move_task_orders_and_limits() {
+        let app_state = init_app_state();
+        let (user_id, first_id) = create_test_user_and_task(&app_state);
+        let second = Task::new(
+            "second",
+            "",
+            NaiveDate::from_ymd_opt(2000, 1, 2).expect("failed to parse NaiveDate"),
+        );
+        let second_id = second.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.tasks.insert(second_id, second);
+                // at most one task in progress
+                let mut workflow = Workflow::default();
+                workflow.states[1].wip_limit = Some(1);
+                user.workflow = Some(workflow);
+            }
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/board", web::get().to(get_board))
+                .route("/users/{user_id}/board/move", web::put().to(move_task)),
+        )
+        .await;
+
+        let move_req = |id: Uuid, status: Status, position: usize| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/board/move", user_id))
+                .set_json(MoveTask {
+                    id,
+                    status,
+                    position,
+                })
+                .to_request()
+        };
+
+        // reorder within ToDo: second goes first
+        let resp = test::call_service(&app, move_req(second_id, Status::ToDo, 0)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(&app, move_req(first_id, Status::InProgress, 0)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // InProgress is full
+        let resp = test::call_service(&app, move_req(second_id, Status::InProgress, 0)).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/board", user_id))
+            .to_request();
+        let board: Board = test::call_and_read_body_json(&app, req).await;
+        let ids = |i: usize| -> Vec<Uuid> { board.columns[i].tasks.iter().map(|t| t.id).collect() };
+        assert_eq!(board.columns[0].status, Status::ToDo);
+        assert_eq!(ids(0), vec![second_id]);
+        assert_eq!(ids(1), vec![first_id]);
+        assert!(ids(2).is_empty());
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    board,
+    events::ChangeKind,
+    routes::{board_routes::MoveTask, workspace_routes::authorize},
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to move a task on a workspace board
+/// URL: "/users/{userid}/workspaces/{workspaceid}/board/move"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/workspaces/{workspaceId}/board/move",
+    tag = "boards",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = MoveTask,
+    responses(
+        (status = 200, description = "Id of the moved task", body = Uuid),
+        (status = 400, description = "Status not in the workflow"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace or task not found"),
+        (status = 409, description = "Transition not allowed or WIP limit reached"),
+    )
+)]
+pub async fn move_workspace_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<MoveTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Editor) {
+        return err.into();
+    }
+    let workspace = match state_data.workspaces.get_mut(&workspace_id) {
+        Some(workspace) => workspace,
+        None => return HttpResponse::NotFound().body("Workspace not found"),
+    };
+
+    let MoveTask {
+        id: task_id,
+        status,
+        position,
+    } = req.into_inner();
+    let workflow = workspace.workflow();
+    if let Err(err) = board::move_task(
+        &mut workspace.tasks,
+        &workflow,
+        task_id,
+        status.clone(),
+        position,
+    ) {
+        return err.into();
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+
+    info!(
+        "Task-Id: {} moved to {} at position {} in WorkspaceID: {}",
+        task_id, status, position, workspace_id
+    );
+    HttpResponse::Ok().json(task_id)
+}
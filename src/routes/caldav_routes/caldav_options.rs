@@ -0,0 +1,57 @@
+use crate::caldav::DAV_COMPLIANCE;
+use actix_web::{http::header, HttpResponse, Responder};
+
+/// API endpoint answering CalDAV capability probes
+/// URL: "/caldav/..."
+pub async fn caldav_options() -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(("DAV", DAV_COMPLIANCE))
+        .insert_header((header::ALLOW, "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT"))
+        .finish()
+}
+
+/// API endpoint pointing clients that only know the host to the CalDAV root
+/// URL: "/.well-known/caldav"
+pub async fn caldav_discovery() -> impl Responder {
+    HttpResponse::MovedPermanently()
+        .insert_header((header::LOCATION, "/caldav/"))
+        .finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{http::Method, http::StatusCode, test, web, App};
+
+    #[actix_web::test]
+    async fn test_caldav_options() {
+        let app = test::init_service(
+            App::new()
+                .route("/.well-known/caldav", web::route().to(caldav_discovery))
+                .route(
+                    "/caldav/{tail:.*}",
+                    web::method(Method::OPTIONS).to(caldav_options),
+                ),
+        )
+        .await;
+
+        // probes need no login
+        let req = test::TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/caldav/some-user/tasks/")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("DAV").unwrap(), DAV_COMPLIANCE);
+        let allow = resp.headers().get(header::ALLOW).unwrap().to_str().unwrap();
+        assert!(allow.contains("PROPFIND"));
+        assert!(allow.contains("REPORT"));
+
+        let req = test::TestRequest::get()
+            .uri("/.well-known/caldav")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/caldav/");
+    }
+}
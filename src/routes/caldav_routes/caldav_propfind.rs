@@ -0,0 +1,165 @@
+use crate::{
+    caldav::{authorize, depth, multistatus, parse_propfind, task_id_of, Resource},
+    AppState, Task,
+};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint listing properties of the CalDAV root, a user's home, their
+/// task calendar or one task, and with `Depth: 1` of their children
+/// URL: "/caldav/{userid}/tasks/{taskid}.ics"
+pub async fn caldav_propfind(
+    state_data: web::Data<AppState>,
+    req: HttpRequest,
+    body: String,
+) -> impl Responder {
+    let props = match parse_propfind(&body) {
+        Ok(props) => props,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let info = req.match_info();
+    let path_user = match info.get("userId").map(Uuid::parse_str) {
+        Some(Ok(user_id)) => Some(user_id),
+        Some(Err(_)) => return HttpResponse::NotFound().body("User not found"),
+        None => None,
+    };
+    if info.get("collection").is_some_and(|name| name != "tasks") {
+        return HttpResponse::NotFound().body("Collection not found");
+    }
+
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    let user_id = match authorize(&state_data, &req, path_user) {
+        Ok(user_id) => user_id,
+        Err(err) => return err.into(),
+    };
+    let user = &state_data.users[&user_id];
+
+    let mut resources = Vec::new();
+    match (info.get("collection"), info.get("resource")) {
+        (_, Some(resource)) => match task_id_of(resource).and_then(|id| user.tasks.get(&id)) {
+            Some(task) => resources.push(Resource::Task(user, task)),
+            None => return HttpResponse::NotFound().body("Task doesn't exist"),
+        },
+        (Some(_), None) => {
+            resources.push(Resource::Calendar(user));
+            if depth(&req) > 0 {
+                let mut tasks: Vec<&Task> = user.tasks.values().collect();
+                tasks.sort_by_key(|task| (task.due_date, task.rank, task.id));
+                resources.extend(tasks.into_iter().map(|task| Resource::Task(user, task)));
+            }
+        }
+        (None, None) if path_user.is_some() => {
+            resources.push(Resource::Home(user));
+            if depth(&req) > 0 {
+                resources.push(Resource::Calendar(user));
+            }
+        }
+        (None, None) => resources.push(Resource::Root(user)),
+    }
+
+    let responses: Vec<String> = resources
+        .iter()
+        .map(|resource| match &props {
+            Some(props) => resource.response(props),
+            None => resource.response(&resource.all_props()),
+        })
+        .collect();
+    multistatus(&responses)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::caldav::dav_method;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    #[actix_web::test]
+    async fn test_caldav_propfind() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.get_mut(&user_id).unwrap().caldav_token = Some(token.clone());
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/caldav/",
+                    web::method(dav_method("PROPFIND")).to(caldav_propfind),
+                )
+                .route(
+                    "/caldav/{userId}/{collection}/",
+                    web::method(dav_method("PROPFIND")).to(caldav_propfind),
+                ),
+        )
+        .await;
+        let auth = format!("Basic {}", STANDARD.encode(format!("me:{}", token)));
+        let propfind = |uri: &str, auth: &str| {
+            test::TestRequest::default()
+                .method(dav_method("PROPFIND"))
+                .uri(uri)
+                .insert_header((header::AUTHORIZATION, auth.to_string()))
+                .insert_header(("Depth", "1"))
+                .set_payload(
+                    r#"<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+                         <d:prop><d:resourcetype/><d:getetag/><c:calendar-home-set/></d:prop>
+                       </d:propfind>"#,
+                )
+                .to_request()
+        };
+
+        // discovery points at the user's home
+        let resp = test::call_service(&app, propfind("/caldav/", &auth)).await;
+        assert_eq!(resp.status(), StatusCode::MULTI_STATUS);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(&format!(
+            "<c:calendar-home-set><d:href>/caldav/{}/</d:href></c:calendar-home-set>",
+            user_id
+        )));
+
+        let resp = test::call_service(
+            &app,
+            propfind(&format!("/caldav/{}/tasks/", user_id), &auth),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::MULTI_STATUS);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>"));
+        assert!(body.contains(&format!(
+            "<d:href>/caldav/{}/tasks/{}.ics</d:href>",
+            user_id, test_task_id
+        )));
+        assert!(body.contains("<c:calendar-home-set/></d:prop><d:status>HTTP/1.1 404 Not Found"));
+
+        // wrong token, or the right token on someone else's calendar
+        let resp = test::call_service(
+            &app,
+            propfind(&format!("/caldav/{}/tasks/", user_id), "Basic bWU6d3Jvbmc="),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(
+            &app,
+            propfind(&format!("/caldav/{}/tasks/", Uuid::new_v4()), &auth),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
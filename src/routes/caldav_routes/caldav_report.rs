@@ -0,0 +1,152 @@
+use crate::{
+    caldav::{
+        authorize, missing, multistatus, parse_report, task_id_of, Report, Resource, CALDAV_NS,
+        DAV_NS,
+    },
+    AppState, Task,
+};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint for `calendar-query` and `calendar-multiget` reports on a
+/// user's task calendar
+/// URL: "/caldav/{userid}/tasks/"
+pub async fn caldav_report(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, String)>,
+    req: HttpRequest,
+    body: String,
+) -> impl Responder {
+    let (user_id, collection) = path.into_inner();
+    if collection != "tasks" {
+        return HttpResponse::NotFound().body("Collection not found");
+    }
+    let report = match parse_report(&body) {
+        Ok(report) => report,
+        Err(err) => return HttpResponse::Forbidden().body(err),
+    };
+
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    if let Err(err) = authorize(&state_data, &req, Some(user_id)) {
+        return err.into();
+    }
+    let user = &state_data.users[&user_id];
+
+    let default_props = || {
+        vec![
+            (DAV_NS.to_string(), "getetag".to_string()),
+            (CALDAV_NS.to_string(), "calendar-data".to_string()),
+        ]
+    };
+    let responses = match report {
+        Report::Query { props, todos } => {
+            let props = if props.is_empty() {
+                default_props()
+            } else {
+                props
+            };
+            let mut tasks: Vec<&Task> = match todos {
+                true => user.tasks.values().collect(),
+                false => Vec::new(),
+            };
+            tasks.sort_by_key(|task| (task.due_date, task.rank, task.id));
+            tasks
+                .into_iter()
+                .map(|task| Resource::Task(user, task).response(&props))
+                .collect::<Vec<_>>()
+        }
+        Report::Multiget { props, hrefs } => {
+            let props = if props.is_empty() {
+                default_props()
+            } else {
+                props
+            };
+            hrefs
+                .iter()
+                .map(
+                    |href| match task_id_of(href).and_then(|id| user.tasks.get(&id)) {
+                        Some(task) => Resource::Task(user, task).response(&props),
+                        None => missing(href),
+                    },
+                )
+                .collect()
+        }
+    };
+    multistatus(&responses)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::caldav::dav_method;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    #[actix_web::test]
+    async fn test_caldav_report() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.get_mut(&user_id).unwrap().caldav_token = Some(token.clone());
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/caldav/{userId}/{collection}/",
+            web::method(dav_method("REPORT")).to(caldav_report),
+        ))
+        .await;
+        let report = |body: String| {
+            test::TestRequest::default()
+                .method(dav_method("REPORT"))
+                .uri(&format!("/caldav/{}/tasks/", user_id))
+                .insert_header((
+                    header::AUTHORIZATION,
+                    format!("Basic {}", STANDARD.encode(format!("me:{}", token))),
+                ))
+                .set_payload(body)
+                .to_request()
+        };
+
+        let query = r#"<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+              <d:prop><d:getetag/><c:calendar-data/></d:prop>
+              <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+            </c:calendar-query>"#;
+        let resp = test::call_service(&app, report(query.to_string())).await;
+        assert_eq!(resp.status(), StatusCode::MULTI_STATUS);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(&format!("UID:{}\r\n", test_task_id)));
+        assert!(body.contains("<d:getetag>&quot;"));
+
+        let gone = Uuid::new_v4();
+        let multiget = format!(
+            r#"<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+              <d:prop><d:getetag/></d:prop>
+              <d:href>/caldav/{0}/tasks/{1}.ics</d:href>
+              <d:href>/caldav/{0}/tasks/{2}.ics</d:href>
+            </c:calendar-multiget>"#,
+            user_id, test_task_id, gone
+        );
+        let body = test::call_and_read_body(&app, report(multiget)).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(body.matches("<d:response>").count(), 2);
+        assert!(body.contains(&format!(
+            "<d:href>/caldav/{}/tasks/{}.ics</d:href><d:status>HTTP/1.1 404 Not Found",
+            user_id, gone
+        )));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
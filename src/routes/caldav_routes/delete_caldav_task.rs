@@ -0,0 +1,132 @@
+use crate::{
+    caldav::{authorize, etag, preconditions_hold, task_id_of},
+    schema::save_data,
+    sync::{apply_op, ConflictStrategy, OpKind, SyncOp},
+    AppState,
+};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde_json::Map;
+use uuid::Uuid;
+
+/// API endpoint to delete a task through its CalDAV resource. Honors If-Match.
+/// URL: "/caldav/{userid}/tasks/{taskid}.ics"
+pub async fn delete_caldav_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, String, String)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let config = &state_data.config;
+    let (user_id, collection, resource) = path.into_inner();
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    if let Err(err) = authorize(&state_data, &req, Some(user_id)) {
+        return err.into();
+    }
+
+    let user = &state_data.users[&user_id];
+    let task = match task_id_of(&resource).and_then(|id| user.tasks.get(&id)) {
+        Some(task) if collection == "tasks" => task,
+        _ => return HttpResponse::NotFound().body("Task doesn't exist"),
+    };
+    if !preconditions_hold(&req, Some(&etag(task))) {
+        return HttpResponse::PreconditionFailed().body("Task was changed");
+    }
+
+    let op = SyncOp {
+        op: OpKind::Delete,
+        task_id: task.id,
+        base_version: None,
+        client_time: Utc::now(),
+        fields: Map::new(),
+    };
+    apply_op(
+        &mut state_data,
+        user_id,
+        &op,
+        ConflictStrategy::LastWriterWins,
+        &config.blob_dir(),
+    );
+    save_data(&state_data);
+
+    info!("Task deleted over CalDAV with ID: {}", op.task_id);
+    HttpResponse::NoContent().finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    #[actix_web::test]
+    async fn test_delete_caldav_task() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        let current = match app_state.data.lock() {
+            Ok(mut state_data) => {
+                let user = state_data.users.get_mut(&user_id).unwrap();
+                user.caldav_token = Some(token.clone());
+                etag(&user.tasks[&test_task_id])
+            }
+            Err(_) => panic!("poisoned lock"),
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/caldav/{userId}/{collection}/{resource}",
+            web::delete().to(delete_caldav_task),
+        ))
+        .await;
+        let uri = format!("/caldav/{}/tasks/{}.ics", user_id, test_task_id);
+        let auth = (
+            header::AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode(format!("me:{}", token))),
+        );
+
+        // without credentials nothing is deleted
+        let req = test::TestRequest::delete().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // a client holding an outdated copy must not delete the task
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, "\"stale\""))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        if let Ok(state_data) = app_state.data.lock() {
+            assert!(state_data.users[&user_id].tasks.contains_key(&test_task_id));
+        };
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, current))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert!(!state_data.users[&user_id].tasks.contains_key(&test_task_id));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
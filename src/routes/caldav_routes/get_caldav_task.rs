@@ -0,0 +1,138 @@
+use crate::{
+    caldav::{authorize, etag, task_calendar, task_id_of},
+    AppState,
+};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint to download one task as an iCalendar VTODO resource
+/// URL: "/caldav/{userid}/tasks/{taskid}.ics"
+pub async fn get_caldav_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, String, String)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (user_id, collection, resource) = path.into_inner();
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    if let Err(err) = authorize(&state_data, &req, Some(user_id)) {
+        return err.into();
+    }
+
+    let user = &state_data.users[&user_id];
+    let task = match task_id_of(&resource).and_then(|id| user.tasks.get(&id)) {
+        Some(task) if collection == "tasks" => task,
+        _ => return HttpResponse::NotFound().body("Task doesn't exist"),
+    };
+    let etag = etag(task);
+    let cached = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes());
+    if cached {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish();
+    }
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header((header::ETAG, etag))
+        .body(task_calendar(user, task))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    fn basic(password: &str) -> (header::HeaderName, String) {
+        (
+            header::AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode(format!("me:{}", password))),
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_get_caldav_task() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (other_id, _) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        let other_token = format!("token-{}", Uuid::new_v4());
+        let feed_token = format!("feed-{}", Uuid::new_v4());
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            user.caldav_token = Some(token.clone());
+            user.feed_token = Some(feed_token.clone());
+            state_data.users.get_mut(&other_id).unwrap().caldav_token = Some(other_token.clone());
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/caldav/{userId}/{collection}/{resource}",
+            web::get().to(get_caldav_task),
+        ))
+        .await;
+        let uri = format!("/caldav/{}/tasks/{}.ics", user_id, test_task_id);
+
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(basic(&token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(&format!("UID:{}\r\n", test_task_id)));
+
+        // unchanged since the client's copy
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(basic(&token))
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        // no credentials, or the read-only feed token, don't log in
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert!(resp.headers().contains_key(header::WWW_AUTHENTICATE));
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(basic(&feed_token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // another user's login can't read this calendar
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(basic(&other_token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/caldav/{}/tasks/{}.ics", user_id, Uuid::new_v4()))
+            .insert_header(basic(&token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            state_data.users.remove(&other_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,27 @@
+pub mod caldav_options;
+pub mod caldav_propfind;
+pub mod caldav_report;
+pub mod delete_caldav_task;
+pub mod get_caldav_task;
+pub mod put_caldav_task;
+
+pub use caldav_options::{caldav_discovery, caldav_options};
+pub use caldav_propfind::caldav_propfind;
+pub use caldav_report::caldav_report;
+pub use delete_caldav_task::delete_caldav_task;
+pub use get_caldav_task::get_caldav_task;
+pub use put_caldav_task::put_caldav_task;
+
+use crate::caldav::AuthError;
+use actix_web::{http::header, HttpResponse};
+
+impl From<AuthError> for HttpResponse {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::Unauthorized => HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Basic realm=\"tasks\""))
+                .body("Unauthorized"),
+            AuthError::Forbidden => HttpResponse::Forbidden().body("Forbidden"),
+        }
+    }
+}
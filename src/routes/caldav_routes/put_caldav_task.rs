@@ -0,0 +1,297 @@
+use crate::{
+    caldav::{authorize, etag, preconditions_hold, task_id_of},
+    ical::{status_to_ical, todo_rows},
+    schema::{save_data, Status},
+    sync::{apply_op, ConflictStrategy, OpKind, OpStatus, SyncOp},
+    AppState,
+};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// API endpoint to create or replace a task from a VTODO uploaded by a
+/// CalDAV client. Honors If-Match and If-None-Match.
+/// URL: "/caldav/{userid}/tasks/{taskid}.ics"
+pub async fn put_caldav_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, String, String)>,
+    req: HttpRequest,
+    body: String,
+) -> impl Responder {
+    let config = &state_data.config;
+    let (user_id, collection, resource) = path.into_inner();
+    if collection != "tasks" {
+        return HttpResponse::NotFound().body("Collection not found");
+    }
+    let task_id = match task_id_of(&resource) {
+        Some(task_id) if !task_id.is_nil() => task_id,
+        _ => return HttpResponse::Forbidden().body("Resource names must be <UUID>.ics"),
+    };
+
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+    if let Err(err) = authorize(&state_data, &req, Some(user_id)) {
+        return err.into();
+    }
+
+    let user = &state_data.users[&user_id];
+    let existing = user.tasks.get(&task_id);
+    if !preconditions_hold(&req, existing.map(etag).as_deref()) {
+        return HttpResponse::PreconditionFailed().body("Task was changed");
+    }
+    let workflow = user.workflow();
+    let mut fields = match todo_rows(&body, &workflow).map(|mut rows| rows.pop()) {
+        Ok(Some(Ok(fields))) => fields,
+        Ok(Some(Err(err))) | Err(err) => return HttpResponse::BadRequest().body(err),
+        Ok(None) => return HttpResponse::BadRequest().body("Calendar has no VTODO"),
+    };
+    // the resource name is the task id, whatever the UID
+    fields.remove("id");
+    // STATUS only tells NEEDS-ACTION, IN-PROCESS and COMPLETED apart, so a
+    // client sending back the category the task is already in keeps its state
+    let incoming = fields.get("status").and_then(Value::as_str);
+    if let (Some(existing), Some(incoming)) = (existing, incoming) {
+        let incoming = Status::from(incoming.to_string());
+        if status_to_ical(&incoming, &workflow) == status_to_ical(&existing.status, &workflow) {
+            fields.remove("status");
+        }
+    }
+    // A PUT replaces the whole resource, so dropped properties are cleared.
+    // Tasks need a due date: one without DUE keeps its date, or is due today.
+    fields
+        .entry("description")
+        .or_insert(Value::String(String::new()));
+    if fields.contains_key("due_date") {
+        for field in ["due_time", "timezone"] {
+            fields.entry(field).or_insert(Value::Null);
+        }
+    } else if existing.is_none() {
+        let today = Utc::now().with_timezone(&user.tz()).date_naive();
+        fields.insert("due_date".into(), Value::String(today.to_string()));
+    }
+
+    let op = SyncOp {
+        op: match existing {
+            Some(_) => OpKind::Update,
+            None => OpKind::Create,
+        },
+        task_id,
+        base_version: existing.map(|task| task.version),
+        client_time: Utc::now(),
+        fields,
+    };
+    let result = apply_op(
+        &mut state_data,
+        user_id,
+        &op,
+        ConflictStrategy::LastWriterWins,
+        &config.blob_dir(),
+    );
+    if result.status != OpStatus::Applied {
+        return HttpResponse::Conflict().body(result.error.unwrap_or_default());
+    }
+    save_data(&state_data);
+
+    let etag = etag(&state_data.users[&user_id].tasks[&task_id]);
+    info!("Task stored over CalDAV with ID: {}", task_id);
+    match op.op {
+        OpKind::Create => HttpResponse::Created(),
+        _ => HttpResponse::NoContent(),
+    }
+    .insert_header((header::ETAG, etag))
+    .finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::caldav_routes::{delete_caldav_task, get_caldav_task};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::{Transition, Workflow, WorkflowState};
+    use actix_web::{http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    #[actix_web::test]
+    async fn test_put_caldav_task() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.get_mut(&user_id).unwrap().caldav_token = Some(token.clone());
+        };
+
+        let path = "/caldav/{userId}/{collection}/{resource}";
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(path, web::put().to(put_caldav_task))
+                .route(path, web::get().to(get_caldav_task))
+                .route(path, web::delete().to(delete_caldav_task)),
+        )
+        .await;
+        let task_id = Uuid::new_v4();
+        let uri = format!("/caldav/{}/tasks/{}.ics", user_id, task_id);
+        let auth = (
+            header::AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode(format!("me:{}", token))),
+        );
+        let vtodo = |summary: &str, status: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//client//EN\r\nBEGIN:VTODO\r\n\
+                 UID:{}\r\nSUMMARY:{}\r\nDESCRIPTION:from the phone\r\n\
+                 DUE:20240301T080000Z\r\nSTATUS:{}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+                task_id, summary, status
+            )
+        };
+
+        // created from a client
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .insert_header((header::IF_NONE_MATCH, "*"))
+            .set_payload(vtodo("Buy milk", "NEEDS-ACTION"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created_etag = resp.headers().get(header::ETAG).unwrap().clone();
+
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get(header::ETAG), Some(&created_etag));
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("SUMMARY:Buy milk\r\n"));
+        assert!(body.contains("DUE:20240301T080000Z\r\n"));
+
+        // edited on the client, then a stale edit is refused
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, created_etag.clone()))
+            .set_payload(vtodo("Buy oat milk", "COMPLETED"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, created_etag.clone()))
+            .set_payload(vtodo("Buy soy milk", "COMPLETED"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+        if let Ok(state_data) = app_state.data.lock() {
+            let task = &state_data.users[&user_id].tasks[&task_id];
+            assert_eq!(task.title, "Buy oat milk");
+            assert_eq!(task.description, "from the phone");
+            assert_eq!(task.status, Status::Done);
+            assert_eq!(task.timezone.as_deref(), Some("UTC"));
+        };
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert!(!state_data.users[&user_id].tasks.contains_key(&task_id));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_put_keeps_in_process_state() {
+        let app_state = init_app_state();
+        let (user_id, task_id) = create_test_user_and_task(&app_state);
+        let token = format!("token-{}", Uuid::new_v4());
+        let review = Status::Custom("InReview".to_string());
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            user.caldav_token = Some(token.clone());
+            // two in-progress states, and no way back from review
+            let states = [
+                Status::ToDo,
+                Status::InProgress,
+                review.clone(),
+                Status::Done,
+            ];
+            user.workflow = Some(Workflow {
+                states: states
+                    .iter()
+                    .map(|status| WorkflowState {
+                        status: status.clone(),
+                        terminal: *status == Status::Done,
+                        wip_limit: None,
+                    })
+                    .collect(),
+                transitions: states
+                    .windows(2)
+                    .map(|pair| Transition {
+                        from: pair[0].clone(),
+                        to: pair[1].clone(),
+                    })
+                    .collect(),
+            });
+            user.tasks.get_mut(&task_id).unwrap().status = review.clone();
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/caldav/{userId}/{collection}/{resource}",
+            web::put().to(put_caldav_task),
+        ))
+        .await;
+        let put = |summary: &str, status: &str| {
+            test::TestRequest::put()
+                .uri(&format!("/caldav/{}/tasks/{}.ics", user_id, task_id))
+                .insert_header((
+                    header::AUTHORIZATION,
+                    format!("Basic {}", STANDARD.encode(format!("me:{}", token))),
+                ))
+                .set_payload(format!(
+                    "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:{}\r\n\
+                     SUMMARY:{}\r\nSTATUS:{}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+                    task_id, summary, status
+                ))
+                .to_request()
+        };
+
+        // only the summary was edited on the client
+        let resp = test::call_service(&app, put("Renamed", "IN-PROCESS")).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        if let Ok(state_data) = app_state.data.lock() {
+            let task = &state_data.users[&user_id].tasks[&task_id];
+            assert_eq!(task.title, "Renamed");
+            assert_eq!(task.status, review);
+        };
+
+        // a real change of category still goes through the workflow
+        let resp = test::call_service(&app, put("Renamed", "NEEDS-ACTION")).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let resp = test::call_service(&app, put("Renamed", "COMPLETED")).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert_eq!(
+                state_data.users[&user_id].tasks[&task_id].status,
+                Status::Done
+            );
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
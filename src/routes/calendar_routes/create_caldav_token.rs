@@ -0,0 +1,121 @@
+use crate::{schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaldavToken {
+    /// Password for the calendar app; any user name is accepted
+    pub token: String,
+    /// Server URL to enter in the calendar app
+    pub url: String,
+}
+
+/// API endpoint to enable CalDAV access for the user, or rotate its password.
+/// Clients logged in with the previous password are locked out.
+/// URL: "/users/{userid}/caldav/token"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/caldav/token",
+    tag = "calendar",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = CaldavToken),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn create_caldav_token(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    user.caldav_token = Some(token.clone());
+    save_data(&state_data);
+
+    info!("CalDAV token issued for User-Id: {}", user_id);
+    HttpResponse::Ok().json(CaldavToken {
+        token,
+        url: "/caldav/".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::caldav::authenticate;
+    use crate::routes::calendar_routes::revoke_caldav_token;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    #[actix_web::test]
+    async fn test_caldav_token() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/users/{user_id}/caldav/token",
+                    web::post().to(create_caldav_token),
+                )
+                .route(
+                    "/users/{user_id}/caldav/token",
+                    web::delete().to(revoke_caldav_token),
+                ),
+        )
+        .await;
+        let uri = format!("/users/{}/caldav/token", user_id);
+        let logged_in = |password: &str| {
+            let req = test::TestRequest::default()
+                .insert_header((
+                    header::AUTHORIZATION,
+                    format!("Basic {}", STANDARD.encode(format!("me:{}", password))),
+                ))
+                .to_http_request();
+            let state_data = app_state.data.lock().unwrap();
+            authenticate(&state_data, &req)
+        };
+
+        let req = test::TestRequest::post().uri(&uri).to_request();
+        let first: CaldavToken = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(first.url, "/caldav/");
+        assert_eq!(logged_in(&first.token), Some(user_id));
+
+        // rotating locks the old password out
+        let req = test::TestRequest::post().uri(&uri).to_request();
+        let second: CaldavToken = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(logged_in(&first.token), None);
+        assert_eq!(logged_in(&second.token), Some(user_id));
+
+        let req = test::TestRequest::delete().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(logged_in(&second.token), None);
+        let req = test::TestRequest::delete().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FeedToken {
+    pub token: String,
+    /// Path of the feed to subscribe to in a calendar app
+    pub url: String,
+}
+
+/// API endpoint to enable the user's calendar feed, or rotate its token.
+/// The previous feed URL stops working.
+/// URL: "/users/{userid}/calendar/token"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/calendar/token",
+    tag = "calendar",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = FeedToken),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn create_feed_token(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    user.feed_token = Some(token.clone());
+    save_data(&state_data);
+
+    info!("Calendar feed token issued for User-Id: {}", user_id);
+    HttpResponse::Ok().json(FeedToken {
+        url: format!("/feeds/{}.ics", token),
+        token,
+    })
+}
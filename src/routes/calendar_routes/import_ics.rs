@@ -0,0 +1,174 @@
+use crate::{
+    ical::todo_rows,
+    routes::transfer_routes::import_tasks::MAX_IMPORT_BYTES,
+    schema::save_data,
+    transfer::{import_rows, ImportReport, OnDuplicate},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IcsImportQuery {
+    /// Validate and report without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub on_duplicate: OnDuplicate,
+}
+
+/// API endpoint to import the VTODOs of an iCalendar file as tasks.
+/// Answers with the same report as the CSV/JSON import.
+/// URL: "/users/{userid}/calendar/import?dry_run=true&on_duplicate=update"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/calendar/import",
+    tag = "calendar",
+    params(("userId" = Uuid, Path), IcsImportQuery),
+    request_body(description = "An iCalendar file", content_type = "text/calendar"),
+    responses(
+        (status = 200, description = "What happened to each VTODO", body = ImportReport),
+        (status = 400, description = "Unreadable calendar"),
+        (status = 404, description = "User not found"),
+        (status = 413, description = "Body too large"),
+    )
+)]
+pub async fn import_ics(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<IcsImportQuery>,
+    payload: web::Payload,
+) -> impl Responder {
+    let body = match payload.to_bytes_limited(MAX_IMPORT_BYTES).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(err)) => return HttpResponse::BadRequest().body(err.to_string()),
+        Err(_) => {
+            return HttpResponse::PayloadTooLarge()
+                .body(format!("Imports are limited to {} bytes", MAX_IMPORT_BYTES))
+        }
+    };
+    let text = match String::from_utf8(body.to_vec()) {
+        Ok(text) => text,
+        Err(_) => return HttpResponse::BadRequest().body("Calendar must be UTF-8"),
+    };
+
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let workflow = match state_data.users.get(&user_id) {
+        Some(user) => user.workflow(),
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let rows = match todo_rows(&text, &workflow) {
+        Ok(rows) => rows,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let report = match import_rows(
+        &mut state_data,
+        user_id,
+        rows,
+        &HashMap::new(),
+        query.on_duplicate,
+        query.dry_run,
+    ) {
+        Some(report) => report,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    if !report.dry_run && report.created + report.updated > 0 {
+        save_data(&state_data);
+    }
+
+    info!(
+        "Calendar import for User-Id: {}: {} created, {} updated, {} skipped, {} invalid{}",
+        user_id,
+        report.created,
+        report.updated,
+        report.skipped,
+        report.invalid,
+        if report.dry_run { " (dry run)" } else { "" }
+    );
+    HttpResponse::Ok().json(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Status;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::transfer::{ImportReport, RowAction};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_import_ics() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/calendar/import",
+            web::post().to(import_ics),
+        ))
+        .await;
+
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+             BEGIN:VTODO\r\nUID:someone-else@example.com\r\nSUMMARY:Call the\r\n  plumber\r\n\
+             DESCRIPTION:kitchen\\, sink\r\nDUE;TZID=Europe/Berlin:20240301T090000\r\n\
+             STATUS:IN-PROCESS\r\nEND:VTODO\r\n\
+             BEGIN:VTODO\r\nUID:{}\r\nSUMMARY:sample-title\r\nDUE;VALUE=DATE:20000101\r\n\
+             STATUS:COMPLETED\r\nEND:VTODO\r\n\
+             BEGIN:VTODO\r\nSUMMARY:No due date\r\nEND:VTODO\r\n\
+             END:VCALENDAR\r\n",
+            test_task_id
+        );
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/calendar/import?on_duplicate=update",
+                user_id
+            ))
+            .set_payload(ics)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let report: ImportReport = test::read_body_json(resp).await;
+        let actions: Vec<_> = report.rows.iter().map(|row| row.action).collect();
+        assert_eq!(
+            actions,
+            vec![RowAction::Created, RowAction::Updated, RowAction::Invalid]
+        );
+        let created = report.rows[0].task_id.unwrap();
+
+        // not a calendar at all
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/calendar/import", user_id))
+            .set_payload("title,due_date\n")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = &state_data.users[&user_id];
+            let task = &user.tasks[&created];
+            assert_eq!(task.title, "Call the plumber");
+            assert_eq!(task.description, "kitchen, sink");
+            assert_eq!(task.due_date.to_string(), "2024-03-01");
+            assert_eq!(task.timezone.as_deref(), Some("Europe/Berlin"));
+            assert_eq!(task.status, Status::InProgress);
+            assert_eq!(user.tasks[&test_task_id].status, Status::Done);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
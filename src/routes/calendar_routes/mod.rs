@@ -0,0 +1,13 @@
+pub mod create_caldav_token;
+pub mod create_feed_token;
+pub mod import_ics;
+pub mod revoke_caldav_token;
+pub mod revoke_feed_token;
+pub mod task_feed;
+
+pub use create_caldav_token::{create_caldav_token, CaldavToken};
+pub use create_feed_token::{create_feed_token, FeedToken};
+pub use import_ics::{import_ics, IcsImportQuery};
+pub use revoke_caldav_token::revoke_caldav_token;
+pub use revoke_feed_token::revoke_feed_token;
+pub use task_feed::{task_feed, FeedQuery};
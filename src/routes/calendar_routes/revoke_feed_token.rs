@@ -0,0 +1,43 @@
+use crate::{schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to disable the user's calendar feed
+/// URL: "/users/{userid}/calendar/token"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/calendar/token",
+    tag = "calendar",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Calendar feed disabled"),
+        (status = 404, description = "User not found or feed not enabled"),
+    )
+)]
+pub async fn revoke_feed_token(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    if user.feed_token.take().is_none() {
+        return HttpResponse::NotFound().body("Calendar feed is not enabled");
+    }
+    save_data(&state_data);
+
+    info!("Calendar feed token revoked for User-Id: {}", user_id);
+    HttpResponse::Ok().body("Calendar feed disabled")
+}
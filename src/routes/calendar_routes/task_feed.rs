@@ -0,0 +1,130 @@
+use crate::{ical::render_calendar, AppState, Task};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    /// Also add an all-day event on each due date, for apps that don't show tasks
+    #[serde(default)]
+    pub events: bool,
+}
+
+/// API endpoint to serve a user's tasks as an iCalendar feed. The token is the
+/// only credential, so the URL can be handed to calendar apps as is.
+/// URL: "/feeds/{token}.ics?events=true"
+#[utoipa::path(
+    get,
+    path = "/feeds/{token}.ics",
+    tag = "calendar",
+    params(("token" = String, Path, description = "Feed token of the user"), FeedQuery),
+    responses(
+        (status = 200, description = "The tasks as VTODOs", content_type = "text/calendar"),
+        (status = 404, description = "Feed not found"),
+    )
+)]
+pub async fn task_feed(
+    state_data: web::Data<AppState>,
+    token: web::Path<String>,
+    query: web::Query<FeedQuery>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let token = token.into_inner();
+    let user = match state_data
+        .users
+        .values()
+        .find(|user| user.feed_token.as_deref() == Some(token.as_str()))
+    {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("Feed not found"),
+    };
+
+    let mut tasks: Vec<Task> = user.tasks.values().cloned().collect();
+    tasks.sort_by_key(|task| (task.due_date, task.rank, task.id));
+    let name = user.display_name.as_deref().unwrap_or(&user.name);
+    let calendar = render_calendar(name, &tasks, &user.workflow(), user.tz(), query.events);
+
+    info!("Serving calendar feed of User-Id: {}", user.id);
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(calendar)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::calendar_routes::{create_feed_token, revoke_feed_token, FeedToken};
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_task_feed() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/users/{user_id}/calendar/token",
+                    web::post().to(create_feed_token),
+                )
+                .route(
+                    "/users/{user_id}/calendar/token",
+                    web::delete().to(revoke_feed_token),
+                )
+                .route("/feeds/{token}.ics", web::get().to(task_feed)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/calendar/token", user_id))
+            .to_request();
+        let feed: FeedToken = test::call_and_read_body_json(&app, req).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{}?events=true", feed.url))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(body.contains(&format!("UID:{}\r\n", test_task_id)));
+        assert!(body.contains("DUE;VALUE=DATE:20000101\r\n"));
+        assert!(body.contains("STATUS:NEEDS-ACTION\r\n"));
+        assert!(body.contains("BEGIN:VEVENT\r\n"));
+
+        // a wrong or revoked token finds nothing
+        let req = test::TestRequest::get()
+            .uri("/feeds/not-a-token.ics")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        let req = test::TestRequest::delete()
+            .uri(&format!("/users/{}/calendar/token", user_id))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+        let req = test::TestRequest::get().uri(&feed.url).to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NOT_FOUND
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
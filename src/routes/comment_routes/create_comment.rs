@@ -0,0 +1,127 @@
+use crate::{
+    routes::comment_routes::find_task,
+    schema::{save_data, Comment},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NewComment {
+    pub author: Uuid,
+    pub body: String,
+}
+
+/// API endpoint to post a comment on a task
+/// URL: "/users/{userid}/tasks/{taskid}/comments/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/{taskId}/comments/create",
+    tag = "comments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = NewComment,
+    responses(
+        (status = 200, description = "Id of the new comment", body = Uuid),
+        (status = 400, description = "Empty comment"),
+        (status = 404, description = "User, task or author not found"),
+    )
+)]
+pub async fn create_comment(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<NewComment>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    if let Some(resp) = find_task(&state_data, user_id, task_id) {
+        return resp;
+    }
+    if !state_data.users.contains_key(&req.author) {
+        return HttpResponse::NotFound().body(format!("Author UserID: {} not found", req.author));
+    }
+    if req.body.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Comment can't be empty");
+    }
+
+    let comment = Comment::new(req.author, &req.body);
+    let comment_id = comment.id;
+    state_data
+        .comments
+        .entry(task_id)
+        .or_default()
+        .push(comment);
+
+    save_data(&state_data);
+
+    info!("Comment: {} posted on Task-Id: {}", comment_id, task_id);
+    HttpResponse::Ok().json(comment_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_create_comment() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/comments/create",
+            web::post().to(create_comment),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/{}/comments/create",
+                user_id, test_task_id
+            ))
+            .set_json(NewComment {
+                author: user_id,
+                body: "Looks good".to_string(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let comment_id: Uuid = test::read_body_json(resp).await;
+
+        // unknown task
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/{}/comments/create",
+                user_id,
+                Uuid::new_v4()
+            ))
+            .set_json(NewComment {
+                author: user_id,
+                body: "Looks good".to_string(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let thread = state_data
+                .comments
+                .remove(&test_task_id)
+                .unwrap_or_default();
+            assert_eq!(thread.len(), 1);
+            assert_eq!(thread[0].id, comment_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
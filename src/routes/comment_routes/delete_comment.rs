@@ -0,0 +1,65 @@
+use crate::{routes::comment_routes::find_task, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeleteComment {
+    pub id: Uuid,
+    pub author: Uuid,
+}
+
+/// API endpoint to delete a comment; only its author may do so
+/// URL: "/users/{userid}/tasks/{taskid}/comments/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/tasks/{taskId}/comments/delete",
+    tag = "comments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = DeleteComment,
+    responses(
+        (status = 200, description = "Id of the deleted comment", body = Uuid),
+        (status = 403, description = "Not the author of the comment"),
+        (status = 404, description = "User, task or comment not found"),
+    )
+)]
+pub async fn delete_comment(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<DeleteComment>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    if let Some(resp) = find_task(&state_data, user_id, task_id) {
+        return resp;
+    }
+
+    let thread = match state_data.comments.get_mut(&task_id) {
+        Some(thread) => thread,
+        None => return HttpResponse::NotFound().body("Comment doesn't exist"),
+    };
+    match thread.iter().position(|comment| comment.id == req.id) {
+        Some(index) if thread[index].author != req.author => {
+            HttpResponse::Forbidden().body("Only the author can delete a comment")
+        }
+        Some(index) => {
+            thread.remove(index);
+            if thread.is_empty() {
+                state_data.comments.remove(&task_id);
+            }
+            save_data(&state_data);
+            info!("Comment: {} deleted", req.id);
+            HttpResponse::Ok().json(req.id)
+        }
+        None => HttpResponse::NotFound().body("Comment doesn't exist"),
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{routes::comment_routes::find_task, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct EditComment {
+    pub id: Uuid,
+    pub author: Uuid,
+    pub body: String,
+}
+
+/// API endpoint to edit a comment; only its author may do so
+/// URL: "/users/{userid}/tasks/{taskid}/comments/edit"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/{taskId}/comments/edit",
+    tag = "comments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = EditComment,
+    responses(
+        (status = 200, description = "Id of the edited comment", body = Uuid),
+        (status = 400, description = "Empty comment"),
+        (status = 403, description = "Not the author of the comment"),
+        (status = 404, description = "User, task or comment not found"),
+    )
+)]
+pub async fn edit_comment(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<EditComment>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    if let Some(resp) = find_task(&state_data, user_id, task_id) {
+        return resp;
+    }
+    if req.body.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Comment can't be empty");
+    }
+
+    let comment = state_data
+        .comments
+        .get_mut(&task_id)
+        .and_then(|thread| thread.iter_mut().find(|comment| comment.id == req.id));
+    match comment {
+        Some(comment) if comment.author != req.author => {
+            HttpResponse::Forbidden().body("Only the author can edit a comment")
+        }
+        Some(comment) => {
+            comment.body = req.body.clone();
+            comment.updated_at = Some(Utc::now());
+            save_data(&state_data);
+            info!("Comment: {} edited", req.id);
+            HttpResponse::Ok().json(req.id)
+        }
+        None => HttpResponse::NotFound().body("Comment doesn't exist"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Comment;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_edit_comment_by_author_only() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let comment = Comment::new(user_id, "typo");
+        let comment_id = comment.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.comments.insert(test_task_id, vec![comment]);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/comments/edit",
+            web::put().to(edit_comment),
+        ))
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/users/{}/tasks/{}/comments/edit",
+                user_id, test_task_id
+            ))
+            .set_json(EditComment {
+                id: comment_id,
+                author: Uuid::new_v4(),
+                body: "hijacked".to_string(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/users/{}/tasks/{}/comments/edit",
+                user_id, test_task_id
+            ))
+            .set_json(EditComment {
+                id: comment_id,
+                author: user_id,
+                body: "fixed".to_string(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let thread = state_data
+                .comments
+                .remove(&test_task_id)
+                .unwrap_or_default();
+            assert_eq!(thread[0].body, "fixed");
+            assert!(thread[0].updated_at.is_some());
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
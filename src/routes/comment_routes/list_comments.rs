@@ -0,0 +1,119 @@
+use crate::{routes::comment_routes::find_task, schema::Comment, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// API endpoint to list a task's comments, oldest first
+/// URL: "/users/{userid}/tasks/{taskid}/comments/list?offset=0&limit=20"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/{taskId}/comments/list",
+    tag = "comments",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path), CommentQuery),
+    responses(
+        (status = 200, description = "Comments oldest first", body = CommentPage),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn list_comments(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<CommentQuery>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    if let Some(resp) = find_task(&state_data, user_id, task_id) {
+        return resp;
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let thread = state_data
+        .comments
+        .get(&task_id)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    HttpResponse::Ok().json(CommentPage {
+        comments: thread.iter().skip(offset).take(limit).cloned().collect(),
+        total: thread.len(),
+        offset,
+        limit,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_comments_paginated() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let thread = (0..5)
+                .map(|i| Comment::new(user_id, &format!("comment-{}", i)))
+                .collect();
+            state_data.comments.insert(test_task_id, thread);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/comments/list",
+            web::get().to(list_comments),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/tasks/{}/comments/list?offset=3&limit=10",
+                user_id, test_task_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let page: CommentPage = test::read_body_json(resp).await;
+        assert_eq!(page.total, 5);
+        assert_eq!(page.comments.len(), 2);
+        assert_eq!(page.comments[0].body, "comment-3");
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.comments.remove(&test_task_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
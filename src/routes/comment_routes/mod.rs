@@ -0,0 +1,22 @@
+pub mod create_comment;
+pub mod delete_comment;
+pub mod edit_comment;
+pub mod list_comments;
+
+pub use create_comment::{create_comment, NewComment};
+pub use delete_comment::{delete_comment, DeleteComment};
+pub use edit_comment::{edit_comment, EditComment};
+pub use list_comments::{list_comments, CommentPage, CommentQuery};
+
+use crate::schema::AppStateData;
+use actix_web::HttpResponse;
+use uuid::Uuid;
+
+/// Ensures `task_id` is one of `user_id`'s tasks, answering 404 otherwise.
+pub(crate) fn find_task(data: &AppStateData, user_id: Uuid, task_id: Uuid) -> Option<HttpResponse> {
+    match data.users.get(&user_id) {
+        Some(user) if user.tasks.contains_key(&task_id) => None,
+        Some(_) => Some(HttpResponse::NotFound().body("Task Doesn't exists")),
+        None => Some(HttpResponse::NotFound().body("User not found")),
+    }
+}
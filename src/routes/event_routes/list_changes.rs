@@ -0,0 +1,188 @@
+use crate::{events::EventScope, events::TaskEvent, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangesQuery {
+    /// Cursor from the previous page; 0 to start from the beginning of the log
+    #[serde(default)]
+    pub since: u64,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChangePage {
+    /// Oldest first
+    pub changes: Vec<TaskEvent>,
+    /// Pass as `since` to fetch what comes after this page
+    pub next: u64,
+    pub has_more: bool,
+}
+
+/// Answered with 410 when `since` is older than the change log reaches back
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChangesExpired {
+    /// Reload the full task list, then poll from this cursor
+    pub last_event_id: u64,
+}
+
+/// API endpoint to poll a user's task changes after a cursor
+/// URL: "/users/{userid}/changes?since=42&limit=100"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/changes",
+    tag = "events",
+    params(("userId" = Uuid, Path), ChangesQuery),
+    responses(
+        (status = 200, body = ChangePage),
+        (status = 404, description = "User not found"),
+        (status = 410, description = "`since` is older than the change log reaches back", body = ChangesExpired),
+    )
+)]
+pub async fn list_changes(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ChangesQuery>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+
+    let mut changes = match state_data.events_since(EventScope::User(user_id), query.since) {
+        Some(changes) => changes,
+        None => {
+            return HttpResponse::Gone().json(ChangesExpired {
+                last_event_id: state_data.last_event_id,
+            })
+        }
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let has_more = changes.len() > limit;
+    changes.truncate(limit);
+    // With nothing more to read the cursor can skip past other users' events
+    let next = match changes.last() {
+        Some(change) if has_more => change.id,
+        _ => state_data.last_event_id,
+    };
+
+    HttpResponse::Ok().json(ChangePage {
+        changes,
+        next,
+        has_more,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::ChangeKind;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_changes() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let since = match app_state.data.lock() {
+            Ok(mut state_data) => {
+                let since = state_data.last_event_id;
+                state_data.publish_task(ChangeKind::Created, test_task_id);
+                state_data.publish_task(ChangeKind::Updated, test_task_id);
+                state_data.publish_deleted(EventScope::User(user_id), test_task_id);
+                since
+            }
+            Err(_) => panic!("poisoned lock"),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/changes", web::get().to(list_changes)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/changes?since={}&limit=2",
+                user_id, since
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: ChangePage = test::read_body_json(resp).await;
+        let kinds: Vec<_> = page.changes.iter().map(|change| change.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Created, ChangeKind::Updated]);
+        assert!(page.has_more);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/changes?since={}", user_id, page.next))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let page: ChangePage = test::read_body_json(resp).await;
+        assert_eq!(page.changes.len(), 1);
+        assert_eq!(page.changes[0].kind, ChangeKind::Deleted);
+        assert!(page.changes[0].task.is_none());
+        assert!(!page.has_more);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/changes?since={}",
+                user_id,
+                u64::MAX / 2
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::GONE);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_list_changes_since_max() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/changes", web::get().to(list_changes)),
+        )
+        .await;
+
+        // a cursor at the very top of the id range is expired, not an overflow
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/changes?since={}", user_id, u64::MAX))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::GONE);
+
+        // and the state lock survives it
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        } else {
+            panic!("poisoned lock");
+        };
+    }
+}
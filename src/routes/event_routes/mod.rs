@@ -0,0 +1,131 @@
+pub mod list_changes;
+pub mod task_socket;
+pub mod task_stream;
+pub mod workspace_socket;
+
+pub use list_changes::{list_changes, ChangePage, ChangesExpired, ChangesQuery};
+pub use task_socket::task_socket;
+pub use task_stream::task_stream;
+pub use workspace_socket::workspace_socket;
+
+use crate::events::{EventScope, TaskEvent};
+use crate::schema::AppStateData;
+use actix_ws::{CloseCode, CloseReason, Message, MessageStream, Session};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use utoipa::{IntoParams, ToSchema};
+
+/// How often the server pings a socket or sends an SSE keep-alive
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// A socket silent for this long is considered dead and closed
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResumeQuery {
+    /// Id of the last event the client saw; missed events are replayed first
+    #[serde(default)]
+    pub last_event_id: Option<u64>,
+}
+
+/// What a stream sends its client
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    Event(Box<TaskEvent>),
+    /// The requested events are no longer available: reload the task list,
+    /// then continue from `last_event_id`
+    Reset {
+        last_event_id: u64,
+    },
+}
+
+/// Subscribes to live events and gathers the replay for `last_event_id`.
+/// Both happen under the caller's state lock, so no event is missed or doubled.
+pub(crate) fn open_stream(
+    data: &AppStateData,
+    scope: EventScope,
+    last_event_id: Option<u64>,
+) -> (Receiver<TaskEvent>, Vec<StreamMessage>) {
+    let events = data.event_bus.subscribe();
+    let backlog = match last_event_id {
+        None => Vec::new(),
+        Some(last_event_id) => match data.events_since(scope, last_event_id) {
+            Some(events) => events
+                .into_iter()
+                .map(|event| StreamMessage::Event(Box::new(event)))
+                .collect(),
+            None => vec![StreamMessage::Reset {
+                last_event_id: data.last_event_id,
+            }],
+        },
+    };
+    (events, backlog)
+}
+
+async fn send(session: &mut Session, message: &StreamMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(json) => session.text(json).await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Feeds `scope`'s events to a socket until either side goes away.
+/// The socket is pinged every `HEARTBEAT_INTERVAL` and dropped after `CLIENT_TIMEOUT`
+/// without any frame from the client.
+pub(crate) async fn pump_socket(
+    mut session: Session,
+    mut stream: MessageStream,
+    mut events: Receiver<TaskEvent>,
+    scope: EventScope,
+    backlog: Vec<StreamMessage>,
+) {
+    for message in &backlog {
+        if !send(&mut session, message).await {
+            return;
+        }
+    }
+
+    let mut heartbeat = actix_web::rt::time::interval(HEARTBEAT_INTERVAL);
+    let mut last_heard = Instant::now();
+    let reason = loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if last_heard.elapsed() > CLIENT_TIMEOUT {
+                    info!("Closing silent socket for {:?}", scope);
+                    break Some(CloseReason::from((CloseCode::Away, "heartbeat timeout")));
+                }
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            }
+            message = stream.recv() => match message {
+                Some(Ok(Message::Ping(bytes))) => {
+                    last_heard = Instant::now();
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(reason))) => break reason,
+                Some(Ok(_)) => last_heard = Instant::now(),
+                Some(Err(_)) | None => break None,
+            },
+            event = events.recv() => match event {
+                Ok(event) if event.scope == scope => {
+                    if !send(&mut session, &StreamMessage::Event(Box::new(event))).await {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                // Too slow to keep up; the client resumes from its last id
+                Err(RecvError::Lagged(_)) => {
+                    break Some(CloseReason::from((CloseCode::Again, "lagged, resume")));
+                }
+                Err(RecvError::Closed) => break None,
+            },
+        }
+    };
+    let _ = session.close(reason).await;
+}
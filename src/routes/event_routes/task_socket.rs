@@ -0,0 +1,135 @@
+use crate::{
+    events::EventScope,
+    routes::event_routes::{open_stream, pump_socket, ResumeQuery},
+    AppState,
+};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to stream a user's task events over a WebSocket
+/// URL: "/users/{userid}/tasks/ws?last_event_id=42"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/ws",
+    tag = "events",
+    params(("userId" = Uuid, Path), ResumeQuery),
+    responses(
+        (status = 101, description = "WebSocket sending one `StreamMessage` per text frame"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn task_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ResumeQuery>,
+) -> impl Responder {
+    let user_id = user_id.into_inner();
+    let scope = EventScope::User(user_id);
+    let (events, backlog) = match state_data.data.lock() {
+        Ok(data) if data.users.contains_key(&user_id) => {
+            open_stream(&data, scope, query.last_event_id)
+        }
+        Ok(_) => return HttpResponse::NotFound().body("User not found"),
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (resp, session, stream) = match actix_ws::handle(&req, body) {
+        Ok(socket) => socket,
+        Err(err) => return err.error_response(),
+    };
+    actix_web::rt::spawn(pump_socket(session, stream, events, scope, backlog));
+
+    info!("Task socket opened for UserID: {}", user_id);
+    resp
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::ChangeKind;
+    use crate::routes::event_routes::StreamMessage;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{App, HttpServer};
+    use futures_util::StreamExt;
+    use tokio_tungstenite::{connect_async, tungstenite};
+
+    async fn next_message<S>(socket: &mut S) -> StreamMessage
+    where
+        S: futures_util::Stream<Item = Result<tungstenite::Message, tungstenite::Error>> + Unpin,
+    {
+        loop {
+            match socket.next().await {
+                Some(Ok(tungstenite::Message::Text(text))) => {
+                    return serde_json::from_str(&text).expect("invalid message")
+                }
+                Some(Ok(_)) => continue,
+                other => panic!("socket ended: {:?}", other),
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_task_socket_pushes_and_resumes() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let state = app_state.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(state.clone())
+                .route("/users/{user_id}/tasks/ws", web::get().to(task_socket))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .expect("failed to bind");
+        let url = format!("ws://{}/users/{}/tasks/ws", server.addrs()[0], user_id);
+        actix_web::rt::spawn(server.run());
+
+        let (mut socket, _) = connect_async(url.as_str()).await.expect("connect failed");
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.publish_task(ChangeKind::Updated, test_task_id);
+        };
+        let event_id = match next_message(&mut socket).await {
+            StreamMessage::Event(event) => {
+                assert_eq!(event.task_id, test_task_id);
+                assert_eq!(event.kind, ChangeKind::Updated);
+                assert_eq!(event.scope, EventScope::User(user_id));
+                event.id
+            }
+            other => panic!("unexpected message: {:?}", other),
+        };
+        drop(socket);
+
+        // reconnecting from before that event replays it
+        let resume = format!("{}?last_event_id={}", url, event_id - 1);
+        let (mut socket, _) = connect_async(resume.as_str())
+            .await
+            .expect("connect failed");
+        match next_message(&mut socket).await {
+            StreamMessage::Event(event) => assert_eq!(event.id, event_id),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        // an id the server never issued asks the client to start over
+        let resume = format!("{}?last_event_id={}", url, u64::MAX / 2);
+        let (mut socket, _) = connect_async(resume.as_str())
+            .await
+            .expect("connect failed");
+        assert!(matches!(
+            next_message(&mut socket).await,
+            StreamMessage::Reset { .. }
+        ));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
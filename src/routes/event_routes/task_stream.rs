@@ -0,0 +1,195 @@
+use crate::{
+    events::{EventScope, TaskEvent},
+    routes::event_routes::{open_stream, ResumeQuery, StreamMessage, HEARTBEAT_INTERVAL},
+    AppState,
+};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use futures_util::stream;
+use log::{error, info};
+use std::collections::VecDeque;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+/// One Server-Sent Events frame; the event id doubles as the resume cursor
+fn frame(message: &StreamMessage) -> web::Bytes {
+    let json = serde_json::to_string(message).unwrap_or_default();
+    let text = match message {
+        StreamMessage::Event(event) => format!("id: {}\nevent: task\ndata: {}\n\n", event.id, json),
+        StreamMessage::Reset { last_event_id } => {
+            format!("id: {}\nevent: reset\ndata: {}\n\n", last_event_id, json)
+        }
+    };
+    web::Bytes::from(text)
+}
+
+struct Feed {
+    backlog: VecDeque<StreamMessage>,
+    events: Receiver<TaskEvent>,
+    scope: EventScope,
+    heartbeat: actix_web::rt::time::Interval,
+}
+
+/// API endpoint to stream a user's task events as Server-Sent Events.
+/// Resumes after the `Last-Event-ID` header (or `last_event_id` query) when given.
+/// URL: "/users/{userid}/tasks/events"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/events",
+    tag = "events",
+    params(("userId" = Uuid, Path), ResumeQuery, ("Last-Event-ID" = Option<u64>, Header, description = "Takes precedence over `last_event_id`")),
+    responses(
+        (status = 200, description = "One `StreamMessage` per event", body = StreamMessage, content_type = "text/event-stream"),
+        (status = 400, description = "Invalid `Last-Event-ID`"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn task_stream(
+    req: HttpRequest,
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ResumeQuery>,
+) -> impl Responder {
+    let last_event_id = match req.headers().get("Last-Event-ID") {
+        Some(value) => match value.to_str().ok().and_then(|id| id.trim().parse().ok()) {
+            Some(id) => Some(id),
+            None => return HttpResponse::BadRequest().body("Invalid Last-Event-ID"),
+        },
+        None => query.last_event_id,
+    };
+
+    let user_id = user_id.into_inner();
+    let scope = EventScope::User(user_id);
+    let (events, backlog) = match state_data.data.lock() {
+        Ok(data) if data.users.contains_key(&user_id) => open_stream(&data, scope, last_event_id),
+        Ok(_) => return HttpResponse::NotFound().body("User not found"),
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let feed = Feed {
+        backlog: backlog.into(),
+        events,
+        scope,
+        heartbeat: actix_web::rt::time::interval(HEARTBEAT_INTERVAL),
+    };
+    // Ends when the client falls too far behind; EventSource then reconnects
+    // with its Last-Event-ID and picks up from the log.
+    let body = stream::unfold(feed, |mut feed| async move {
+        if let Some(message) = feed.backlog.pop_front() {
+            return Some((Ok::<_, actix_web::Error>(frame(&message)), feed));
+        }
+        loop {
+            tokio::select! {
+                _ = feed.heartbeat.tick() => {
+                    return Some((Ok(web::Bytes::from_static(b": keep-alive\n\n")), feed));
+                }
+                event = feed.events.recv() => match event {
+                    Ok(event) if event.scope == feed.scope => {
+                        let message = StreamMessage::Event(Box::new(event));
+                        return Some((Ok(frame(&message)), feed));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => return None,
+                },
+            }
+        }
+    });
+
+    info!("Event stream opened for UserID: {}", user_id);
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::ChangeKind;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::body::{BoxBody, MessageBody};
+    use actix_web::{http::StatusCode, test, App};
+    use std::pin::Pin;
+
+    /// Next non-heartbeat frame of an event stream
+    async fn next_frame(body: &mut BoxBody) -> String {
+        loop {
+            let chunk = std::future::poll_fn(|cx| Pin::new(&mut *body).poll_next(cx))
+                .await
+                .expect("stream ended")
+                .expect("stream failed");
+            let text = String::from_utf8(chunk.to_vec()).expect("not utf-8");
+            if !text.starts_with(':') {
+                return text;
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_task_stream_resumes_from_last_event_id() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let first_id = match app_state.data.lock() {
+            Ok(mut state_data) => {
+                state_data.publish_task(ChangeKind::Updated, test_task_id);
+                state_data.last_event_id
+            }
+            Err(_) => panic!("poisoned lock"),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/events", web::get().to(task_stream)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/events", user_id))
+            .insert_header(("Last-Event-ID", (first_id - 1).to_string()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        let mut body = resp.into_body();
+
+        // the missed event is replayed first
+        let replayed = next_frame(&mut body).await;
+        assert!(replayed.starts_with(&format!("id: {}\nevent: task\n", first_id)));
+
+        // then live events follow with increasing ids
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.publish_deleted(EventScope::User(user_id), test_task_id);
+        };
+        let live = next_frame(&mut body).await;
+        let data = live
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .expect("no data line");
+        match serde_json::from_str(data).expect("invalid data") {
+            StreamMessage::Event(event) => {
+                assert!(event.id > first_id);
+                assert_eq!(event.kind, ChangeKind::Deleted);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/events", user_id))
+            .insert_header(("Last-Event-ID", "yesterday"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
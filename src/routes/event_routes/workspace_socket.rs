@@ -0,0 +1,117 @@
+use crate::{
+    events::EventScope,
+    routes::{
+        event_routes::{open_stream, pump_socket, ResumeQuery},
+        workspace_routes::authorize,
+    },
+    schema::Role,
+    AppState,
+};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to stream a workspace's task events over a WebSocket; any member may listen
+/// URL: "/users/{userid}/workspaces/{workspaceid}/ws?last_event_id=42"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/workspaces/{workspaceId}/ws",
+    tag = "events",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path), ResumeQuery),
+    responses(
+        (status = 101, description = "WebSocket sending one `StreamMessage` per text frame"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn workspace_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<ResumeQuery>,
+) -> impl Responder {
+    let (user_id, workspace_id) = path.into_inner();
+    let scope = EventScope::Workspace(workspace_id);
+    let (events, backlog) = match state_data.data.lock() {
+        Ok(data) => {
+            if let Err(err) = authorize(&data, workspace_id, user_id, Role::Viewer) {
+                return err.into();
+            }
+            open_stream(&data, scope, query.last_event_id)
+        }
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (resp, session, stream) = match actix_ws::handle(&req, body) {
+        Ok(socket) => socket,
+        Err(err) => return err.error_response(),
+    };
+    actix_web::rt::spawn(pump_socket(session, stream, events, scope, backlog));
+
+    info!(
+        "Task socket opened for WorkspaceID: {} by UserID: {}",
+        workspace_id, user_id
+    );
+    resp
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{save_data, User, Workspace};
+    use crate::test_utils::init_app_state;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_workspace_socket_requires_membership() {
+        let app_state = init_app_state();
+        let owner = User::new("socket-owner");
+        let outsider = User::new("socket-outsider");
+        let workspace = Workspace::new("Team", owner.id);
+        let (owner_id, outsider_id, workspace_id) = (owner.id, outsider.id, workspace.id);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.insert(owner_id, owner);
+            state_data.users.insert(outsider_id, outsider);
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/{workspace_id}/ws",
+            web::get().to(workspace_socket),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/ws",
+                outsider_id, workspace_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/ws",
+                owner_id, workspace_id
+            ))
+            .insert_header(("Connection", "Upgrade"))
+            .insert_header(("Upgrade", "websocket"))
+            .insert_header(("Sec-WebSocket-Version", "13"))
+            .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&outsider_id);
+            save_data(&state_data);
+        };
+    }
+}
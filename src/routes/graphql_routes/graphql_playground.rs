@@ -0,0 +1,11 @@
+use actix_web::{http::header::ContentType, HttpResponse, Responder};
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+
+/// API endpoint to serve an in-browser IDE for the GraphQL schema
+/// URL: "/graphql"
+pub async fn graphql_playground() -> impl Responder {
+    let config = GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/graphql/ws");
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(playground_source(config))
+}
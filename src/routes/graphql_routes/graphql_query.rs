@@ -0,0 +1,69 @@
+use crate::graphql::TaskSchema;
+use actix_web::{web, HttpResponse, Responder};
+
+/// API endpoint to run a GraphQL query or mutation
+/// URL: "/graphql"
+pub async fn graphql_query(
+    schema: web::Data<TaskSchema>,
+    req: web::Json<async_graphql::Request>,
+) -> impl Responder {
+    // Errors are part of the GraphQL response, so the status is always 200
+    HttpResponse::Ok().json(schema.execute(req.into_inner()).await)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graphql::build_schema;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_graphql_query() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(build_schema(app_state.clone())))
+                .route("/graphql", web::post().to(graphql_query)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(json!({
+                "query": "query($id: UUID!) { user(id: $id) { id tasks { id title overdue } } }",
+                "variables": { "id": user_id },
+            }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(
+            resp["data"]["user"],
+            json!({
+                "id": user_id,
+                "tasks": [{ "id": test_task_id, "title": "sample-title", "overdue": true }],
+            })
+        );
+
+        // unknown users are errors in the body, like any GraphQL error
+        let req = test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(json!({
+                "query": "query($id: UUID!) { tasks(userId: $id) { id } }",
+                "variables": { "id": Uuid::nil() },
+            }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["errors"][0]["message"], "User not found");
+        assert_eq!(resp["errors"][0]["extensions"]["status"], 404);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,204 @@
+use crate::graphql::TaskSchema;
+use actix_web::{
+    http::header::{self, HeaderValue},
+    web, HttpRequest, HttpResponse, Responder,
+};
+use actix_ws::{CloseReason, Message, MessageStream, Session};
+use async_graphql::http::{WebSocket, WebSocketProtocols, WsMessage};
+use futures_util::{stream, StreamExt};
+use log::info;
+
+/// The first GraphQL-over-WebSocket protocol the client offers that the server speaks
+fn protocol(req: &HttpRequest) -> Option<WebSocketProtocols> {
+    req.headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|protocol| protocol.trim().parse().ok())
+}
+
+/// Passes the client's frames to the GraphQL protocol handler and its replies
+/// back, until either side closes
+async fn serve(
+    schema: TaskSchema,
+    protocol: WebSocketProtocols,
+    mut session: Session,
+    stream: MessageStream,
+) {
+    let frames = stream::unfold(
+        (stream, session.clone()),
+        |(mut stream, mut session)| async move {
+            loop {
+                match stream.recv().await? {
+                    Ok(Message::Text(text)) => return Some((text.into_bytes(), (stream, session))),
+                    Ok(Message::Binary(bytes)) => return Some((bytes, (stream, session))),
+                    Ok(Message::Ping(bytes)) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return None;
+                        }
+                    }
+                    Ok(Message::Close(_)) | Err(_) => return None,
+                    Ok(_) => {}
+                }
+            }
+        },
+    );
+    let mut replies = std::pin::pin!(WebSocket::new(schema, frames, protocol));
+    let reason = loop {
+        match replies.next().await {
+            Some(WsMessage::Text(text)) => {
+                if session.text(text).await.is_err() {
+                    return;
+                }
+            }
+            Some(WsMessage::Close(code, description)) => {
+                break Some(CloseReason {
+                    code: code.into(),
+                    description: Some(description),
+                })
+            }
+            None => break None,
+        }
+    };
+    let _ = session.close(reason).await;
+}
+
+/// API endpoint to run GraphQL subscriptions over a WebSocket, speaking either
+/// `graphql-transport-ws` or the older `graphql-ws` protocol
+/// URL: "/graphql/ws"
+pub async fn graphql_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    schema: web::Data<TaskSchema>,
+) -> impl Responder {
+    let Some(protocol) = protocol(&req) else {
+        return HttpResponse::BadRequest().body("Unsupported Sec-WebSocket-Protocol");
+    };
+
+    let (mut resp, session, stream) = match actix_ws::handle(&req, body) {
+        Ok(socket) => socket,
+        Err(err) => return err.error_response(),
+    };
+    resp.headers_mut().insert(
+        header::SEC_WEBSOCKET_PROTOCOL,
+        HeaderValue::from_static(protocol.sec_websocket_protocol()),
+    );
+    actix_web::rt::spawn(serve(schema.as_ref().clone(), protocol, session, stream));
+
+    info!("GraphQL socket opened");
+    resp
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::ChangeKind;
+    use crate::graphql::build_schema;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App, HttpServer};
+    use futures_util::SinkExt;
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest, Message as WsFrame},
+    };
+
+    async fn next_json<S>(socket: &mut S) -> Option<Value>
+    where
+        S: futures_util::Stream<Item = Result<WsFrame, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        loop {
+            match socket.next().await? {
+                Ok(WsFrame::Text(text)) => return serde_json::from_str(&text).ok(),
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_task_changes_subscription() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let schema = web::Data::new(build_schema(app_state.clone()));
+
+        // plain WebSocket clients without a GraphQL protocol are turned away
+        let app = test::init_service(
+            App::new()
+                .app_data(schema.clone())
+                .route("/graphql/ws", web::get().to(graphql_socket)),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/graphql/ws").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(schema.clone())
+                .route("/graphql/ws", web::get().to(graphql_socket))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .expect("failed to bind");
+        let url = format!("ws://{}/graphql/ws", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+
+        let mut request = url.into_client_request().unwrap();
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            "graphql-transport-ws".parse().unwrap(),
+        );
+        let (mut socket, _) = connect_async(request).await.expect("connect failed");
+        let frame = |message: Value| WsFrame::Text(message.to_string());
+        socket
+            .send(frame(json!({ "type": "connection_init" })))
+            .await
+            .unwrap();
+        assert_eq!(
+            next_json(&mut socket).await.unwrap()["type"],
+            "connection_ack"
+        );
+        socket
+            .send(frame(json!({
+                "id": "1",
+                "type": "subscribe",
+                "payload": {
+                    "query": "subscription($id: UUID!) { taskChanges(userId: $id) { kind taskId task { title } } }",
+                    "variables": { "id": user_id },
+                },
+            })))
+            .await
+            .unwrap();
+
+        // the subscription starts listening shortly after the message arrives
+        let mut change = None;
+        for _ in 0..50 {
+            if let Ok(mut state_data) = app_state.data.lock() {
+                state_data.publish_task(ChangeKind::Updated, test_task_id);
+            };
+            let next = next_json(&mut socket);
+            if let Ok(message) =
+                actix_web::rt::time::timeout(Duration::from_millis(100), next).await
+            {
+                change = message;
+                break;
+            }
+        }
+        let change = change.expect("no change received");
+        assert_eq!(change["type"], "next");
+        assert_eq!(
+            change["payload"]["data"]["taskChanges"],
+            json!({ "kind": "UPDATED", "taskId": test_task_id, "task": { "title": "sample-title" } })
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,7 @@
+pub mod graphql_playground;
+pub mod graphql_query;
+pub mod graphql_socket;
+
+pub use graphql_playground::graphql_playground;
+pub use graphql_query::graphql_query;
+pub use graphql_socket::graphql_socket;
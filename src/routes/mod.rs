@@ -1,5 +1,33 @@
+pub mod attachment_routes;
+pub mod board_routes;
+pub mod caldav_routes;
+pub mod calendar_routes;
+pub mod comment_routes;
+pub mod event_routes;
+pub mod graphql_routes;
+pub mod project_routes;
+pub mod reminder_routes;
+pub mod sync_routes;
 pub mod task_routes;
+pub mod transfer_routes;
 pub mod user_routes;
+pub mod webhook_routes;
+pub mod workflow_routes;
+pub mod workspace_routes;
 
+pub use attachment_routes::*;
+pub use board_routes::*;
+pub use caldav_routes::*;
+pub use calendar_routes::*;
+pub use comment_routes::*;
+pub use event_routes::*;
+pub use graphql_routes::*;
+pub use project_routes::*;
+pub use reminder_routes::*;
+pub use sync_routes::*;
 pub use task_routes::*;
-pub use user_routes::*;
\ No newline at end of file
+pub use transfer_routes::*;
+pub use user_routes::*;
+pub use webhook_routes::*;
+pub use workflow_routes::*;
+pub use workspace_routes::*;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use crate::{
+    schema::{is_valid_colour, save_data, Project},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NewProject {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub colour: Option<String>,
+}
+
+/// API endpoint to create a project
+/// URL: "/users/{userid}/projects/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/projects/create",
+    tag = "projects",
+    params(("userId" = Uuid, Path)),
+    request_body = NewProject,
+    responses(
+        (status = 200, description = "Id of the new project", body = Uuid),
+        (status = 400, description = "Empty name or invalid colour"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn create_project(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<NewProject>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    if req.name.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Project name can't be empty");
+    }
+    if let Some(colour) = &req.colour {
+        if !is_valid_colour(colour) {
+            return HttpResponse::BadRequest().body(format!("Invalid colour: {}", colour));
+        }
+    }
+
+    let user = match state_data.users.get_mut(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let project = Project::new(req.name.trim(), &req.description, req.colour.clone());
+    let project_id = project.id;
+    user.projects.insert(project_id, project);
+    save_data(&state_data);
+
+    info!("Project created successfully with ID: {}", project_id);
+    HttpResponse::Ok().json(project_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_create_project() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/projects/create",
+            web::post().to(create_project),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/projects/create", user_id))
+            .set_json(NewProject {
+                name: "Website".to_string(),
+                description: "Relaunch".to_string(),
+                colour: Some("#1E90FF".to_string()),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let project_id: Uuid = test::read_body_json(resp).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/projects/create", user_id))
+            .set_json(NewProject {
+                name: "Website".to_string(),
+                description: String::new(),
+                colour: Some("blue".to_string()),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get(&user_id) {
+                assert!(user.projects.contains_key(&project_id));
+            }
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
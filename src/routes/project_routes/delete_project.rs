@@ -0,0 +1,187 @@
+use crate::{events::ChangeKind, routes::task_routes::remove_task, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeleteProject {
+    pub id: Uuid,
+    /// Delete the project's tasks too; otherwise they are kept without a project
+    #[serde(default)]
+    pub delete_tasks: bool,
+}
+
+/// API endpoint to delete a project
+/// URL: "/users/{userid}/projects/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/projects/delete",
+    tag = "projects",
+    params(("userId" = Uuid, Path)),
+    request_body = DeleteProject,
+    responses(
+        (status = 200, description = "Id of the deleted project", body = Uuid),
+        (status = 404, description = "User or project not found"),
+    )
+)]
+pub async fn delete_project(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<DeleteProject>,
+) -> impl Responder {
+    let config = &state_data.config;
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    if user.projects.remove(&req.id).is_none() {
+        return HttpResponse::NotFound().body("Project not found");
+    }
+
+    let mut deleted = Vec::new();
+    let mut detached = Vec::new();
+    for task in user.tasks.values_mut() {
+        if task.project_id != Some(req.id) {
+            continue;
+        }
+        if req.delete_tasks {
+            deleted.push(task.id);
+        } else {
+            task.project_id = None;
+            detached.push(task.id);
+        }
+    }
+    for task_id in &deleted {
+        // Same cleanup and announcements as deleting each task on its own
+        if let Err(err) = remove_task(&mut state_data, &config.blob_dir(), user_id, *task_id) {
+            warn!("Failed to delete Task-id: {}: {:?}", task_id, err);
+        }
+    }
+    for task_id in detached {
+        state_data.publish_task(ChangeKind::Updated, task_id);
+    }
+    save_data(&state_data);
+
+    info!(
+        "Project: {} deleted along with {} task(s)",
+        req.id,
+        deleted.len()
+    );
+    HttpResponse::Ok().json(req.id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Project;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{EventKind, Subscription};
+    use actix_web::{http::StatusCode, test, App};
+    use std::collections::HashSet;
+
+    #[actix_web::test]
+    async fn test_delete_project_keeps_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let project = Project::new("Website", "", None);
+        let project_id = project.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.projects.insert(project_id, project);
+                if let Some(task) = user.tasks.get_mut(&test_task_id) {
+                    task.project_id = Some(project_id);
+                }
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/projects/delete",
+            web::delete().to(delete_project),
+        ))
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/users/{}/projects/delete", user_id))
+            .set_json(DeleteProject {
+                id: project_id,
+                delete_tasks: false,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&user_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.project_id.is_none()));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_delete_project_with_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let project = Project::new("Website", "", None);
+        let project_id = project.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.projects.insert(project_id, project);
+                user.webhooks.push(Subscription::new(
+                    "http://localhost/hook",
+                    HashSet::from([EventKind::TaskDeleted]),
+                    None,
+                ));
+                if let Some(task) = user.tasks.get_mut(&test_task_id) {
+                    task.project_id = Some(project_id);
+                }
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/projects/delete",
+            web::delete().to(delete_project),
+        ))
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/users/{}/projects/delete", user_id))
+            .set_json(DeleteProject {
+                id: project_id,
+                delete_tasks: true,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert!(!state_data.users[&user_id].tasks.contains_key(&test_task_id));
+            // announced like any other deletion
+            assert!(state_data.webhook_queue.iter().any(|delivery| {
+                delivery.user_id == user_id && delivery.event.event == EventKind::TaskDeleted
+            }));
+            assert!(state_data.event_log.iter().any(|event| {
+                event.task_id == test_task_id && event.kind == ChangeKind::Deleted
+            }));
+            state_data.users.remove(&user_id);
+            state_data
+                .webhook_queue
+                .retain(|delivery| delivery.user_id != user_id);
+            save_data(&state_data);
+        };
+    }
+}
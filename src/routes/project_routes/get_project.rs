@@ -0,0 +1,42 @@
+use crate::{schema::Project, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint to fetch a project
+/// URL: "/users/{userid}/projects/{projectid}"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/projects/{projectId}",
+    tag = "projects",
+    params(("userId" = Uuid, Path), ("projectId" = Uuid, Path)),
+    responses(
+        (status = 200, body = Project),
+        (status = 404, description = "User or project not found"),
+    )
+)]
+pub async fn get_project(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, project_id) = path.into_inner();
+    match state_data
+        .users
+        .get(&user_id)
+        .and_then(|user| user.projects.get(&project_id))
+    {
+        Some(project) => HttpResponse::Ok().json(project),
+        None => HttpResponse::NotFound().body(format!(
+            "ProjectId: {} or UserId: {} not found",
+            project_id, user_id
+        )),
+    }
+}
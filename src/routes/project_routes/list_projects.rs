@@ -0,0 +1,59 @@
+use crate::{schema::Project, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectListQuery {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProjectList {
+    pub projects: Vec<Project>,
+}
+
+/// API endpoint to list a user's projects, archived ones only on request
+/// URL: "/users/{userid}/projects/list?include_archived=false"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/projects/list",
+    tag = "projects",
+    params(("userId" = Uuid, Path), ProjectListQuery),
+    responses(
+        (status = 200, body = ProjectList),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn list_projects(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ProjectListQuery>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user = match state_data.users.get(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let mut projects: Vec<Project> = user
+        .projects
+        .values()
+        .filter(|project| query.include_archived || !project.archived)
+        .cloned()
+        .collect();
+    projects.sort_by_key(|project| project.name.to_lowercase());
+
+    HttpResponse::Ok().json(ProjectList { projects })
+}
@@ -0,0 +1,15 @@
+pub mod create_project;
+pub mod delete_project;
+pub mod get_project;
+pub mod list_projects;
+pub mod move_to_project;
+pub mod project_summary;
+pub mod update_project;
+
+pub use create_project::{create_project, NewProject};
+pub use delete_project::{delete_project, DeleteProject};
+pub use get_project::get_project;
+pub use list_projects::{list_projects, ProjectList, ProjectListQuery};
+pub use move_to_project::{move_to_project, MoveToProject};
+pub use project_summary::{project_summary, ProjectSummary};
+pub use update_project::{update_project, UpdateProject};
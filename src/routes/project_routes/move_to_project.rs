@@ -0,0 +1,128 @@
+use crate::{events::ChangeKind, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct MoveToProject {
+    pub task_id: Uuid,
+    /// Target project; `null` takes the task out of its project
+    pub project_id: Option<Uuid>,
+}
+
+/// API endpoint to move a task into another project
+/// URL: "/users/{userid}/projects/move-task"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/projects/move-task",
+    tag = "projects",
+    params(("userId" = Uuid, Path)),
+    request_body = MoveToProject,
+    responses(
+        (status = 200, description = "Id of the moved task", body = Uuid),
+        (status = 404, description = "User, task or project not found"),
+        (status = 409, description = "Project is archived"),
+    )
+)]
+pub async fn move_to_project(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<MoveToProject>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user = match state_data.users.get_mut(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let MoveToProject {
+        task_id,
+        project_id,
+    } = req.into_inner();
+    if let Some(project_id) = project_id {
+        match user.projects.get(&project_id) {
+            Some(project) if project.archived => {
+                return HttpResponse::Conflict().body("Project is archived")
+            }
+            Some(_) => {}
+            None => return HttpResponse::NotFound().body("Project not found"),
+        }
+    }
+
+    match user.tasks.get_mut(&task_id) {
+        Some(task) => {
+            task.project_id = project_id;
+            state_data.publish_task(ChangeKind::Updated, task_id);
+            save_data(&state_data);
+            info!("Task-Id: {} moved to project: {:?}", task_id, project_id);
+            HttpResponse::Ok().json(task_id)
+        }
+        None => {
+            warn!("Task-id: {} doesn't exists", task_id);
+            HttpResponse::NotFound().body("Task Doesn't exists")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Project;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_move_to_project() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let active = Project::new("Active", "", None);
+        let mut archived = Project::new("Archived", "", None);
+        archived.archived = true;
+        let (active_id, archived_id) = (active.id, archived.id);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.projects.insert(active_id, active);
+                user.projects.insert(archived_id, archived);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/projects/move-task",
+            web::put().to(move_to_project),
+        ))
+        .await;
+
+        let move_req = |project_id: Uuid| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/projects/move-task", user_id))
+                .set_json(MoveToProject {
+                    task_id: test_task_id,
+                    project_id: Some(project_id),
+                })
+                .to_request()
+        };
+        let resp = test::call_service(&app, move_req(active_id)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, move_req(archived_id)).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&user_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.project_id == Some(active_id)));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
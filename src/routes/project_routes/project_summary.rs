@@ -0,0 +1,143 @@
+use crate::{schema::Status, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Progress of a project: task counts per status and how many are overdue
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProjectSummary {
+    pub project_id: Uuid,
+    pub total: usize,
+    pub by_status: HashMap<Status, usize>,
+    /// Tasks in terminal states
+    pub completed: usize,
+    pub overdue: usize,
+}
+
+/// API endpoint to summarise a project's progress
+/// URL: "/users/{userid}/projects/{projectid}/summary"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/projects/{projectId}/summary",
+    tag = "projects",
+    params(("userId" = Uuid, Path), ("projectId" = Uuid, Path)),
+    responses(
+        (status = 200, body = ProjectSummary),
+        (status = 404, description = "User or project not found"),
+    )
+)]
+pub async fn project_summary(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, project_id) = path.into_inner();
+    let user = match state_data.users.get(&user_id) {
+        Some(user) if user.projects.contains_key(&project_id) => user,
+        Some(_) => return HttpResponse::NotFound().body("Project not found"),
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let workflow = user.workflow();
+    let (now, tz) = (Utc::now(), user.tz());
+    // Every workflow state is reported, even when empty
+    let mut summary = ProjectSummary {
+        project_id,
+        total: 0,
+        by_status: workflow
+            .states
+            .iter()
+            .map(|state| (state.status.clone(), 0))
+            .collect(),
+        completed: 0,
+        overdue: 0,
+    };
+    for task in user
+        .tasks
+        .values()
+        .filter(|task| task.project_id == Some(project_id))
+    {
+        summary.total += 1;
+        *summary.by_status.entry(task.status.clone()).or_default() += 1;
+        if workflow.is_terminal(&task.status) {
+            summary.completed += 1;
+        }
+        if task.is_overdue(now, tz, &workflow) {
+            summary.overdue += 1;
+        }
+    }
+
+    HttpResponse::Ok().json(summary)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{save_data, Project, Task};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::Days;
+
+    #[actix_web::test]
+    async fn test_project_summary() {
+        let app_state = init_app_state();
+        // the sample task is due in 2000, hence overdue
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let project = Project::new("Website", "", None);
+        let project_id = project.id;
+        let mut done = Task::new("done", "", Utc::now().date_naive() - Days::new(3));
+        done.status = Status::Done;
+        done.project_id = Some(project_id);
+        let mut future = Task::new("future", "", Utc::now().date_naive() + Days::new(3));
+        future.project_id = Some(project_id);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.projects.insert(project_id, project);
+                if let Some(task) = user.tasks.get_mut(&test_task_id) {
+                    task.project_id = Some(project_id);
+                }
+                user.tasks.insert(done.id, done);
+                user.tasks.insert(future.id, future);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/projects/{project_id}/summary",
+            web::get().to(project_summary),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/projects/{}/summary",
+                user_id, project_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let summary: ProjectSummary = test::read_body_json(resp).await;
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.by_status[&Status::ToDo], 2);
+        assert_eq!(summary.by_status[&Status::InProgress], 0);
+        assert_eq!(summary.by_status[&Status::Done], 1);
+        assert_eq!(summary.completed, 1);
+        assert_eq!(summary.overdue, 1);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
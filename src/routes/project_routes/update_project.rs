@@ -0,0 +1,91 @@
+use crate::{
+    schema::{is_valid_colour, save_data, Project},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Project fields to change; omitted fields are left untouched
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct UpdateProject {
+    pub id: Uuid,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub colour: Option<String>,
+    #[serde(default)]
+    pub archived: Option<bool>,
+}
+
+/// API endpoint to edit or (un)archive a project
+/// URL: "/users/{userid}/projects/update"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/projects/update",
+    tag = "projects",
+    params(("userId" = Uuid, Path)),
+    request_body = UpdateProject,
+    responses(
+        (status = 200, description = "The updated project", body = Project),
+        (status = 400, description = "Empty name or invalid colour"),
+        (status = 404, description = "User or project not found"),
+    )
+)]
+pub async fn update_project(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<UpdateProject>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let UpdateProject {
+        id: project_id,
+        name,
+        description,
+        colour,
+        archived,
+    } = req.into_inner();
+    if name.as_ref().is_some_and(|name| name.trim().is_empty()) {
+        return HttpResponse::BadRequest().body("Project name can't be empty");
+    }
+    if let Some(colour) = colour.as_ref().filter(|colour| !is_valid_colour(colour)) {
+        return HttpResponse::BadRequest().body(format!("Invalid colour: {}", colour));
+    }
+
+    let project = match state_data
+        .users
+        .get_mut(&user_id.into_inner())
+        .and_then(|user| user.projects.get_mut(&project_id))
+    {
+        Some(project) => project,
+        None => return HttpResponse::NotFound().body("Project not found"),
+    };
+    if let Some(name) = name {
+        project.name = name.trim().to_string();
+    }
+    if let Some(description) = description {
+        project.description = description;
+    }
+    if colour.is_some() {
+        project.colour = colour;
+    }
+    if let Some(archived) = archived {
+        project.archived = archived;
+    }
+    let project = project.clone();
+    save_data(&state_data);
+
+    info!("Project: {} updated", project_id);
+    HttpResponse::Ok().json(project)
+}
@@ -0,0 +1,126 @@
+use crate::{
+    events::ChangeKind,
+    reminders::{Channel, Reminder, ReminderTrigger},
+    schema::save_data,
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NewReminder {
+    pub trigger: ReminderTrigger,
+    #[serde(default = "default_channel")]
+    pub channel: Channel,
+}
+
+fn default_channel() -> Channel {
+    Channel::Log
+}
+
+/// API endpoint to add a reminder to a task
+/// URL: "/users/{userid}/tasks/{taskid}/reminders/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/{taskId}/reminders/create",
+    tag = "reminders",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = NewReminder,
+    responses(
+        (status = 200, description = "Id of the new reminder", body = Uuid),
+        (status = 400, description = "Invalid channel"),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn create_reminder(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<NewReminder>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    if let Err(err) = req.channel.validate() {
+        return HttpResponse::BadRequest().body(err);
+    }
+
+    let (user_id, task_id) = path.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let task = match user.tasks.get_mut(&task_id) {
+        Some(task) => task,
+        None => return HttpResponse::NotFound().body("Task Doesn't exists"),
+    };
+
+    let NewReminder { trigger, channel } = req.into_inner();
+    let reminder = Reminder::new(trigger, channel);
+    let reminder_id = reminder.id;
+    task.reminders.push(reminder);
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+
+    info!("Reminder: {} set on Task-Id: {}", reminder_id, task_id);
+    HttpResponse::Ok().json(reminder_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_create_reminder() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/reminders/create",
+            web::post().to(create_reminder),
+        ))
+        .await;
+        let uri = format!("/users/{}/tasks/{}/reminders/create", user_id, test_task_id);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(serde_json::json!({
+                "trigger": { "before_due": 30 },
+                "channel": { "type": "webhook", "url": "http://localhost:9000/hook" }
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let reminder_id: Uuid = test::read_body_json(resp).await;
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(serde_json::json!({
+                "trigger": { "before_due": 30 },
+                "channel": { "type": "email", "to": "not-an-address" }
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.remove(&user_id).expect("user missing");
+            let reminders = &user.tasks[&test_task_id].reminders;
+            assert_eq!(reminders.len(), 1);
+            assert_eq!(reminders[0].id, reminder_id);
+            assert_eq!(reminders[0].trigger, ReminderTrigger::BeforeDue(30));
+            // announced like any other change to the task
+            assert_eq!(user.tasks[&test_task_id].version, state_data.last_event_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{events::ChangeKind, schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeleteReminder {
+    pub id: Uuid,
+}
+
+/// API endpoint to remove a reminder; any delivery still pending for it is dropped
+/// URL: "/users/{userid}/tasks/{taskid}/reminders/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/tasks/{taskId}/reminders/delete",
+    tag = "reminders",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    request_body = DeleteReminder,
+    responses(
+        (status = 200, description = "Id of the deleted reminder", body = Uuid),
+        (status = 404, description = "User, task or reminder not found"),
+    )
+)]
+pub async fn delete_reminder(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<DeleteReminder>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let task = match user.tasks.get_mut(&task_id) {
+        Some(task) => task,
+        None => return HttpResponse::NotFound().body("Task Doesn't exists"),
+    };
+
+    let before = task.reminders.len();
+    task.reminders.retain(|reminder| reminder.id != req.id);
+    if task.reminders.len() == before {
+        return HttpResponse::NotFound().body("Reminder doesn't exist");
+    }
+    state_data
+        .reminder_jobs
+        .retain(|job| job.reminder_id != req.id);
+    state_data.reminders_fired.remove(&req.id);
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+
+    info!("Reminder: {} deleted", req.id);
+    HttpResponse::Ok().json(req.id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::reminders::{Channel, Reminder, ReminderTrigger};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_delete_reminder() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let reminder = Reminder::new(ReminderTrigger::BeforeDue(5), Channel::Log);
+        let reminder_id = reminder.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(task) = state_data
+                .users
+                .get_mut(&user_id)
+                .and_then(|user| user.tasks.get_mut(&test_task_id))
+            {
+                task.reminders.push(reminder);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/reminders/delete",
+            web::delete().to(delete_reminder),
+        ))
+        .await;
+        let uri = format!("/users/{}/tasks/{}/reminders/delete", user_id, test_task_id);
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteReminder { id: reminder_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteReminder { id: reminder_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.remove(&user_id).expect("user missing");
+            assert!(user.tasks[&test_task_id].reminders.is_empty());
+            save_data(&state_data);
+        };
+    }
+}
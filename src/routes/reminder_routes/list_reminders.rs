@@ -0,0 +1,127 @@
+use crate::{reminders::Reminder, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A reminder with the instant it goes off for its task
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledReminder {
+    #[serde(flatten)]
+    pub reminder: Reminder,
+    pub fire_at: DateTime<Utc>,
+    /// Already queued for delivery at `fire_at`
+    pub fired: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReminderList {
+    pub reminders: Vec<ScheduledReminder>,
+}
+
+/// API endpoint to list a task's reminders
+/// URL: "/users/{userid}/tasks/{taskid}/reminders/list"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/{taskId}/reminders/list",
+    tag = "reminders",
+    params(("userId" = Uuid, Path), ("taskId" = Uuid, Path)),
+    responses(
+        (status = 200, body = ReminderList),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn list_reminders(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, task_id) = path.into_inner();
+    let user = match state_data.users.get(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let task = match user.tasks.get(&task_id) {
+        Some(task) => task,
+        None => return HttpResponse::NotFound().body("Task Doesn't exists"),
+    };
+
+    let reminders = task
+        .reminders
+        .iter()
+        .map(|reminder| {
+            let fire_at = reminder.fire_at(task, user.tz());
+            ScheduledReminder {
+                reminder: reminder.clone(),
+                fire_at,
+                fired: state_data.reminders_fired.get(&reminder.id) == Some(&fire_at),
+            }
+        })
+        .collect();
+    HttpResponse::Ok().json(ReminderList { reminders })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::reminders::{Channel, ReminderTrigger};
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::TimeZone;
+
+    #[actix_web::test]
+    async fn test_list_reminders() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let reminder = Reminder::new(ReminderTrigger::BeforeDue(90), Channel::Log);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(task) = state_data
+                .users
+                .get_mut(&user_id)
+                .and_then(|user| user.tasks.get_mut(&test_task_id))
+            {
+                task.reminders.push(reminder.clone());
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/{task_id}/reminders/list",
+            web::get().to(list_reminders),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/tasks/{}/reminders/list",
+                user_id, test_task_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // the test task is due all day on 2000-01-01 (UTC)
+        let list: ReminderList = test::read_body_json(resp).await;
+        assert_eq!(list.reminders.len(), 1);
+        assert_eq!(list.reminders[0].reminder, reminder);
+        assert_eq!(
+            list.reminders[0].fire_at,
+            Utc.with_ymd_and_hms(2000, 1, 1, 22, 30, 0).unwrap()
+        );
+        assert!(!list.reminders[0].fired);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,7 @@
+pub mod create_reminder;
+pub mod delete_reminder;
+pub mod list_reminders;
+
+pub use create_reminder::{create_reminder, NewReminder};
+pub use delete_reminder::{delete_reminder, DeleteReminder};
+pub use list_reminders::{list_reminders, ReminderList, ScheduledReminder};
@@ -0,0 +1,3 @@
+pub mod sync_tasks;
+
+pub use sync_tasks::{sync_tasks, SyncRequest, SyncResponse};
@@ -0,0 +1,386 @@
+use crate::{
+    events::{EventScope, TaskEvent},
+    save_data,
+    sync::{apply_op, ConflictStrategy, OpResult, OpStatus, SyncOp},
+    AppState, Task,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Operations accepted in one request
+pub const MAX_OPS: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SyncRequest {
+    /// `sync_token` from the previous sync; omit on the first one
+    #[serde(default)]
+    pub sync_token: Option<u64>,
+    #[serde(default)]
+    pub strategy: ConflictStrategy,
+    /// Changes queued on the client while offline, oldest first
+    #[serde(default)]
+    pub ops: Vec<SyncOp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SyncResponse {
+    /// One per op, in request order
+    pub results: Vec<OpResult>,
+    /// Changes since `sync_token`, including the ones just applied
+    pub changes: Vec<TaskEvent>,
+    /// Full task list, sent instead of `changes` when the client had no token
+    /// or its token is older than the change log reaches back
+    #[serde(default)]
+    pub tasks: Option<Vec<Task>>,
+    /// Pass back on the next sync
+    pub sync_token: u64,
+}
+
+/// API endpoint to push a client's offline changes and pull what changed on the server
+/// URL: "/users/{userid}/sync"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/sync",
+    tag = "sync",
+    params(("userId" = Uuid, Path)),
+    request_body = SyncRequest,
+    responses(
+        (status = 200, body = SyncResponse),
+        (status = 404, description = "User not found"),
+        (status = 413, description = "Too many operations"),
+    )
+)]
+pub async fn sync_tasks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<SyncRequest>,
+) -> impl Responder {
+    let config = &state_data.config;
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+    if req.ops.len() > MAX_OPS {
+        return HttpResponse::PayloadTooLarge()
+            .body(format!("At most {} operations per sync", MAX_OPS));
+    }
+
+    let before = state_data.last_event_id;
+    let results: Vec<OpResult> = req
+        .ops
+        .iter()
+        .map(|op| {
+            apply_op(
+                &mut state_data,
+                user_id,
+                op,
+                req.strategy,
+                &config.blob_dir(),
+            )
+        })
+        .collect();
+    let changed = state_data.last_event_id != before
+        || results
+            .iter()
+            .any(|result| result.status == OpStatus::Applied);
+
+    let delta = req
+        .sync_token
+        .and_then(|token| state_data.events_since(EventScope::User(user_id), token));
+    let (changes, tasks) = match delta {
+        Some(changes) => (changes, None),
+        None => {
+            let tasks = state_data.users[&user_id].tasks.values().cloned().collect();
+            (Vec::new(), Some(tasks))
+        }
+    };
+    let sync_token = state_data.last_event_id;
+    if changed {
+        save_data(&state_data);
+    }
+
+    HttpResponse::Ok().json(SyncResponse {
+        results,
+        changes,
+        tasks,
+        sync_token,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::ChangeKind;
+    use crate::sync::OpKind;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{EventKind, Subscription};
+    use crate::workflow::{Transition, Workflow};
+    use crate::Status;
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::{TimeDelta, Utc};
+    use serde_json::{json, Map, Value};
+    use std::collections::HashSet;
+
+    fn op(kind: OpKind, task_id: Uuid, base_version: Option<u64>, fields: Value) -> SyncOp {
+        let fields: Map<String, Value> = serde_json::from_value(fields).unwrap();
+        SyncOp {
+            op: kind,
+            task_id,
+            base_version,
+            client_time: Utc::now() - TimeDelta::minutes(5),
+            fields,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_sync_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/sync", web::post().to(sync_tasks)),
+        )
+        .await;
+        let uri = format!("/users/{}/sync", user_id);
+
+        // first sync: offline edit plus a task created on the client
+        let new_task_id = Uuid::new_v4();
+        let create = op(
+            OpKind::Create,
+            new_task_id,
+            None,
+            json!({"title": "offline", "due_date": "2000-01-02"}),
+        );
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(SyncRequest {
+                sync_token: None,
+                strategy: ConflictStrategy::Report,
+                ops: vec![
+                    op(
+                        OpKind::Update,
+                        test_task_id,
+                        Some(0),
+                        json!({"title": "edited"}),
+                    ),
+                    create.clone(),
+                    create,
+                ],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: SyncResponse = test::read_body_json(resp).await;
+        assert!(body.results.iter().all(|r| r.status == OpStatus::Applied));
+        let tasks = body.tasks.expect("full snapshot without a token");
+        assert_eq!(tasks.len(), 2);
+        let base = body.results[0].version;
+        let token = body.sync_token;
+
+        // meanwhile the task's status changes on the server
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            user.tasks.get_mut(&test_task_id).unwrap().status = Status::InProgress;
+            state_data.publish_task(ChangeKind::Updated, test_task_id);
+        };
+
+        // the client edited status and description from the older base version
+        let edit = op(
+            OpKind::Update,
+            test_task_id,
+            base,
+            json!({"status": "Done", "description": "from client"}),
+        );
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(SyncRequest {
+                sync_token: Some(token),
+                strategy: ConflictStrategy::Report,
+                ops: vec![edit.clone()],
+            })
+            .to_request();
+        let body: SyncResponse = test::call_and_read_body_json(&app, req).await;
+        let result = &body.results[0];
+        assert_eq!(result.status, OpStatus::Conflict);
+        assert_eq!(result.applied, vec!["description"]);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].field, "status");
+        assert_eq!(result.conflicts[0].server, json!("InProgress"));
+        assert!(body.tasks.is_none());
+        let kinds: Vec<_> = body.changes.iter().map(|change| change.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Updated, ChangeKind::Updated]);
+
+        // with last-writer-wins a newer client change overrides the server
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(SyncRequest {
+                sync_token: Some(body.sync_token),
+                strategy: ConflictStrategy::LastWriterWins,
+                ops: vec![SyncOp {
+                    client_time: Utc::now() + TimeDelta::minutes(1),
+                    ..edit
+                }],
+            })
+            .to_request();
+        let body: SyncResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body.results[0].status, OpStatus::Applied);
+        assert_eq!(body.results[0].applied, vec!["status"]);
+        assert_eq!(body.changes.len(), 1);
+
+        // unknown fields and expired tokens
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(SyncRequest {
+                sync_token: Some(u64::MAX / 2),
+                strategy: ConflictStrategy::LastWriterWins,
+                ops: vec![
+                    op(OpKind::Update, test_task_id, None, json!({"rank": 3})),
+                    op(OpKind::Delete, new_task_id, None, json!({})),
+                ],
+            })
+            .to_request();
+        let body: SyncResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body.results[0].status, OpStatus::Rejected);
+        assert_eq!(body.results[1].status, OpStatus::Applied);
+        assert_eq!(body.tasks.map(|tasks| tasks.len()), Some(1));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = &state_data.users[&user_id].tasks[&test_task_id];
+            assert_eq!(task.title, "edited");
+            assert_eq!(task.description, "from client");
+            assert_eq!(task.status, Status::Done);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_sync_follows_task_rules() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        // a forward-only workflow, with a hook listening for completions
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            let workflow = Workflow {
+                transitions: vec![
+                    Transition {
+                        from: Status::ToDo,
+                        to: Status::InProgress,
+                    },
+                    Transition {
+                        from: Status::InProgress,
+                        to: Status::Done,
+                    },
+                ],
+                ..Workflow::default()
+            };
+            user.workflow = Some(workflow);
+            user.webhooks.push(Subscription::new(
+                "http://localhost/hook",
+                HashSet::from([EventKind::TaskCompleted]),
+                None,
+            ));
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/sync", web::post().to(sync_tasks)),
+        )
+        .await;
+        let sync = |ops: Vec<SyncOp>| {
+            test::TestRequest::post()
+                .uri(&format!("/users/{}/sync", user_id))
+                .set_json(SyncRequest {
+                    sync_token: None,
+                    strategy: ConflictStrategy::LastWriterWins,
+                    ops,
+                })
+                .to_request()
+        };
+
+        let new_task_id = Uuid::new_v4();
+        let ops = vec![
+            op(
+                OpKind::Create,
+                Uuid::new_v4(),
+                None,
+                json!({"title": "  ", "due_date": "2000-01-02"}),
+            ),
+            op(
+                OpKind::Create,
+                Uuid::nil(),
+                None,
+                json!({"title": "nil", "due_date": "2000-01-02"}),
+            ),
+            op(
+                OpKind::Create,
+                new_task_id,
+                None,
+                json!({"title": "started", "due_date": "2000-01-02", "status": "InProgress"}),
+            ),
+            op(
+                OpKind::Update,
+                test_task_id,
+                Some(0),
+                json!({"title": "skipped ahead", "status": "Done"}),
+            ),
+            SyncOp {
+                client_time: Utc::now() + TimeDelta::minutes(1),
+                ..op(OpKind::Update, new_task_id, None, json!({"status": "Done"}))
+            },
+        ];
+        let body: SyncResponse = test::call_and_read_body_json(&app, sync(ops)).await;
+        let errors: Vec<_> = body
+            .results
+            .iter()
+            .map(|result| (result.status, result.error.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (OpStatus::Rejected, Some("Title can't be empty")),
+                (OpStatus::Rejected, Some("Task id can't be nil")),
+                (OpStatus::Applied, None),
+                (
+                    OpStatus::Rejected,
+                    Some("Transition from ToDo to Done is not allowed")
+                ),
+                (OpStatus::Applied, None),
+            ]
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = &state_data.users[&user_id];
+            assert_eq!(user.tasks.len(), 2);
+            assert_eq!(user.tasks[&test_task_id].title, "sample-title");
+            assert_eq!(user.tasks[&new_task_id].status, Status::Done);
+            let completed: Vec<_> = state_data
+                .webhook_queue
+                .iter()
+                .filter(|delivery| delivery.user_id == user_id)
+                .map(|delivery| delivery.event.data["id"].clone())
+                .collect();
+            assert_eq!(completed, vec![json!(new_task_id)]);
+            state_data.users.remove(&user_id);
+            state_data
+                .webhook_queue
+                .retain(|delivery| delivery.user_id != user_id);
+            save_data(&state_data);
+        };
+    }
+}
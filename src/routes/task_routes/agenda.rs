@@ -0,0 +1,264 @@
+use crate::{schema::Task, workflow::Workflow, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AgendaQuery {
+    /// First day of the week, e.g. `monday` or `sun`; defaults to Monday
+    #[serde(default)]
+    pub week_start: Option<String>,
+    #[serde(default)]
+    pub include_completed: bool,
+}
+
+/// A user's tasks bucketed by when they fall due, each bucket soonest first
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct Agenda {
+    pub overdue: Vec<Task>,
+    pub today: Vec<Task>,
+    pub tomorrow: Vec<Task>,
+    /// After tomorrow but before the next week starts
+    pub this_week: Vec<Task>,
+    pub later: Vec<Task>,
+    /// Tasks in a terminal state; only filled when asked for
+    pub completed: Vec<Task>,
+}
+
+impl Agenda {
+    pub fn build<'a>(
+        tasks: impl Iterator<Item = &'a Task>,
+        workflow: &Workflow,
+        tz: Tz,
+        week_start: Weekday,
+        include_completed: bool,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let today = now.with_timezone(&tz).date_naive();
+        let tomorrow = today + Days::new(1);
+        let days_into_week = today.weekday().days_since(week_start);
+        let next_week = today + Days::new(7 - u64::from(days_into_week));
+
+        let mut agenda = Agenda::default();
+        for task in tasks {
+            let bucket = if workflow.is_terminal(&task.status) {
+                if !include_completed {
+                    continue;
+                }
+                &mut agenda.completed
+            } else if task.is_overdue(now, tz, workflow) {
+                &mut agenda.overdue
+            } else {
+                let due = local_due_date(task, tz);
+                if due <= today {
+                    &mut agenda.today
+                } else if due == tomorrow {
+                    &mut agenda.tomorrow
+                } else if due < next_week {
+                    &mut agenda.this_week
+                } else {
+                    &mut agenda.later
+                }
+            };
+            bucket.push(task.clone());
+        }
+        for bucket in [
+            &mut agenda.overdue,
+            &mut agenda.today,
+            &mut agenda.tomorrow,
+            &mut agenda.this_week,
+            &mut agenda.later,
+            &mut agenda.completed,
+        ] {
+            bucket.sort_by_key(|task| (task.due_at(tz), task.id));
+        }
+        agenda
+    }
+}
+
+/// The calendar day, in the viewer's zone, a task is due on. All-day tasks keep
+/// their own date; timed tasks are shifted into `tz`.
+fn local_due_date(task: &Task, tz: Tz) -> NaiveDate {
+    match task.due_time {
+        Some(_) => task.due_at(tz).with_timezone(&tz).date_naive(),
+        None => task.due_date,
+    }
+}
+
+/// API endpoint to bucket a user's tasks into overdue, today, tomorrow, this week and later
+/// URL: "/users/{userid}/tasks/agenda?week_start=monday&include_completed=false"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/agenda",
+    tag = "tasks",
+    params(("userId" = Uuid, Path), AgendaQuery),
+    responses(
+        (status = 200, body = Agenda),
+        (status = 400, description = "Unknown weekday"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn agenda(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<AgendaQuery>,
+) -> impl Responder {
+    let week_start = match &query.week_start {
+        Some(day) => match day.parse::<Weekday>() {
+            Ok(day) => day,
+            Err(_) => return HttpResponse::BadRequest().body(format!("Unknown weekday: {}", day)),
+        },
+        None => Weekday::Mon,
+    };
+
+    let state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    match state_data.users.get(&user_id) {
+        Some(user) => {
+            info!("Building agenda for user ID: {}", user_id);
+            HttpResponse::Ok().json(Agenda::build(
+                user.tasks.values(),
+                &user.workflow(),
+                user.tz(),
+                week_start,
+                query.include_completed,
+                Utc::now(),
+            ))
+        }
+        None => HttpResponse::NotFound().body("User not found"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{save_data, Status};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::{NaiveTime, TimeZone};
+
+    fn task_due(date: NaiveDate) -> Task {
+        Task::new("t", "", date)
+    }
+
+    #[actix_web::test]
+    async fn test_agenda_buckets() {
+        // Wednesday 2024-07-03, 10:00 UTC
+        let now = Utc.with_ymd_and_hms(2024, 7, 3, 10, 0, 0).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 7, day).expect("valid date");
+
+        let yesterday = task_due(date(2));
+        let mut earlier_today = task_due(date(3));
+        earlier_today.due_time = NaiveTime::from_hms_opt(9, 0, 0);
+        let today = task_due(date(3));
+        let tomorrow = task_due(date(4));
+        let sunday = task_due(date(7));
+        let next_monday = task_due(date(8));
+        let mut done = task_due(date(2));
+        done.status = Status::Done;
+        let tasks = [
+            &yesterday,
+            &earlier_today,
+            &today,
+            &tomorrow,
+            &sunday,
+            &next_monday,
+            &done,
+        ];
+        let ids = |bucket: &Vec<Task>| bucket.iter().map(|task| task.id).collect::<Vec<_>>();
+
+        let agenda = Agenda::build(
+            tasks.into_iter(),
+            &Workflow::default(),
+            Tz::UTC,
+            Weekday::Mon,
+            false,
+            now,
+        );
+        assert_eq!(ids(&agenda.overdue), vec![yesterday.id, earlier_today.id]);
+        assert_eq!(ids(&agenda.today), vec![today.id]);
+        assert_eq!(ids(&agenda.tomorrow), vec![tomorrow.id]);
+        assert_eq!(ids(&agenda.this_week), vec![sunday.id]);
+        assert_eq!(ids(&agenda.later), vec![next_monday.id]);
+        assert!(agenda.completed.is_empty());
+
+        // a Sunday-start week ends on Saturday
+        let agenda = Agenda::build(
+            tasks.into_iter(),
+            &Workflow::default(),
+            Tz::UTC,
+            Weekday::Sun,
+            true,
+            now,
+        );
+        assert!(agenda.this_week.is_empty());
+        assert_eq!(ids(&agenda.later), vec![sunday.id, next_monday.id]);
+        assert_eq!(ids(&agenda.completed), vec![done.id]);
+
+        // 16:00 UTC is already Thursday in Tokyo
+        let agenda = Agenda::build(
+            tasks.into_iter(),
+            &Workflow::default(),
+            chrono_tz::Asia::Tokyo,
+            Weekday::Mon,
+            false,
+            now + chrono::TimeDelta::hours(6),
+        );
+        assert_eq!(
+            ids(&agenda.overdue),
+            vec![yesterday.id, earlier_today.id, today.id]
+        );
+        assert_eq!(ids(&agenda.today), vec![tomorrow.id]);
+    }
+
+    #[actix_web::test]
+    async fn test_agenda() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/agenda", web::get().to(agenda)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/tasks/agenda?week_start=sunday",
+                user_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let agenda: Agenda = test::read_body_json(resp).await;
+        assert_eq!(agenda.overdue.len(), 1);
+        assert_eq!(agenda.overdue[0].id, test_task_id);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/tasks/agenda?week_start=someday",
+                user_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
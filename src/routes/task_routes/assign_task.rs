@@ -0,0 +1,210 @@
+use crate::{
+    events::{ChangeKind, EventScope},
+    routes::task_routes::resolve_task,
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AssignTask {
+    pub id: Uuid,
+    pub assignee: Uuid,
+}
+
+/// API endpoint to assign a task to another (or the same) user. Workspace
+/// tasks take an Editor and can only be assigned to members.
+/// URL: "/users/{userid}/tasks/assign"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/assign",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = AssignTask,
+    responses(
+        (status = 200, description = "Id of the assigned task", body = Uuid),
+        (status = 400, description = "Assignee is not a member of the task's workspace"),
+        (status = 403, description = "Not an Editor of the task's workspace"),
+        (status = 404, description = "User, assignee or task not found"),
+    )
+)]
+pub async fn assign_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<AssignTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let AssignTask {
+        id: task_id,
+        assignee,
+    } = req.into_inner();
+    if !state_data.users.contains_key(&assignee) {
+        return HttpResponse::NotFound().body(format!("Assignee UserID: {} not found", assignee));
+    }
+
+    match resolve_task(&state_data, user_id.into_inner(), task_id, Role::Editor) {
+        // Shared tasks stay within their workspace
+        Ok(EventScope::Workspace(workspace_id))
+            if state_data.member_role(workspace_id, assignee).is_none() =>
+        {
+            return HttpResponse::BadRequest().body(format!(
+                "Assignee UserID: {} is not a member of this workspace",
+                assignee
+            ));
+        }
+        Ok(_) => {}
+        Err(err) => {
+            warn!("Task-id: {} can't be assigned: {:?}", task_id, err);
+            return err.into();
+        }
+    }
+
+    if let Some(task) = state_data.all_tasks_mut().find(|task| task.id == task_id) {
+        task.assignee = Some(assignee);
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+    info!("Task-Id: {} assigned to UserID: {}", task_id, assignee);
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Workspace;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_assign_task() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (assignee_id, _assignee_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/assign", web::put().to(assign_task)),
+        )
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/assign", owner_id))
+            .set_json(AssignTask {
+                id: test_task_id,
+                assignee: assignee_id,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // unknown assignee
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/assign", owner_id))
+            .set_json(AssignTask {
+                id: test_task_id,
+                assignee: Uuid::new_v4(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let assignee = state_data
+                .users
+                .get(&owner_id)
+                .and_then(|user| user.tasks.get(&test_task_id))
+                .and_then(|task| task.assignee);
+            assert_eq!(assignee, Some(assignee_id));
+
+            // deleting the assignee clears the assignment but keeps the task
+            state_data.users.remove(&assignee_id);
+            state_data.remove_user_assignments(assignee_id);
+            let task = state_data
+                .users
+                .get(&owner_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.assignee.is_none()));
+
+            state_data.users.remove(&owner_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_assign_workspace_task() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (viewer_id, _viewer_task_id) = create_test_user_and_task(&app_state);
+        let (outsider_id, _outsider_task_id) = create_test_user_and_task(&app_state);
+
+        // move the owner's task into a shared workspace
+        let mut workspace = Workspace::new("assign-workspace", owner_id);
+        let workspace_id = workspace.id;
+        workspace.members.insert(viewer_id, Role::Viewer);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.remove(&test_task_id))
+                .expect("test task exists");
+            workspace.tasks.insert(test_task_id, task);
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/assign", web::put().to(assign_task)),
+        )
+        .await;
+
+        let assign = |user_id: Uuid, assignee: Uuid| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/tasks/assign", user_id))
+                .set_json(AssignTask {
+                    id: test_task_id,
+                    assignee,
+                })
+                .to_request()
+        };
+
+        // the owner can assign to members only
+        let resp = test::call_service(&app, assign(owner_id, viewer_id)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, assign(owner_id, outsider_id)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // viewers and outsiders can't assign
+        let resp = test::call_service(&app, assign(viewer_id, viewer_id)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = test::call_service(&app, assign(outsider_id, outsider_id)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let assignee = state_data
+                .workspaces
+                .get(&workspace_id)
+                .and_then(|workspace| workspace.tasks.get(&test_task_id))
+                .and_then(|task| task.assignee);
+            assert_eq!(assignee, Some(viewer_id));
+
+            state_data.workspaces.remove(&workspace_id);
+            for user_id in [owner_id, viewer_id, outsider_id] {
+                state_data.users.remove(&user_id);
+            }
+            save_data(&state_data);
+        };
+    }
+}
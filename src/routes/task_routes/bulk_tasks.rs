@@ -0,0 +1,312 @@
+use crate::{
+    events::ChangeKind,
+    routes::task_routes::{add_task, discard_task, error_message, set_status, DeleteError},
+    schema::{save_data, Task, User},
+    webhooks::{emit, EventKind},
+    AppState, DeleteTask, NewTask, UpdateTask,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Operations accepted in one request
+pub const MAX_BULK_OPS: usize = 500;
+
+/// One operation, with the same body as its single-task endpoint
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOp {
+    Create(NewTask),
+    Update(UpdateTask),
+    Delete(DeleteTask),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BulkRequest {
+    /// Applied in order; later operations see the effect of earlier ones
+    pub ops: Vec<BulkOp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkStatus {
+    Ok,
+    Failed,
+    /// Valid, but undone because another operation failed
+    RolledBack,
+    /// Not attempted because an earlier operation failed
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BulkResult {
+    pub status: BulkStatus,
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BulkOutcome {
+    /// Whether the batch was applied; it never is in part
+    pub committed: bool,
+    /// One per operation, in request order
+    pub results: Vec<BulkResult>,
+}
+
+/// What a staged operation still has to announce once the batch commits
+enum Effect {
+    Created(Uuid, Value),
+    Updated(Uuid, Value, bool),
+    Deleted(Box<Task>),
+}
+
+/// Applies `op` to a scratch copy of the user
+fn stage(user: &mut User, op: BulkOp) -> Result<Effect, String> {
+    match op {
+        BulkOp::Create(req) => {
+            let task_id = add_task(user, &req).map_err(error_message)?;
+            let payload = serde_json::to_value(&user.tasks[&task_id]).unwrap_or_default();
+            Ok(Effect::Created(task_id, payload))
+        }
+        BulkOp::Update(req) => {
+            let workflow = user.workflow();
+            let completed = set_status(&mut user.tasks, &workflow, &req).map_err(error_message)?;
+            let payload = serde_json::to_value(&user.tasks[&req.id]).unwrap_or_default();
+            Ok(Effect::Updated(req.id, payload, completed))
+        }
+        BulkOp::Delete(DeleteTask { id }) => match user.tasks.remove(&id) {
+            Some(task) => Ok(Effect::Deleted(Box::new(task))),
+            None => Err(error_message(DeleteError::TaskNotFound(id))),
+        },
+    }
+}
+
+/// API endpoint to create, update and delete many tasks at once, all or nothing
+/// URL: "/users/{userid}/tasks/bulk"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/bulk",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = BulkRequest,
+    responses(
+        (status = 200, description = "Every operation was applied", body = BulkOutcome),
+        (status = 404, description = "User not found"),
+        (status = 413, description = "Too many operations"),
+        (status = 422, description = "An operation failed, nothing was applied", body = BulkOutcome),
+    )
+)]
+pub async fn bulk_tasks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<BulkRequest>,
+) -> impl Responder {
+    let config = &state_data.config;
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let mut user = match state_data.users.get(&user_id) {
+        Some(user) => user.clone(),
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let BulkRequest { ops } = req.into_inner();
+    if ops.len() > MAX_BULK_OPS {
+        return HttpResponse::PayloadTooLarge()
+            .body(format!("At most {} operations per request", MAX_BULK_OPS));
+    }
+
+    // Stage every operation on a copy; the real state is only touched once all succeed
+    let count = ops.len();
+    let mut results = Vec::with_capacity(count);
+    let mut effects = Vec::with_capacity(count);
+    for (index, op) in ops.into_iter().enumerate() {
+        // ids held by other users or workspaces aren't visible on the copy
+        let staged = match &op {
+            BulkOp::Create(NewTask {
+                id: Some(task_id), ..
+            }) if state_data.task_scope(*task_id).is_some() => {
+                Err(format!("Task-Id: {} already exists", task_id))
+            }
+            _ => stage(&mut user, op),
+        };
+        match staged {
+            Ok(effect) => {
+                let task_id = match &effect {
+                    Effect::Created(id, _) | Effect::Updated(id, _, _) => *id,
+                    Effect::Deleted(task) => task.id,
+                };
+                results.push(BulkResult {
+                    status: BulkStatus::Ok,
+                    task_id: Some(task_id),
+                    error: None,
+                });
+                effects.push(effect);
+            }
+            Err(err) => {
+                warn!(
+                    "Bulk operation {} for User-Id: {} failed: {}",
+                    index, user_id, err
+                );
+                for result in &mut results {
+                    result.status = BulkStatus::RolledBack;
+                }
+                results.push(BulkResult {
+                    status: BulkStatus::Failed,
+                    task_id: None,
+                    error: Some(err),
+                });
+                results.resize_with(count, || BulkResult {
+                    status: BulkStatus::Skipped,
+                    task_id: None,
+                    error: None,
+                });
+                return HttpResponse::UnprocessableEntity().json(BulkOutcome {
+                    committed: false,
+                    results,
+                });
+            }
+        }
+    }
+
+    state_data.users.insert(user_id, user);
+    for effect in effects {
+        match effect {
+            Effect::Created(task_id, payload) => {
+                emit(&mut state_data, EventKind::TaskCreated, user_id, payload);
+                state_data.publish_task(ChangeKind::Created, task_id);
+            }
+            Effect::Updated(task_id, payload, completed) => {
+                emit(
+                    &mut state_data,
+                    EventKind::TaskUpdated,
+                    user_id,
+                    payload.clone(),
+                );
+                if completed {
+                    emit(&mut state_data, EventKind::TaskCompleted, user_id, payload);
+                }
+                state_data.publish_task(ChangeKind::Updated, task_id);
+            }
+            Effect::Deleted(task) => {
+                discard_task(&mut state_data, &config.blob_dir(), user_id, *task);
+            }
+        }
+    }
+    save_data(&state_data);
+
+    info!("Applied {} bulk operations for User-Id: {}", count, user_id);
+    HttpResponse::Ok().json(BulkOutcome {
+        committed: true,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Status;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::NaiveDate;
+
+    fn new_task(title: &str) -> BulkOp {
+        BulkOp::Create(NewTask {
+            id: None,
+            title: title.to_string(),
+            description: "bulk".to_string(),
+            due_date: NaiveDate::from_ymd_opt(2000, 1, 2).unwrap(),
+            due_time: None,
+            timezone: None,
+            status: Status::ToDo,
+            project_id: None,
+            priority: None,
+            tags: Vec::new(),
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_bulk_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/bulk", web::post().to(bulk_tasks)),
+        )
+        .await;
+        let uri = format!("/users/{}/tasks/bulk", user_id);
+
+        // a failing operation leaves everything untouched
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(BulkRequest {
+                ops: vec![
+                    new_task("first"),
+                    BulkOp::Update(UpdateTask {
+                        id: Uuid::new_v4(),
+                        status: Status::Done,
+                    }),
+                    BulkOp::Delete(DeleteTask { id: test_task_id }),
+                ],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let outcome: BulkOutcome = test::read_body_json(resp).await;
+        assert!(!outcome.committed);
+        let statuses: Vec<_> = outcome.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BulkStatus::RolledBack,
+                BulkStatus::Failed,
+                BulkStatus::Skipped
+            ]
+        );
+        if let Ok(state_data) = app_state.data.lock() {
+            assert_eq!(state_data.users[&user_id].tasks.len(), 1);
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(BulkRequest {
+                ops: vec![
+                    new_task("first"),
+                    new_task("second"),
+                    BulkOp::Update(UpdateTask {
+                        id: test_task_id,
+                        status: Status::InProgress,
+                    }),
+                    BulkOp::Delete(DeleteTask { id: test_task_id }),
+                ],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let outcome: BulkOutcome = test::read_body_json(resp).await;
+        assert!(outcome.committed);
+        assert!(outcome.results.iter().all(|r| r.status == BulkStatus::Ok));
+        assert_eq!(outcome.results[3].task_id, Some(test_task_id));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks.len(), 2);
+            assert!(!tasks.contains_key(&test_task_id));
+            assert!(tasks.values().all(|task| task.description == "bulk"));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -1,23 +1,162 @@
 use crate::{
-    schema::{save_data, Status, Task},
+    board::next_rank,
+    events::ChangeKind,
+    schema::{check_labels, parse_timezone, save_data, AppStateData, Status, Task, User},
+    webhooks::{emit, EventKind},
     AppState,
 };
 use actix_web::{web, HttpResponse, Responder};
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use log::{error, info};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct NewTask {
+    /// Client-chosen id, e.g. to keep references when importing; generated when omitted
+    #[serde(default)]
+    pub id: Option<Uuid>,
     pub title: String,
     pub description: String,
     pub due_date: NaiveDate,
+    /// Omit for an all-day task
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    /// IANA zone of the due date/time; defaults to the user's profile zone
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub status: Status,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// `A` (highest) to `Z`
+    #[serde(default)]
+    pub priority: Option<char>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Why a task couldn't be created
+#[derive(Debug, PartialEq)]
+pub enum CreateError {
+    UserNotFound,
+    EmptyTitle,
+    UnknownTimezone(String),
+    InvalidLabels(String),
+    NilId,
+    IdTaken(Uuid),
+    ProjectNotFound,
+    ProjectArchived,
+}
+
+impl From<CreateError> for HttpResponse {
+    fn from(err: CreateError) -> Self {
+        match err {
+            CreateError::UserNotFound => HttpResponse::NotFound().body("User not found"),
+            CreateError::EmptyTitle => HttpResponse::BadRequest().body("Title can't be empty"),
+            CreateError::UnknownTimezone(timezone) => {
+                HttpResponse::BadRequest().body(format!("Unknown time zone: {}", timezone))
+            }
+            CreateError::InvalidLabels(err) => HttpResponse::BadRequest().body(err),
+            CreateError::NilId => HttpResponse::BadRequest().body("Task id can't be nil"),
+            CreateError::IdTaken(task_id) => {
+                HttpResponse::Conflict().body(format!("Task-Id: {} already exists", task_id))
+            }
+            CreateError::ProjectNotFound => HttpResponse::NotFound().body("Project not found"),
+            CreateError::ProjectArchived => HttpResponse::Conflict().body("Project is archived"),
+        }
+    }
+}
+
+/// Adds the task `req` describes to `user_id`'s tasks and announces it.
+/// The caller saves.
+pub fn insert_task(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    req: &NewTask,
+) -> Result<Uuid, CreateError> {
+    // Client-chosen ids must be unique across all users and workspaces
+    if let Some(task_id) = req.id.filter(|task_id| !task_id.is_nil()) {
+        if data.task_scope(task_id).is_some() {
+            return Err(CreateError::IdTaken(task_id));
+        }
+    }
+
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .ok_or(CreateError::UserNotFound)?;
+    let task_id = add_task(user, req)?;
+    let payload = serde_json::to_value(&user.tasks[&task_id]).unwrap_or_default();
+
+    emit(data, EventKind::TaskCreated, user_id, payload);
+    data.publish_task(ChangeKind::Created, task_id);
+    Ok(task_id)
+}
+
+/// Adds the task `req` describes to `user`'s tasks without announcing it.
+/// Ids are only checked against `user`'s own tasks.
+pub fn add_task(user: &mut User, req: &NewTask) -> Result<Uuid, CreateError> {
+    if req.title.trim().is_empty() {
+        return Err(CreateError::EmptyTitle);
+    }
+    if let Some(timezone) = &req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Err(CreateError::UnknownTimezone(timezone.clone()));
+        }
+    }
+    check_labels(req.priority, &req.tags).map_err(CreateError::InvalidLabels)?;
+    if let Some(task_id) = req.id {
+        if task_id.is_nil() {
+            return Err(CreateError::NilId);
+        }
+        if user.tasks.contains_key(&task_id) {
+            return Err(CreateError::IdTaken(task_id));
+        }
+    }
+
+    // Tasks can only be filed under an active project of the same user
+    if let Some(project_id) = req.project_id {
+        match user.projects.get(&project_id) {
+            Some(project) if project.archived => return Err(CreateError::ProjectArchived),
+            Some(_) => {}
+            None => return Err(CreateError::ProjectNotFound),
+        }
+    }
+    let mut new_task = Task::new(&req.title, &req.description, req.due_date);
+    if let Some(task_id) = req.id {
+        new_task.id = task_id;
+    }
+    // New tasks start in the first state of the user's workflow
+    if let Some(initial) = user.workflow().states.first() {
+        new_task.status = initial.status.clone();
+    }
+    new_task.rank = next_rank(&user.tasks, &new_task.status);
+    new_task.project_id = req.project_id;
+    new_task.due_time = req.due_time;
+    new_task.timezone = req.timezone.clone();
+    new_task.priority = req.priority;
+    new_task.tags = req.tags.clone();
+    let task_id = new_task.id;
+    user.tasks.insert(task_id, new_task);
+    Ok(task_id)
 }
 
 /// API endpoint to create new task
 /// URL: "/users/{userid}/tasks/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/create",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = NewTask,
+    responses(
+        (status = 200, description = "Id of the new task", body = Uuid),
+        (status = 400, description = "Empty title, unknown time zone, invalid labels or nil id"),
+        (status = 404, description = "User or project not found"),
+        (status = 409, description = "Id already taken or project archived"),
+    )
+)]
 pub async fn create_task(
     state_data: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -31,22 +170,13 @@ pub async fn create_task(
         }
     };
 
-    let user_id = user_id.into_inner();
-
-    match state_data.users.get_mut(&user_id) {
-        Some(user) => {
-            let new_task = Task::new(&req.title, &req.description, req.due_date);
-            let task_id = new_task.id;
-            user.tasks.insert(task_id, new_task);
-
+    match insert_task(&mut state_data, user_id.into_inner(), &req) {
+        Ok(task_id) => {
             save_data(&state_data);
-
             info!("Task created successfully with ID: {}", task_id);
             HttpResponse::Ok().json(task_id)
         }
-        None => {
-            HttpResponse::NotFound().body("User not found")
-        }
+        Err(err) => err.into(),
     }
 }
 
@@ -78,10 +208,16 @@ mod test {
 
         // Define a new task
         let new_task = NewTask {
+            id: None,
             title: "Test Task".to_string(),
             description: "Test Description".to_string(),
             due_date: NaiveDate::from_ymd_opt(2024, 5, 24).expect("failed to create Due-Date"),
+            due_time: None,
+            timezone: None,
             status: Status::ToDo,
+            project_id: None,
+            priority: None,
+            tags: Vec::new(),
         };
 
         // Create a request to create a task
@@ -96,11 +232,29 @@ mod test {
 
         let resp_body = test::read_body(resp).await;
         let task_id: Uuid = serde_json::from_slice(&resp_body).unwrap();
+        // server ids are time-ordered
+        assert_eq!(task_id.get_version_num(), 7);
+
+        // a client-chosen id is kept, and can't be taken twice
+        let client_id = Uuid::new_v4();
+        let imported = NewTask {
+            id: Some(client_id),
+            ..new_task
+        };
+        for expected in [StatusCode::OK, StatusCode::CONFLICT] {
+            let req = test::TestRequest::post()
+                .uri(&format!("/users/{}/tasks/create", user_id))
+                .set_json(&imported)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), expected);
+        }
 
         // Verify that the task was added to the user's tasks
         if let Ok(mut state_data) = app_state.data.lock() {
             if let Some(user) = state_data.users.get(&user_id) {
                 assert!(user.tasks.contains_key(&task_id));
+                assert!(user.tasks.contains_key(&client_id));
             };
             // forcefully reomving "new-test-user" to avoid duplicates
             state_data.users.remove(&user_id);
@@ -1,21 +1,90 @@
-use crate::{schema::save_data, AppState};
+use crate::{
+    blobs::remove_orphaned_blobs,
+    events::EventScope,
+    schema::{save_data, AppStateData, Task},
+    webhooks::{emit, EventKind},
+    AppState,
+};
 use actix_web::{web, HttpResponse, Responder};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct DeleteTask {
     pub id: Uuid,
 }
 
-/// API endpoint to delete new task
+/// Why a task couldn't be deleted
+#[derive(Debug, PartialEq)]
+pub enum DeleteError {
+    UserNotFound,
+    TaskNotFound(Uuid),
+}
+
+impl From<DeleteError> for HttpResponse {
+    fn from(err: DeleteError) -> Self {
+        match err {
+            DeleteError::UserNotFound => HttpResponse::NotFound().body("User not found"),
+            DeleteError::TaskNotFound(_) => HttpResponse::NotFound().body("Task doesn't exist"),
+        }
+    }
+}
+
+/// Removes `task_id` from `user_id`'s tasks together with its comments and
+/// unshared attachments, and announces it. The caller saves.
+pub fn remove_task(
+    data: &mut AppStateData,
+    blob_dir: &Path,
+    user_id: Uuid,
+    task_id: Uuid,
+) -> Result<(), DeleteError> {
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .ok_or(DeleteError::UserNotFound)?;
+    let Some(task) = user.tasks.remove(&task_id) else {
+        warn!("Task-id: {} doesn't exist", task_id);
+        return Err(DeleteError::TaskNotFound(task_id));
+    };
+    discard_task(data, blob_dir, user_id, task);
+    Ok(())
+}
+
+/// Cleans up after `task`, already taken out of `user_id`'s tasks: drops its
+/// comments and unshared attachments, and announces the deletion.
+pub fn discard_task(data: &mut AppStateData, blob_dir: &Path, user_id: Uuid, task: Task) {
+    let task_id = task.id;
+    data.comments.remove(&task_id);
+    let payload = serde_json::to_value(&task).unwrap_or_default();
+    emit(data, EventKind::TaskDeleted, user_id, payload);
+    // Drop blobs no other task shares
+    let shas = task.attachments.into_iter().map(|a| a.sha256);
+    remove_orphaned_blobs(data, blob_dir, shas);
+    data.publish_deleted(EventScope::User(user_id), task_id);
+}
+
+/// API endpoint to delete a task
 /// URL: "/users/{userid}/tasks/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/tasks/delete",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = DeleteTask,
+    responses(
+        (status = 200, description = "Id of the deleted task", body = Uuid),
+        (status = 404, description = "User or task not found"),
+    )
+)]
 pub async fn delete_task(
     state_data: web::Data<AppState>,
     user_id: web::Path<Uuid>,
     req: web::Json<DeleteTask>,
 ) -> impl Responder {
+    let config = &state_data.config;
     let mut state_data = match state_data.data.lock() {
         Ok(data) => data,
         Err(_) => {
@@ -24,21 +93,14 @@ pub async fn delete_task(
         }
     };
 
-    let user_id = user_id.into_inner();
-    let task_id = &req.id;
-
-    match state_data.users.get_mut(&user_id) {
-        Some(user) => {
-            if user.tasks.remove(task_id).is_some() {
-                save_data(&state_data);
-                info!("Task deleted successfully with ID: {}", task_id);
-                HttpResponse::Ok().json(task_id)
-            } else {
-                warn!("Task-id: {} doesn't exist", task_id);
-                HttpResponse::NotFound().body("Task doesn't exist")
-            }
+    let (user_id, task_id) = (user_id.into_inner(), req.id);
+    match remove_task(&mut state_data, &config.blob_dir(), user_id, task_id) {
+        Ok(()) => {
+            save_data(&state_data);
+            info!("Task deleted successfully with ID: {}", task_id);
+            HttpResponse::Ok().json(task_id)
         }
-        None => HttpResponse::NotFound().body("User not found"),
+        Err(err) => err.into(),
     }
 }
 
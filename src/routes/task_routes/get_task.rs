@@ -1,15 +1,27 @@
-use crate::AppState;
+use crate::{schema::Task, AppState};
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct GetTask {
     pub id: Uuid,
 }
 
-/// API endpoint to delete new task
+/// API endpoint to fetch one task
 /// URL: "/users/{userid}/tasks/get-task"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/get-task",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = GetTask,
+    responses(
+        (status = 200, body = Task),
+        (status = 404, description = "User or task not found"),
+    )
+)]
 pub async fn get_task(
     state_data: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -0,0 +1,126 @@
+use crate::{schema::Task, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A task assigned to the caller together with where it lives
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AssignedTask {
+    /// Set for personal tasks
+    pub owner_id: Option<Uuid>,
+    /// Set for workspace tasks
+    pub workspace_id: Option<Uuid>,
+    pub task: Task,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignedTaskList {
+    pub tasks: Vec<AssignedTask>,
+}
+
+/// API endpoint to list tasks assigned to a user, across all owners and workspaces
+/// URL: "/users/{userid}/tasks/assigned"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/assigned",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = AssignedTaskList),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn list_assigned(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+
+    let personal = state_data.users.values().flat_map(|owner| {
+        owner
+            .tasks
+            .values()
+            .filter(|task| task.assignee == Some(user_id))
+            .map(|task| AssignedTask {
+                owner_id: Some(owner.id),
+                workspace_id: None,
+                task: task.clone(),
+            })
+    });
+    let shared = state_data.workspaces.values().flat_map(|workspace| {
+        workspace
+            .tasks
+            .values()
+            .filter(|task| task.assignee == Some(user_id))
+            .map(|task| AssignedTask {
+                owner_id: None,
+                workspace_id: Some(workspace.id),
+                task: task.clone(),
+            })
+    });
+    let mut tasks: Vec<AssignedTask> = personal.chain(shared).collect();
+    tasks.sort_by_key(|assigned| assigned.task.due_date);
+
+    info!("Listing tasks assigned to user ID: {}", user_id);
+    HttpResponse::Ok().json(AssignedTaskList { tasks })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_assigned() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (assignee_id, _assignee_task_id) = create_test_user_and_task(&app_state);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(task) = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.get_mut(&test_task_id))
+            {
+                task.assignee = Some(assignee_id);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/assigned",
+            web::get().to(list_assigned),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/assigned", assignee_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let list: AssignedTaskList = test::read_body_json(resp).await;
+        assert_eq!(list.tasks.len(), 1);
+        assert_eq!(list.tasks[0].owner_id, Some(owner_id));
+        assert_eq!(list.tasks[0].task.id, test_task_id);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&assignee_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -1,16 +1,63 @@
-use crate::{schema::Task, AppState};
+use crate::{
+    schema::{Comment, Task},
+    workflow::Workflow,
+    AppState,
+};
 use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use chrono_tz::Tz;
 use log::{error, info};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A listed task together with the size of its comment thread
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TaskListItem {
+    #[serde(flatten)]
+    pub task: Task,
+    pub comment_count: usize,
+    /// Judged in the owner's time zone
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TaskList {
-    pub tasks: Vec<Task>,
+    pub tasks: Vec<TaskListItem>,
 }
 
-/// API endpoint to create new task
+impl TaskList {
+    pub fn from_tasks<'a>(
+        tasks: impl Iterator<Item = &'a Task>,
+        comments: &HashMap<Uuid, Vec<Comment>>,
+        workflow: &Workflow,
+        tz: Tz,
+    ) -> Self {
+        let now = Utc::now();
+        let tasks = tasks
+            .map(|task| TaskListItem {
+                task: task.clone(),
+                comment_count: comments.get(&task.id).map_or(0, Vec::len),
+                overdue: task.is_overdue(now, tz, workflow),
+            })
+            .collect();
+        TaskList { tasks }
+    }
+}
+
+/// API endpoint to list a user's tasks
 /// URL: "/users/{userid}/tasks/list"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/list",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = TaskList),
+        (status = 404, description = "User not found"),
+    )
+)]
 pub async fn list_task(
     state_data: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -29,10 +76,58 @@ pub async fn list_task(
     // Try finding the user in the database
     match state_data.users.get(&user_id) {
         Some(user) => {
-            let task_list: Vec<Task> = user.tasks.values().cloned().collect();
             info!("Listing tasks for user ID: {}", user_id);
-            HttpResponse::Ok().json(TaskList { tasks: task_list })
+            HttpResponse::Ok().json(TaskList::from_tasks(
+                user.tasks.values(),
+                &state_data.comments,
+                &user.workflow(),
+                user.tz(),
+            ))
         }
         None => HttpResponse::NotFound().body("User not found"),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_task() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data
+                .comments
+                .insert(test_task_id, vec![Comment::new(user_id, "first!")]);
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/list", web::get().to(list_task)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/list", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let list: TaskList = test::read_body_json(resp).await;
+        assert_eq!(list.tasks.len(), 1);
+        assert_eq!(list.tasks[0].task.id, test_task_id);
+        assert_eq!(list.tasks[0].comment_count, 1);
+        assert!(list.tasks[0].overdue);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.comments.remove(&test_task_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
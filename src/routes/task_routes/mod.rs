@@ -1,11 +1,88 @@
+pub mod agenda;
+pub mod assign_task;
+pub mod bulk_tasks;
 pub mod create_task;
 pub mod delete_task;
 pub mod update_task;
+pub mod list_assigned;
 pub mod list_task;
 pub mod get_task;
+pub mod quick_add;
+pub mod reschedule_task;
+pub mod unassign_task;
+pub mod unwatch_task;
+pub mod watch_task;
 
-pub use create_task::{create_task, NewTask};
-pub use delete_task::{delete_task, DeleteTask};
-pub use update_task::{update_task, UpdateTask};
+pub use agenda::{agenda, Agenda, AgendaQuery};
+pub use assign_task::{assign_task, AssignTask};
+pub use bulk_tasks::{bulk_tasks, BulkOp, BulkOutcome, BulkRequest, BulkResult, BulkStatus};
+pub use create_task::{add_task, create_task, insert_task, CreateError, NewTask};
+pub use delete_task::{delete_task, discard_task, remove_task, DeleteError, DeleteTask};
+pub use update_task::{change_status, set_status, update_task, UpdateError, UpdateTask};
+pub use list_assigned::{list_assigned, AssignedTask, AssignedTaskList};
 pub use list_task::{list_task, TaskList};
-pub use get_task::{get_task, GetTask};
\ No newline at end of file
+pub use get_task::{get_task, GetTask};
+pub use quick_add::{quick_add, QuickAdd, QuickAddResult};
+pub use reschedule_task::{reschedule, reschedule_task, RescheduleError, RescheduleTask};
+pub use unassign_task::unassign_task;
+pub use unwatch_task::unwatch_task;
+pub use watch_task::{watch_task, WatchTask};
+
+use crate::{
+    events::EventScope,
+    routes::workspace_routes::{authorize, AccessError},
+    schema::{AppStateData, Role},
+};
+use actix_web::{body::MessageBody, HttpResponse};
+use uuid::Uuid;
+
+/// The message the single-task endpoint answers with for `err`
+pub fn error_message(err: impl Into<HttpResponse>) -> String {
+    let resp: HttpResponse = err.into();
+    match resp.into_body().try_into_bytes() {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Why a task route can't reach a task
+#[derive(Debug, PartialEq)]
+pub enum TaskAccessError {
+    UserNotFound,
+    TaskNotFound(Uuid),
+    Denied(AccessError),
+}
+
+impl From<TaskAccessError> for HttpResponse {
+    fn from(err: TaskAccessError) -> Self {
+        match err {
+            TaskAccessError::UserNotFound => HttpResponse::NotFound().body("User not found"),
+            TaskAccessError::TaskNotFound(_) => {
+                HttpResponse::NotFound().body("Task Doesn't exists")
+            }
+            TaskAccessError::Denied(err) => err.into(),
+        }
+    }
+}
+
+/// Where `task_id` lives, if `user_id` may work on it: among their own tasks,
+/// or in a workspace where they hold at least `required`. Other users'
+/// personal tasks count as not found.
+pub fn resolve_task(
+    data: &AppStateData,
+    user_id: Uuid,
+    task_id: Uuid,
+    required: Role,
+) -> Result<EventScope, TaskAccessError> {
+    if !data.users.contains_key(&user_id) {
+        return Err(TaskAccessError::UserNotFound);
+    }
+    match data.task_scope(task_id).map(|(scope, _)| scope) {
+        Some(EventScope::User(owner)) if owner == user_id => Ok(EventScope::User(owner)),
+        Some(EventScope::Workspace(workspace_id)) => {
+            authorize(data, workspace_id, user_id, required).map_err(TaskAccessError::Denied)?;
+            Ok(EventScope::Workspace(workspace_id))
+        }
+        _ => Err(TaskAccessError::TaskNotFound(task_id)),
+    }
+}
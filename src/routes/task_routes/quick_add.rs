@@ -0,0 +1,156 @@
+use crate::{
+    quickadd::parse,
+    routes::task_routes::{create_task::insert_task, NewTask},
+    schema::save_data,
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuickAdd {
+    /// e.g. "Ship release notes next friday #docs !high"
+    pub text: String,
+    /// Only parse, without creating the task
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QuickAddResult {
+    /// Id of the created task; `None` for a preview
+    pub id: Option<Uuid>,
+    /// The task as parsed
+    pub task: NewTask,
+}
+
+/// API endpoint to create a task from one line of text. Dates are relative
+/// to today in the user's time zone.
+/// URL: "/users/{userid}/tasks/quick-add"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/quick-add",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = QuickAdd,
+    responses(
+        (status = 200, body = QuickAddResult),
+        (status = 400, description = "Nothing left for a title"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn quick_add(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<QuickAdd>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    let today = Utc::now().with_timezone(&user.tz()).date_naive();
+    let mut task = match parse(&req.text, today) {
+        Ok(task) => task,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    // the status the task will start in
+    if let Some(initial) = user.workflow().states.first() {
+        task.status = initial.status.clone();
+    }
+    if req.preview {
+        return HttpResponse::Ok().json(QuickAddResult { id: None, task });
+    }
+
+    match insert_task(&mut state_data, user_id, &task) {
+        Ok(task_id) => {
+            save_data(&state_data);
+            info!("Task quick-added with ID: {}", task_id);
+            HttpResponse::Ok().json(QuickAddResult {
+                id: Some(task_id),
+                task,
+            })
+        }
+        Err(err) => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::{Datelike, Weekday};
+
+    #[actix_web::test]
+    async fn test_quick_add() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.get_mut(&user_id).unwrap().timezone =
+                Some("Pacific/Kiritimati".to_string());
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/quick-add",
+            web::post().to(quick_add),
+        ))
+        .await;
+        let quick_add = |text: &str, preview: bool| {
+            test::TestRequest::post()
+                .uri(&format!("/users/{}/tasks/quick-add", user_id))
+                .set_json(QuickAdd {
+                    text: text.to_string(),
+                    preview,
+                })
+                .to_request()
+        };
+
+        let preview: QuickAddResult = test::call_and_read_body_json(
+            &app,
+            quick_add("Ship release notes next friday #docs !high", true),
+        )
+        .await;
+        assert_eq!(preview.id, None);
+        assert_eq!(preview.task.title, "Ship release notes");
+        assert_eq!(preview.task.due_date.weekday(), Weekday::Fri);
+        // today in the user's zone, 14 hours ahead of UTC
+        let today = Utc::now()
+            .with_timezone(&chrono_tz::Pacific::Kiritimati)
+            .date_naive();
+        assert!(preview.task.due_date > today);
+        assert!((preview.task.due_date - today).num_days() <= 7);
+        if let Ok(state_data) = app_state.data.lock() {
+            assert_eq!(state_data.users[&user_id].tasks.len(), 1);
+        };
+
+        let created: QuickAddResult =
+            test::call_and_read_body_json(&app, quick_add("Water plants #home !c", false)).await;
+        let task_id = created.id.unwrap();
+        assert_eq!(created.task.due_date, today);
+
+        let resp = test::call_service(&app, quick_add("#home !low", false)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = &state_data.users[&user_id].tasks[&task_id];
+            assert_eq!(task.title, "Water plants");
+            assert_eq!(task.tags, vec!["home"]);
+            assert_eq!(task.priority, Some('C'));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    events::ChangeKind,
+    schema::{parse_timezone, save_data, AppStateData},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{NaiveDate, NaiveTime};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RescheduleTask {
+    pub id: Uuid,
+    pub due_date: NaiveDate,
+    /// Omit to make the task all-day
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    /// Omit to follow the user's profile zone
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// Why a task couldn't be rescheduled
+#[derive(Debug, PartialEq)]
+pub enum RescheduleError {
+    UnknownTimezone(String),
+    UserNotFound,
+    TaskNotFound(Uuid),
+}
+
+impl From<RescheduleError> for HttpResponse {
+    fn from(err: RescheduleError) -> Self {
+        match err {
+            RescheduleError::UnknownTimezone(timezone) => {
+                HttpResponse::BadRequest().body(format!("Unknown time zone: {}", timezone))
+            }
+            RescheduleError::UserNotFound => HttpResponse::NotFound().body("User not found"),
+            RescheduleError::TaskNotFound(_) => {
+                HttpResponse::NotFound().body("Task Doesn't exists")
+            }
+        }
+    }
+}
+
+/// Sets when `req.id` is due and announces the change. The caller saves.
+pub fn reschedule(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    req: RescheduleTask,
+) -> Result<Uuid, RescheduleError> {
+    let RescheduleTask {
+        id: task_id,
+        due_date,
+        due_time,
+        timezone,
+    } = req;
+    if let Some(timezone) = &timezone {
+        if parse_timezone(timezone).is_none() {
+            return Err(RescheduleError::UnknownTimezone(timezone.clone()));
+        }
+    }
+
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .ok_or(RescheduleError::UserNotFound)?;
+    let Some(task) = user.tasks.get_mut(&task_id) else {
+        warn!("Task-id: {} doesn't exists", task_id);
+        return Err(RescheduleError::TaskNotFound(task_id));
+    };
+    task.due_date = due_date;
+    task.due_time = due_time;
+    task.timezone = timezone;
+    data.publish_task(ChangeKind::Updated, task_id);
+    Ok(task_id)
+}
+
+/// API endpoint to change when a task is due
+/// URL: "/users/{userid}/tasks/reschedule"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/reschedule",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = RescheduleTask,
+    responses(
+        (status = 200, description = "Id of the rescheduled task", body = Uuid),
+        (status = 400, description = "Unknown time zone"),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn reschedule_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<RescheduleTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let due_date = req.due_date;
+    match reschedule(&mut state_data, user_id.into_inner(), req.into_inner()) {
+        Ok(task_id) => {
+            save_data(&state_data);
+            info!("Task-Id: {} rescheduled to {}", task_id, due_date);
+            HttpResponse::Ok().json(task_id)
+        }
+        Err(err) => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::Workflow;
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::{TimeZone, Utc};
+
+    #[actix_web::test]
+    async fn test_reschedule_task_with_zone() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/reschedule",
+            web::put().to(reschedule_task),
+        ))
+        .await;
+
+        // due at 17:00 Berlin time (UTC+2 in summer)
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/reschedule", user_id))
+            .set_json(RescheduleTask {
+                id: test_task_id,
+                due_date: NaiveDate::from_ymd_opt(2024, 7, 1).expect("valid date"),
+                due_time: NaiveTime::from_hms_opt(17, 0, 0),
+                timezone: Some("Europe/Berlin".to_string()),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/reschedule", user_id))
+            .set_json(RescheduleTask {
+                id: test_task_id,
+                due_date: NaiveDate::from_ymd_opt(2024, 7, 1).expect("valid date"),
+                due_time: None,
+                timezone: Some("Mars/Olympus_Mons".to_string()),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&user_id)
+                .and_then(|user| user.tasks.get(&test_task_id))
+                .cloned()
+                .expect("task missing");
+            let due = Utc.with_ymd_and_hms(2024, 7, 1, 15, 0, 0).unwrap();
+            let workflow = Workflow::default();
+            assert_eq!(task.due_at(chrono_tz::Tz::UTC), due);
+            assert!(!task.is_overdue(
+                due - chrono::TimeDelta::minutes(1),
+                chrono_tz::Tz::UTC,
+                &workflow
+            ));
+            assert!(task.is_overdue(due, chrono_tz::Tz::UTC, &workflow));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    events::ChangeKind,
+    routes::task_routes::{resolve_task, GetTask},
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use uuid::Uuid;
+
+/// API endpoint to clear a task's assignee
+/// URL: "/users/{userid}/tasks/unassign"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/unassign",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = GetTask,
+    responses(
+        (status = 200, description = "Id of the unassigned task", body = Uuid),
+        (status = 403, description = "Not an Editor of the task's workspace"),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn unassign_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<GetTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let task_id = req.id;
+    if let Err(err) = resolve_task(&state_data, user_id.into_inner(), task_id, Role::Editor) {
+        warn!("Task-id: {} can't be unassigned: {:?}", task_id, err);
+        return err.into();
+    }
+
+    if let Some(task) = state_data.all_tasks_mut().find(|task| task.id == task_id) {
+        task.assignee = None;
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+    info!("Task-Id: {} unassigned", task_id);
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_unassign_task() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(task) = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.get_mut(&test_task_id))
+            {
+                task.assignee = Some(owner_id);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/unassign",
+            web::put().to(unassign_task),
+        ))
+        .await;
+
+        let unassign = |user_id: Uuid, task_id: Uuid| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/tasks/unassign", user_id))
+                .set_json(GetTask { id: task_id })
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, unassign(owner_id, test_task_id)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // unknown user
+        let resp = test::call_service(&app, unassign(Uuid::new_v4(), test_task_id)).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // unknown task
+        let resp = test::call_service(&app, unassign(owner_id, Uuid::new_v4())).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&owner_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.assignee.is_none()));
+            state_data.users.remove(&owner_id);
+            save_data(&state_data);
+        };
+    }
+}
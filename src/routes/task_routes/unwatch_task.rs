@@ -0,0 +1,135 @@
+use crate::{
+    events::ChangeKind,
+    routes::task_routes::{resolve_task, WatchTask},
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use uuid::Uuid;
+
+/// API endpoint to remove a watcher from a task. On workspace tasks any member
+/// may stop watching, removing others takes an Editor.
+/// URL: "/users/{userid}/tasks/unwatch"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/unwatch",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = WatchTask,
+    responses(
+        (status = 200, description = "Id of the task", body = Uuid),
+        (status = 403, description = "Role in the task's workspace too low"),
+        (status = 404, description = "User or task not found"),
+    )
+)]
+pub async fn unwatch_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<WatchTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let WatchTask {
+        id: task_id,
+        watcher,
+    } = req.into_inner();
+    let required = if watcher == user_id {
+        Role::Viewer
+    } else {
+        Role::Editor
+    };
+    if let Err(err) = resolve_task(&state_data, user_id, task_id, required) {
+        warn!("Task-id: {} can't be unwatched: {:?}", task_id, err);
+        return err.into();
+    }
+
+    if let Some(task) = state_data.all_tasks_mut().find(|task| task.id == task_id) {
+        task.watchers.remove(&watcher);
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+    info!("UserID: {} stopped watching Task-Id: {}", watcher, task_id);
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Workspace;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_unwatch_workspace_task() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (viewer_id, _viewer_task_id) = create_test_user_and_task(&app_state);
+
+        // a shared task watched by both members
+        let mut workspace = Workspace::new("unwatch-workspace", owner_id);
+        let workspace_id = workspace.id;
+        workspace.members.insert(viewer_id, Role::Viewer);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let mut task = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.remove(&test_task_id))
+                .expect("test task exists");
+            task.watchers.extend([owner_id, viewer_id]);
+            workspace.tasks.insert(test_task_id, task);
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/unwatch",
+            web::put().to(unwatch_task),
+        ))
+        .await;
+
+        let unwatch = |user_id: Uuid, task_id: Uuid, watcher: Uuid| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/tasks/unwatch", user_id))
+                .set_json(WatchTask {
+                    id: task_id,
+                    watcher,
+                })
+                .to_request()
+        };
+
+        // a viewer can't remove someone else, but can stop watching
+        let resp = test::call_service(&app, unwatch(viewer_id, test_task_id, owner_id)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = test::call_service(&app, unwatch(viewer_id, test_task_id, viewer_id)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // unknown user
+        let resp = test::call_service(&app, unwatch(Uuid::new_v4(), test_task_id, owner_id)).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // unknown task
+        let resp = test::call_service(&app, unwatch(owner_id, Uuid::new_v4(), owner_id)).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let watchers = state_data
+                .workspaces
+                .get(&workspace_id)
+                .and_then(|workspace| workspace.tasks.get(&test_task_id))
+                .map(|task| task.watchers.clone());
+            assert_eq!(watchers, Some([owner_id].into_iter().collect()));
+
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&viewer_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -1,20 +1,127 @@
 use crate::{
-    schema::{save_data, Status},
+    board::{check_wip_limit, next_rank, MoveError},
+    events::ChangeKind,
+    schema::{save_data, AppStateData, Status, Task},
+    webhooks::{emit, EventKind},
+    workflow::Workflow,
     AppState,
 };
 use actix_web::{web, HttpResponse, Responder};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct UpdateTask {
     pub id: Uuid,
     pub status: Status,
 }
 
-/// API endpoint to create new task
+/// Why a task's status couldn't be changed
+#[derive(Debug, PartialEq)]
+pub enum UpdateError {
+    UserNotFound,
+    TaskNotFound(Uuid),
+    /// Refused by the user's workflow
+    Rejected(MoveError),
+}
+
+impl From<UpdateError> for HttpResponse {
+    fn from(err: UpdateError) -> Self {
+        match err {
+            UpdateError::UserNotFound => HttpResponse::NotFound().body("User not found"),
+            UpdateError::TaskNotFound(_) => HttpResponse::NotFound().body("Task Doesn't exists"),
+            UpdateError::Rejected(err) => err.into(),
+        }
+    }
+}
+
+/// Moves `req.id` into `req.status` if the user's workflow allows it, and
+/// announces the change. The caller saves.
+pub fn change_status(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    req: &UpdateTask,
+) -> Result<Uuid, UpdateError> {
+    // try finding the user in db
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .ok_or(UpdateError::UserNotFound)?;
+    let workflow = user.workflow();
+    let completed = set_status(&mut user.tasks, &workflow, req)?;
+    let task_id = req.id;
+
+    let payload = serde_json::to_value(&user.tasks[&task_id]).unwrap_or_default();
+    emit(data, EventKind::TaskUpdated, user_id, payload.clone());
+    if completed {
+        emit(data, EventKind::TaskCompleted, user_id, payload);
+    }
+    data.publish_task(ChangeKind::Updated, task_id);
+    Ok(task_id)
+}
+
+/// Moves `req.id` into `req.status` within `tasks`, governed by `workflow`,
+/// without announcing it. Returns whether the move completed the task.
+pub fn set_status(
+    tasks: &mut HashMap<Uuid, Task>,
+    workflow: &Workflow,
+    req: &UpdateTask,
+) -> Result<bool, UpdateError> {
+    let UpdateTask {
+        id: task_id,
+        status: task_status,
+    } = req.clone();
+
+    let current = tasks.get(&task_id).map(|task| task.status.clone());
+    if current.as_ref().is_some_and(|status| *status != task_status) {
+        check_wip_limit(tasks, workflow, &task_status).map_err(UpdateError::Rejected)?;
+    }
+    let rank = next_rank(tasks, &task_status);
+    let Some(task) = tasks.get_mut(&task_id) else {
+        warn!("Task-id: {} doesn't exists", task_id);
+        return Err(UpdateError::TaskNotFound(task_id));
+    };
+    // Only moves allowed by the workflow are accepted
+    if !workflow.has_state(&task_status) {
+        return Err(UpdateError::Rejected(MoveError::UnknownStatus(task_status)));
+    }
+    if !workflow.can_transition(&task.status, &task_status) {
+        warn!(
+            "Task-id: {} can't move from {} to {}",
+            task_id, task.status, task_status
+        );
+        return Err(UpdateError::Rejected(MoveError::TransitionNotAllowed {
+            from: task.status.clone(),
+            to: task_status,
+        }));
+    }
+    if task.status != task_status {
+        // A task changing column lands at the bottom of it
+        task.rank = rank;
+    }
+    let completed = !workflow.is_terminal(&task.status) && workflow.is_terminal(&task_status);
+    task.status = task_status;
+    Ok(completed)
+}
+
+/// API endpoint to move a task to another status
 /// URL: "/users/{userid}/tasks/update"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/update",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = UpdateTask,
+    responses(
+        (status = 200, description = "Id of the updated task", body = Uuid),
+        (status = 400, description = "Status not in the workflow"),
+        (status = 404, description = "User or task not found"),
+        (status = 409, description = "Transition not allowed or WIP limit reached"),
+    )
+)]
 pub async fn update_task(
     state_data: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -29,32 +136,14 @@ pub async fn update_task(
         }
     };
 
-    // try finding the user in db
-    let user = match state_data.users.get_mut(&user_id.into_inner()) {
-        Some(user) => user,
-        None => return HttpResponse::NotFound().body("User not found"),
-    };
-
-    // parse task_id
-    let UpdateTask {
-        id: task_id,
-        status: task_status,
-    } = req.into_inner();
-
-    if let Some(task) = user.tasks.get_mut(&task_id) {
-        task.status = task_status.clone();
-
-        // update the new data to DB
-        save_data(&state_data);
-
-        info!(
-            "Staus of Task-Id: {}, updated to: {:?}",
-            task_id, task_status
-        );
-        HttpResponse::Ok().json(task_id)
-    } else {
-        warn!("Task-id: {} doesn't exists", task_id);
-        HttpResponse::NotFound().body("Task Doesn't exists")
+    match change_status(&mut state_data, user_id.into_inner(), &req) {
+        Ok(task_id) => {
+            // update the new data to DB
+            save_data(&state_data);
+            info!("Staus of Task-Id: {}, updated to: {:?}", task_id, req.status);
+            HttpResponse::Ok().json(task_id)
+        }
+        Err(err) => err.into(),
     }
 }
 
@@ -118,4 +207,53 @@ mod test {
 
         // Clean up: Remove the test user to avoid duplicates
     }
+
+    #[actix_web::test]
+    async fn test_completing_task_emits_webhook() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let subscription = crate::webhooks::Subscription::new(
+            "http://localhost:9000/hook",
+            [EventKind::TaskCompleted].into(),
+            None,
+        );
+        let subscription_id = subscription.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription);
+            }
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/update", web::put().to(update_task)),
+        )
+        .await;
+
+        for status in [Status::InProgress, Status::Done] {
+            let req = test::TestRequest::put()
+                .uri(&format!("/users/{}/tasks/update", user_id))
+                .set_json(UpdateTask {
+                    id: test_task_id,
+                    status,
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let events: Vec<_> = state_data
+                .webhook_queue
+                .iter()
+                .filter(|delivery| delivery.subscription_id == subscription_id)
+                .map(|delivery| delivery.event.event)
+                .collect();
+            assert_eq!(events, vec![EventKind::TaskCompleted]);
+            crate::webhooks::forget_subscription(&mut state_data, subscription_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
 }
@@ -0,0 +1,148 @@
+use crate::{
+    events::{ChangeKind, EventScope},
+    routes::task_routes::resolve_task,
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct WatchTask {
+    pub id: Uuid,
+    pub watcher: Uuid,
+}
+
+/// API endpoint to add a watcher to a task. On workspace tasks any member may
+/// watch themselves, adding others takes an Editor; watchers must be members.
+/// URL: "/users/{userid}/tasks/watch"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/tasks/watch",
+    tag = "tasks",
+    params(("userId" = Uuid, Path)),
+    request_body = WatchTask,
+    responses(
+        (status = 200, description = "Id of the watched task", body = Uuid),
+        (status = 400, description = "Watcher is not a member of the task's workspace"),
+        (status = 403, description = "Role in the task's workspace too low"),
+        (status = 404, description = "User, watcher or task not found"),
+    )
+)]
+pub async fn watch_task(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<WatchTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let WatchTask {
+        id: task_id,
+        watcher,
+    } = req.into_inner();
+    if !state_data.users.contains_key(&watcher) {
+        return HttpResponse::NotFound().body(format!("Watcher UserID: {} not found", watcher));
+    }
+
+    let user_id = user_id.into_inner();
+    let required = if watcher == user_id {
+        Role::Viewer
+    } else {
+        Role::Editor
+    };
+    match resolve_task(&state_data, user_id, task_id, required) {
+        Ok(EventScope::Workspace(workspace_id))
+            if state_data.member_role(workspace_id, watcher).is_none() =>
+        {
+            return HttpResponse::BadRequest().body(format!(
+                "Watcher UserID: {} is not a member of this workspace",
+                watcher
+            ));
+        }
+        Ok(_) => {}
+        Err(err) => {
+            warn!("Task-id: {} can't be watched: {:?}", task_id, err);
+            return err.into();
+        }
+    }
+
+    if let Some(task) = state_data.all_tasks_mut().find(|task| task.id == task_id) {
+        task.watchers.insert(watcher);
+    }
+    state_data.publish_task(ChangeKind::Updated, task_id);
+    save_data(&state_data);
+    info!("UserID: {} is now watching Task-Id: {}", watcher, task_id);
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::task_routes::unwatch_task;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_watch_and_unwatch_task() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (watcher_id, _watcher_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/watch", web::put().to(watch_task))
+                .route(
+                    "/users/{user_id}/tasks/unwatch",
+                    web::put().to(unwatch_task),
+                ),
+        )
+        .await;
+
+        let payload = WatchTask {
+            id: test_task_id,
+            watcher: watcher_id,
+        };
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/watch", owner_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        if let Ok(state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&owner_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.watchers.contains(&watcher_id)));
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/tasks/unwatch", owner_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = state_data
+                .users
+                .get(&owner_id)
+                .and_then(|user| user.tasks.get(&test_task_id));
+            assert!(task.is_some_and(|task| task.watchers.is_empty()));
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&watcher_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    todotxt::Item,
+    transfer::{csv_header, csv_row, Format},
+    AppState, Task,
+};
+use actix_web::{http::header, web, HttpResponse, Responder};
+use futures_util::stream;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: Format,
+}
+
+/// API endpoint to download all of a user's tasks as CSV, JSON, todo.txt or
+/// a Markdown checklist
+/// URL: "/users/{userid}/tasks/export?format=csv"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/tasks/export",
+    tag = "transfer",
+    params(("userId" = Uuid, Path), ExportQuery),
+    responses(
+        (status = 200, description = "All tasks as a download", content(("application/json"), ("text/csv"), ("text/plain"), ("text/markdown"))),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn export_tasks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ExportQuery>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let (mut tasks, workflow): (Vec<Task>, _) = match state_data.users.get(&user_id) {
+        Some(user) => (user.tasks.values().cloned().collect(), user.workflow()),
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    // The rows are encoded while streaming, after the lock is released
+    drop(state_data);
+    tasks.sort_by_key(|task| (task.due_date, task.rank, task.id));
+    info!("Exporting {} tasks of User-Id: {}", tasks.len(), user_id);
+
+    let (content_type, filename, chunks): (_, _, Box<dyn Iterator<Item = Vec<u8>>>) = match query
+        .format
+    {
+        Format::Csv => (
+            "text/csv; charset=utf-8",
+            "tasks.csv",
+            Box::new(
+                std::iter::once(csv_header()).chain(tasks.into_iter().map(|task| csv_row(&task))),
+            ),
+        ),
+        Format::Json => {
+            let rows = tasks.into_iter().enumerate().map(|(index, task)| {
+                let mut chunk = if index == 0 {
+                    b"\n".to_vec()
+                } else {
+                    b",\n".to_vec()
+                };
+                chunk.extend(serde_json::to_vec(&task).unwrap_or_default());
+                chunk
+            });
+            (
+                "application/json",
+                "tasks.json",
+                Box::new(
+                    std::iter::once(b"[".to_vec())
+                        .chain(rows)
+                        .chain(std::iter::once(b"\n]\n".to_vec())),
+                ),
+            )
+        }
+        Format::TodoTxt => (
+            "text/plain; charset=utf-8",
+            "todo.txt",
+            Box::new(tasks.into_iter().map(move |task| {
+                let line = Item::from_task(&task, &workflow).to_todo_txt();
+                format!("{}\n", line).into_bytes()
+            })),
+        ),
+        Format::Markdown => (
+            "text/markdown; charset=utf-8",
+            "tasks.md",
+            Box::new(
+                tasks
+                    .into_iter()
+                    .map(move |task| Item::from_task(&task, &workflow).to_markdown().into_bytes()),
+            ),
+        ),
+    };
+
+    HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        ))
+        .streaming(stream::iter(
+            chunks.map(|chunk| Ok::<_, actix_web::Error>(web::Bytes::from(chunk))),
+        ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::transfer::CSV_COLUMNS;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_export_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/tasks/export", web::get().to(export_tasks)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/export?format=csv", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/csv; charset=utf-8"
+        );
+        let body = test::read_body(resp).await;
+        let mut reader = csv::Reader::from_reader(body.as_ref());
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<_>>(),
+            CSV_COLUMNS
+        );
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(&rows[0][0], test_task_id.to_string());
+        assert_eq!(&rows[0][3], "2000-01-01");
+        assert_eq!(&rows[0][6], "ToDo");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/export", user_id))
+            .to_request();
+        let tasks: Vec<Task> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, test_task_id);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{
+    schema::save_data,
+    todotxt::{keep_states, parse_markdown, parse_todo_txt, rows},
+    transfer::{
+        import_rows, parse_columns, parse_csv, parse_json, Format, ImportReport, OnDuplicate,
+    },
+    AppState,
+};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+/// Largest accepted import body
+pub const MAX_IMPORT_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// Defaults to CSV for a `text/csv` body, todo.txt for `text/plain`,
+    /// Markdown for `text/markdown` and JSON otherwise
+    #[serde(default)]
+    pub format: Option<Format>,
+    /// Validate and report without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub on_duplicate: OnDuplicate,
+    /// `Source:field` pairs mapping input columns to task fields
+    #[serde(default)]
+    pub columns: Option<String>,
+}
+
+/// API endpoint to import tasks from CSV, JSON, todo.txt or a Markdown checklist
+/// URL: "/users/{userid}/tasks/import?format=csv&dry_run=true&on_duplicate=skip&columns=Name:title"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/tasks/import",
+    tag = "transfer",
+    params(("userId" = Uuid, Path), ImportQuery),
+    request_body(description = "Tasks as CSV, JSON, todo.txt or a Markdown checklist", content(("application/json"), ("text/csv"), ("text/plain"), ("text/markdown"))),
+    responses(
+        (status = 200, description = "What happened to each row", body = ImportReport),
+        (status = 400, description = "Unreadable body, format or column mapping"),
+        (status = 404, description = "User not found"),
+        (status = 413, description = "Body too large"),
+    )
+)]
+pub async fn import_tasks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ImportQuery>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> impl Responder {
+    let body = match payload.to_bytes_limited(MAX_IMPORT_BYTES).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(err)) => return HttpResponse::BadRequest().body(err.to_string()),
+        Err(_) => {
+            return HttpResponse::PayloadTooLarge()
+                .body(format!("Imports are limited to {} bytes", MAX_IMPORT_BYTES))
+        }
+    };
+    let columns = match query.columns.as_deref().map(parse_columns) {
+        Some(Ok(columns)) => columns,
+        Some(Err(err)) => return HttpResponse::BadRequest().body(err),
+        None => HashMap::new(),
+    };
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let format = query.format.unwrap_or(match content_type {
+        t if t.starts_with("text/csv") => Format::Csv,
+        t if t.starts_with("text/plain") => Format::TodoTxt,
+        t if t.starts_with("text/markdown") => Format::Markdown,
+        _ => Format::Json,
+    });
+
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    // checked and open items map onto the user's workflow
+    let text = || std::str::from_utf8(&body).map_err(|_| "Body must be UTF-8".to_string());
+    let rows = match format {
+        Format::Csv => parse_csv(&body),
+        Format::Json => parse_json(&body),
+        Format::TodoTxt => text().map(|text| rows(parse_todo_txt(text), &user.workflow())),
+        Format::Markdown => text().map(|text| rows(parse_markdown(text), &user.workflow())),
+    };
+    let mut rows = match rows {
+        Ok(rows) => rows,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    if matches!(format, Format::TodoTxt | Format::Markdown) {
+        // due dates are optional in these formats, tasks without one are due today
+        let today = Utc::now().with_timezone(&user.tz()).date_naive();
+        for fields in rows.iter_mut().flatten() {
+            fields
+                .entry("due_date")
+                .or_insert(Value::String(today.to_string()));
+        }
+        keep_states(&mut rows, user);
+    }
+    let report = match import_rows(
+        &mut state_data,
+        user_id,
+        rows,
+        &columns,
+        query.on_duplicate,
+        query.dry_run,
+    ) {
+        Some(report) => report,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+    if !report.dry_run && report.created + report.updated > 0 {
+        save_data(&state_data);
+    }
+
+    info!(
+        "Import for User-Id: {}: {} created, {} updated, {} skipped, {} invalid{}",
+        user_id,
+        report.created,
+        report.updated,
+        report.skipped,
+        report.invalid,
+        if report.dry_run { " (dry run)" } else { "" }
+    );
+    HttpResponse::Ok().json(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Status;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::transfer::{ImportReport, RowAction};
+    use crate::webhooks::{forget_subscription, EventKind, Subscription};
+    use crate::workflow::{Transition, Workflow, WorkflowState};
+    use actix_web::{http::StatusCode, test, App};
+    use serde_json::json;
+
+    #[actix_web::test]
+    async fn test_import_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new(
+            "http://localhost:9000/hook",
+            [EventKind::TaskCompleted].into(),
+            None,
+        );
+        let subscription_id = subscription.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/import",
+            web::post().to(import_tasks),
+        ))
+        .await;
+
+        let csv = "Name,Deadline,State,Notes\n\
+                   Write report,2024-03-01,InProgress,\"quarterly, final\"\n\
+                   No date,,ToDo,\n\
+                   Write report,2024-03-01,Done,again\n\
+                   sample-title,2000-01-01,ToDo,\n";
+        let import = |query: &str| {
+            test::TestRequest::post()
+                .uri(&format!(
+                    "/users/{}/tasks/import?columns=Name:title,Deadline:due_date,State:status,Notes:description{}",
+                    user_id, query
+                ))
+                .insert_header((header::CONTENT_TYPE, "text/csv"))
+                .set_payload(csv)
+                .to_request()
+        };
+
+        // a dry run reports every row and changes nothing
+        let resp = test::call_service(&app, import("&dry_run=true")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let report: ImportReport = test::read_body_json(resp).await;
+        let actions: Vec<_> = report.rows.iter().map(|row| row.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                RowAction::Created,
+                RowAction::Invalid,
+                RowAction::Skipped,
+                RowAction::Skipped
+            ]
+        );
+        assert!(report.rows[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("due_date"));
+        assert_eq!(report.rows[3].task_id, Some(test_task_id));
+        if let Ok(state_data) = app_state.data.lock() {
+            assert_eq!(state_data.users[&user_id].tasks.len(), 1);
+        };
+
+        let report: ImportReport = test::call_and_read_body_json(&app, import("")).await;
+        assert_eq!((report.created, report.skipped, report.invalid), (1, 2, 1));
+        let imported = report.rows[0].task_id.unwrap();
+
+        // JSON rows updating the imported task
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/import?on_duplicate=update",
+                user_id
+            ))
+            .set_json(json!([
+                { "id": imported, "title": "Write report", "due_date": "2024-03-08", "status": "Done" },
+                { "title": "Unknown state", "due_date": "2024-03-08", "status": "Blocked" }
+            ]))
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(report.rows[0].action, RowAction::Updated);
+        assert_eq!(report.rows[1].action, RowAction::Invalid);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let task = &state_data.users[&user_id].tasks[&imported];
+            assert_eq!(task.status, Status::Done);
+            // columns the rows leave out keep their value
+            assert_eq!(task.description, "quarterly, final");
+            assert_eq!(task.due_date.to_string(), "2024-03-08");
+            // completing a task on import announces it like a move on the board
+            let completed: Vec<_> = state_data
+                .webhook_queue
+                .iter()
+                .filter(|delivery| delivery.subscription_id == subscription_id)
+                .map(|delivery| delivery.event.data["id"].clone())
+                .collect();
+            assert_eq!(completed, vec![json!(imported)]);
+            forget_subscription(&mut state_data, subscription_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_import_follows_workflow() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                // at most one task in progress, and nothing skips it on the way to done
+                let mut workflow = Workflow::default();
+                workflow.states[1].wip_limit = Some(1);
+                workflow
+                    .transitions
+                    .retain(|t| !(t.from == Status::ToDo && t.to == Status::Done));
+                user.workflow = Some(workflow);
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/import",
+            web::post().to(import_tasks),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/import?on_duplicate=update",
+                user_id
+            ))
+            .set_json(json!([
+                { "id": test_task_id, "title": "sample-title", "due_date": "2000-01-01", "status": "Done" },
+                { "id": test_task_id, "title": "sample-title", "due_date": "2000-01-01", "status": "InProgress", "tags": ["urgent"] },
+                { "title": "one too many", "due_date": "2000-01-02", "status": "InProgress" }
+            ]))
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        let actions: Vec<_> = report.rows.iter().map(|row| row.action).collect();
+        assert_eq!(
+            actions,
+            vec![RowAction::Invalid, RowAction::Updated, RowAction::Invalid]
+        );
+        assert!(report.rows[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("not allowed"));
+        assert!(report.rows[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("WIP limit"));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks.len(), 1);
+            let task = &tasks[&test_task_id];
+            assert_eq!(task.status, Status::InProgress);
+            assert_eq!(task.tags, vec!["urgent"]);
+            assert_eq!(task.description, "sample-info");
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_todo_txt_and_markdown_round_trip() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/users/{user_id}/tasks/import",
+                    web::post().to(import_tasks),
+                )
+                .route(
+                    "/users/{user_id}/tasks/export",
+                    web::get().to(crate::export_tasks),
+                ),
+        )
+        .await;
+
+        let checklist = "## This week
+                         - [ ] (A) Book flights +travel @laptop due:2024-05-01
+  window seat
+                         - [x] Renew passport +travel
+";
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/tasks/import", user_id))
+            .insert_header((header::CONTENT_TYPE, "text/markdown"))
+            .set_payload(checklist)
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(report.created, 2);
+        let flights = report.rows[0].task_id.unwrap();
+        let passport = report.rows[1].task_id.unwrap();
+        if let Ok(state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks[&flights].priority, Some('A'));
+            assert_eq!(tasks[&flights].tags, vec!["travel", "@laptop"]);
+            assert_eq!(tasks[&flights].description, "window seat");
+            // no due date in the list, so it's due today
+            assert_eq!(tasks[&passport].due_date, Utc::now().date_naive());
+            assert_eq!(tasks[&passport].status, Status::Done);
+        };
+
+        // the todo.txt export imports back onto the same tasks
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/export?format=todo_txt", user_id))
+            .to_request();
+        let todo_txt = test::call_and_read_body(&app, req).await;
+        let todo_txt = String::from_utf8(todo_txt.to_vec()).unwrap();
+        assert!(todo_txt.contains(&format!(
+            "(A) Book flights +travel @laptop due:2024-05-01 id:{}\n",
+            flights
+        )));
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/import?on_duplicate=update",
+                user_id
+            ))
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .set_payload(todo_txt)
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        assert_eq!((report.created, report.updated), (0, 3));
+        if let Ok(state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks.len(), 3);
+            assert_eq!(tasks[&flights].tags, vec!["travel", "@laptop"]);
+            // todo.txt has no descriptions to overwrite
+            assert_eq!(tasks[&flights].description, "window seat");
+            assert_eq!(tasks[&passport].status, Status::Done);
+        };
+
+        // so does the Markdown export, which exports the same again
+        let export = || {
+            test::TestRequest::get()
+                .uri(&format!("/users/{}/tasks/export?format=markdown", user_id))
+                .to_request()
+        };
+        let markdown = test::call_and_read_body(&app, export()).await;
+        assert!(String::from_utf8(markdown.to_vec())
+            .unwrap()
+            .contains(&format!("id:{}\n  window seat\n", flights)));
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/tasks/import?on_duplicate=update",
+                user_id
+            ))
+            .insert_header((header::CONTENT_TYPE, "text/markdown"))
+            .set_payload(markdown.clone())
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        assert_eq!((report.created, report.updated), (0, 3));
+        assert_eq!(test::call_and_read_body(&app, export()).await, markdown);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert_eq!(state_data.users[&user_id].tasks.len(), 3);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_todo_txt_follows_custom_workflow() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let states = ["Open", "Doing", "Closed"].map(|name| Status::Custom(name.to_string()));
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            user.workflow = Some(Workflow {
+                states: states
+                    .iter()
+                    .map(|status| WorkflowState {
+                        status: status.clone(),
+                        terminal: status.as_str() == "Closed",
+                        wip_limit: None,
+                    })
+                    .collect(),
+                transitions: states
+                    .iter()
+                    .flat_map(|from| {
+                        states.iter().map(|to| Transition {
+                            from: from.clone(),
+                            to: to.clone(),
+                        })
+                    })
+                    .collect(),
+            });
+            user.tasks.get_mut(&test_task_id).unwrap().status = states[1].clone();
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/tasks/import",
+            web::post().to(import_tasks),
+        ))
+        .await;
+        let import = |todo_txt: String| {
+            test::TestRequest::post()
+                .uri(&format!(
+                    "/users/{}/tasks/import?on_duplicate=update",
+                    user_id
+                ))
+                .insert_header((header::CONTENT_TYPE, "text/plain"))
+                .set_payload(todo_txt)
+                .to_request()
+        };
+
+        // an open item leaves a task in progress where it is
+        let report: ImportReport = test::call_and_read_body_json(
+            &app,
+            import(format!(
+                "sample-title due:2000-01-01 id:{}\nx Archive mail\n",
+                test_task_id
+            )),
+        )
+        .await;
+        assert_eq!((report.created, report.updated), (1, 1));
+        let archived = report.rows[1].task_id.unwrap();
+        if let Ok(state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks[&test_task_id].status, states[1]);
+            assert_eq!(tasks[&test_task_id].description, "sample-info");
+            assert_eq!(tasks[&archived].status, states[2]);
+        };
+
+        // checking and unchecking items closes and reopens their tasks
+        let report: ImportReport = test::call_and_read_body_json(
+            &app,
+            import(format!(
+                "x sample-title due:2000-01-01 id:{}\nArchive mail id:{}\n",
+                test_task_id, archived
+            )),
+        )
+        .await;
+        assert_eq!(report.updated, 2);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let tasks = &state_data.users[&user_id].tasks;
+            assert_eq!(tasks[&test_task_id].status, states[2]);
+            assert_eq!(tasks[&archived].status, states[0]);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
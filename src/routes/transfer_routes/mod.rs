@@ -0,0 +1,5 @@
+pub mod export_tasks;
+pub mod import_tasks;
+
+pub use export_tasks::{export_tasks, ExportQuery};
+pub use import_tasks::{import_tasks, ImportQuery};
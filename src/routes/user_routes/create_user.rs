@@ -1,19 +1,123 @@
 // src/routes/user_routes/create_user.rs
 use crate::{
-    schema::{save_data, User},
+    schema::{is_valid_email, parse_timezone, save_data, AppStateData, User, UsernameError},
+    webhooks::{emit, EventKind},
     AppState,
 };
 use actix_web::{web, HttpResponse, Responder};
-use log::{error, info};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct NewUser {
+    /// Client-chosen id, e.g. to keep references when importing; generated when omitted
+    #[serde(default)]
+    pub id: Option<Uuid>,
     pub name: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
-/// API endpoint to create new task
-/// URL: "/users/delete/"
+/// Why a user couldn't be created
+#[derive(Debug, PartialEq)]
+pub enum SignupError {
+    EmptyName,
+    NameTaken(String),
+    NilId,
+    IdTaken(Uuid),
+    InvalidEmail(String),
+    UnknownTimezone(String),
+}
+
+impl From<SignupError> for HttpResponse {
+    fn from(err: SignupError) -> Self {
+        match err {
+            SignupError::EmptyName => HttpResponse::BadRequest().body("Username can't be empty"),
+            SignupError::NameTaken(name) => {
+                HttpResponse::Conflict().body(format!("Username: {} already taken", name))
+            }
+            SignupError::NilId => HttpResponse::BadRequest().body("User id can't be nil"),
+            SignupError::IdTaken(user_id) => {
+                HttpResponse::Conflict().body(format!("UserID: {} already exists", user_id))
+            }
+            SignupError::InvalidEmail(email) => {
+                HttpResponse::BadRequest().body(format!("Invalid email: {}", email))
+            }
+            SignupError::UnknownTimezone(timezone) => {
+                HttpResponse::BadRequest().body(format!("Unknown time zone: {}", timezone))
+            }
+        }
+    }
+}
+
+/// Adds the user `req` describes and announces it. The caller saves.
+pub fn insert_user(data: &mut AppStateData, req: &NewUser) -> Result<Uuid, SignupError> {
+    // Usernames must be non-empty and unique (case-insensitive)
+    match data.validate_username(&req.name, None) {
+        Ok(()) => {}
+        Err(UsernameError::Empty) => return Err(SignupError::EmptyName),
+        Err(UsernameError::Taken) => {
+            warn!("Username: {} already taken", req.name);
+            return Err(SignupError::NameTaken(req.name.clone()));
+        }
+    }
+
+    if let Some(user_id) = req.id {
+        if user_id.is_nil() {
+            return Err(SignupError::NilId);
+        }
+        if data.users.contains_key(&user_id) {
+            warn!("UserID: {} already exists", user_id);
+            return Err(SignupError::IdTaken(user_id));
+        }
+    }
+
+    if let Some(email) = &req.email {
+        if !is_valid_email(email) {
+            return Err(SignupError::InvalidEmail(email.clone()));
+        }
+    }
+    if let Some(timezone) = &req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Err(SignupError::UnknownTimezone(timezone.clone()));
+        }
+    }
+
+    let mut new_user = User::new(req.name.trim());
+    if let Some(user_id) = req.id {
+        new_user.id = user_id;
+    }
+    new_user.email = req.email.clone();
+    new_user.display_name = req.display_name.clone();
+    new_user.timezone = req.timezone.clone();
+    let user_id = new_user.id;
+    let payload = serde_json::json!({ "id": user_id, "name": new_user.name });
+
+    // Add new user to the DB
+    data.users.insert(user_id, new_user);
+    emit(data, EventKind::UserCreated, user_id, payload);
+    Ok(user_id)
+}
+
+/// API endpoint to create new user
+/// URL: "/users/create"
+#[utoipa::path(
+    post,
+    path = "/users/create",
+    tag = "users",
+    request_body = NewUser,
+    responses(
+        (status = 200, description = "Id of the new user", body = Uuid),
+        (status = 400, description = "Empty name, nil id, invalid email or unknown time zone"),
+        (status = 409, description = "Name or id already taken"),
+    )
+)]
 pub async fn create_user(
     state_data: web::Data<AppState>,
     req: web::Json<NewUser>,
@@ -27,25 +131,30 @@ pub async fn create_user(
         }
     };
 
-    let new_user = User::new(&req.name);
-    let user_id = new_user.id;
-
-    // Add new user to the DB
-    state_data.users.insert(user_id, new_user);
-
-    // Update the new data to DB
-    save_data(&state_data);
-
-    info!("User created successfully with ID: {}", user_id);
-    HttpResponse::Ok().json(user_id)
+    match insert_user(&mut state_data, &req) {
+        Ok(user_id) => {
+            // Update the new data to DB
+            save_data(&state_data);
+            info!("User created successfully with ID: {}", user_id);
+            HttpResponse::Ok().json(user_id)
+        }
+        Err(err) => err.into(),
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::Config;
     use crate::load_data;
+    use crate::routes::user_routes::remove_user;
+    use crate::schema::load_data_from;
+    use crate::test_utils::temp_config;
+    use crate::webhooks::Subscription;
+    use actix_web::http::StatusCode;
     use actix_web::{test, App};
+    use std::collections::HashSet;
     use std::sync::Mutex;
     use uuid::Uuid;
 
@@ -53,6 +162,7 @@ mod test {
     async fn test_create_user() {
         let app_state = web::Data::new(AppState {
             data: Mutex::new(load_data()),
+            config: Config::default(),
         });
 
         // creating Test app
@@ -64,10 +174,15 @@ mod test {
         .await;
 
         // creating req for api
+        let name = format!("Test-User-{}", Uuid::new_v4());
         let req = test::TestRequest::post()
             .uri("/users/create")
             .set_json(NewUser {
-                name: "Test-User".to_string(),
+                id: None,
+                name: name.clone(),
+                email: Some("test-user@example.com".to_string()),
+                display_name: None,
+                timezone: None,
             })
             .to_request();
 
@@ -75,6 +190,34 @@ mod test {
         let resp: Uuid = test::call_and_read_body_json(&app, req).await;
         assert!(!resp.is_nil());
 
+        // same name with different casing must be rejected
+        let req = test::TestRequest::post()
+            .uri("/users/create")
+            .set_json(NewUser {
+                id: None,
+                name: name.to_uppercase(),
+                email: None,
+                display_name: None,
+                timezone: None,
+            })
+            .to_request();
+        let dup_resp = test::call_service(&app, req).await;
+        assert_eq!(dup_resp.status(), StatusCode::CONFLICT);
+
+        // a client-chosen id already in use is rejected as well
+        let req = test::TestRequest::post()
+            .uri("/users/create")
+            .set_json(NewUser {
+                id: Some(resp),
+                name: format!("Test-User-{}", Uuid::new_v4()),
+                email: None,
+                display_name: None,
+                timezone: None,
+            })
+            .to_request();
+        let dup_resp = test::call_service(&app, req).await;
+        assert_eq!(dup_resp.status(), StatusCode::CONFLICT);
+
         // forcefully reomving "new-test-user" to avoid duplicates
         if let Ok(mut state_data) = app_state.data.lock() {
             if state_data.users.remove(&resp).is_some() {
@@ -82,4 +225,63 @@ mod test {
             }
         };
     }
+
+    #[actix_web::test]
+    async fn test_create_user_empty_name() {
+        let app_state = web::Data::new(AppState {
+            data: Mutex::new(load_data()),
+            config: Config::default(),
+        });
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/create", web::post().to(create_user)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/users/create")
+            .set_json(NewUser {
+                id: None,
+                name: "   ".to_string(),
+                email: None,
+                display_name: None,
+                timezone: None,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_user_events_stay_private() {
+        let config = temp_config();
+        let mut data = load_data_from(&config.data_file());
+
+        // another tenant listening to every event
+        let mut other = User::new(&format!("other-{}", Uuid::new_v4()));
+        other.webhooks.push(Subscription::new(
+            "http://localhost/hook",
+            HashSet::new(),
+            None,
+        ));
+        let other_id = other.id;
+        data.users.insert(other_id, other);
+
+        let user_id = insert_user(
+            &mut data,
+            &NewUser {
+                id: None,
+                name: format!("private-{}", Uuid::new_v4()),
+                email: Some("private@example.com".to_string()),
+                display_name: None,
+                timezone: None,
+            },
+        )
+        .expect("signup failed");
+        assert!(remove_user(&mut data, &config.blob_dir(), user_id));
+        assert!(data.webhook_queue.is_empty());
+    }
 }
@@ -1,16 +1,61 @@
-use crate::{schema::save_data, AppState};
+use crate::{
+    blobs::remove_orphaned_blobs,
+    schema::{save_data, AppStateData},
+    webhooks::{emit, forget_subscription, EventKind},
+    AppState,
+};
 use actix_web::{web, HttpResponse, Responder};
 use log::{error, info};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteUser {
     pub id: Uuid,
 }
 
+/// Removes `user_id` with everything only they own and announces it.
+/// Returns whether the user existed; the caller saves.
+pub fn remove_user(data: &mut AppStateData, blob_dir: &Path, user_id: Uuid) -> bool {
+    let Some(user) = data.users.remove(&user_id) else {
+        return false;
+    };
+    for task_id in user.tasks.keys() {
+        data.comments.remove(task_id);
+    }
+    // Shared workspaces outlive the user; only their membership goes away
+    let workspace_shas = data.remove_user_memberships(user_id);
+    // Tasks assigned to or watched by the user stay with their owners
+    data.remove_user_assignments(user_id);
+    for subscription in &user.webhooks {
+        forget_subscription(data, subscription.id);
+    }
+    let payload = serde_json::json!({ "id": user_id, "name": user.name });
+    emit(data, EventKind::UserDeleted, user_id, payload);
+    let shas = user
+        .tasks
+        .into_values()
+        .flat_map(|task| task.attachments)
+        .map(|attachment| attachment.sha256)
+        .chain(workspace_shas);
+    remove_orphaned_blobs(data, blob_dir, shas);
+    true
+}
+
 /// API endpoint to delete a user
 /// URL: "/users/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/delete",
+    tag = "users",
+    request_body = DeleteUser,
+    responses(
+        (status = 200, description = "User deleted"),
+        (status = 404, description = "User not found"),
+    )
+)]
 pub async fn delete_user(
     req: web::Json<DeleteUser>,
     state_data: web::Data<AppState>,
@@ -28,7 +73,7 @@ pub async fn delete_user(
     info!("Removing user: {} from db", user_id);
 
     // Attempt to remove the user from the database
-    if db.users.remove(&user_id).is_some() {
+    if remove_user(&mut db, &state_data.config.blob_dir(), user_id) {
         save_data(&db);
         HttpResponse::Ok().body(format!("UserID: {} deleted", user_id))
     } else {
@@ -40,6 +85,7 @@ pub async fn delete_user(
 mod test {
 
     use super::*;
+    use crate::Config;
     use crate::schema::load_data;
     use crate::schema::User;
     use actix_web::http::StatusCode;
@@ -50,6 +96,7 @@ mod test {
     async fn test_delete_user() {
         let app_state = web::Data::new(AppState {
             data: Mutex::new(load_data()),
+            config: Config::default(),
         });
 
         // adding 'test-user' before deleting it.
@@ -79,4 +126,43 @@ mod test {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[actix_web::test]
+    async fn test_remove_user_drops_workspace_blobs() {
+        use crate::blobs::{blob_path, store_blob};
+        use crate::schema::{Attachment, Task, Workspace};
+        use crate::test_utils::temp_config;
+        use chrono::{NaiveDate, Utc};
+
+        let config = temp_config();
+        let blob_dir = config.blob_dir();
+        let sha256 = store_blob(&blob_dir, b"workspace notes").expect("failed to store blob");
+
+        // the user's only workspace goes away with them, attachments included
+        let mut data = AppStateData::default();
+        let user = User::new("test-delete-user");
+        let user_id = user.id;
+        data.users.insert(user_id, user);
+        let mut task = Task::new(
+            "sample-title",
+            "sample-info",
+            NaiveDate::from_ymd_opt(2000, 1, 1).expect("failed to parse NaiveDate"),
+        );
+        task.attachments.push(Attachment {
+            id: Uuid::new_v4(),
+            name: "notes.txt".to_string(),
+            size: 15,
+            mime_type: "text/plain".to_string(),
+            sha256: sha256.clone(),
+            uploaded_at: Utc::now(),
+        });
+        let mut workspace = Workspace::new("delete-user-workspace", user_id);
+        workspace.tasks.insert(task.id, task);
+        data.workspaces.insert(workspace.id, workspace);
+
+        assert!(remove_user(&mut data, &blob_dir, user_id));
+        assert!(data.workspaces.is_empty());
+        assert!(!blob_path(&blob_dir, &sha256).unwrap().exists());
+        let _ = std::fs::remove_dir_all(&config.data_dir);
+    }
 }
@@ -0,0 +1,100 @@
+use crate::{schema::User, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Public view of a user, without the task map
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub task_count: usize,
+}
+
+impl From<&User> for UserProfile {
+    fn from(user: &User) -> Self {
+        UserProfile {
+            id: user.id,
+            name: user.name.clone(),
+            email: user.email.clone(),
+            display_name: user.display_name.clone(),
+            timezone: user.timezone.clone(),
+            task_count: user.tasks.len(),
+        }
+    }
+}
+
+/// API endpoint to fetch a user's profile
+/// URL: "/users/{userid}"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}",
+    tag = "users",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = UserProfile),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn get_user(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Try acquiring the lock on the mutex
+    let state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+
+    match state_data.users.get(&user_id) {
+        Some(user) => HttpResponse::Ok().json(UserProfile::from(user)),
+        None => HttpResponse::NotFound().body(format!("UserID: {} not found", user_id)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_get_user() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}", web::get().to(get_user)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let profile: UserProfile = test::read_body_json(resp).await;
+        assert_eq!(profile.id, user_id);
+        assert_eq!(profile.task_count, 1);
+
+        // unknown user
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{routes::user_routes::UserProfile, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserList {
+    pub users: Vec<UserProfile>,
+}
+
+/// API endpoint to list all users, sorted by name
+/// URL: "/users/list"
+#[utoipa::path(
+    get,
+    path = "/users/list",
+    tag = "users",
+    responses((status = 200, body = UserList))
+)]
+pub async fn list_users(state_data: web::Data<AppState>) -> impl Responder {
+    // Try acquiring the lock on the mutex
+    let state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let mut users: Vec<UserProfile> = state_data.users.values().map(UserProfile::from).collect();
+    users.sort_by_key(|user| user.name.to_lowercase());
+
+    HttpResponse::Ok().json(UserList { users })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_users() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/list", web::get().to(list_users)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/users/list").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let list: UserList = test::read_body_json(resp).await;
+        assert!(list.users.iter().any(|user| user.id == user_id));
+    }
+}
@@ -1,5 +1,13 @@
 pub mod create_user;
 pub mod delete_user;
+pub mod get_user;
+pub mod list_users;
+pub mod rename_user;
+pub mod update_user;
 
-pub use create_user::{create_user, NewUser};
-pub use delete_user::{delete_user, DeleteUser};
\ No newline at end of file
+pub use create_user::{create_user, insert_user, NewUser, SignupError};
+pub use delete_user::{delete_user, remove_user, DeleteUser};
+pub use get_user::{get_user, UserProfile};
+pub use list_users::{list_users, UserList};
+pub use rename_user::{rename_user, RenameUser};
+pub use update_user::{update_user, UpdateUser};
\ No newline at end of file
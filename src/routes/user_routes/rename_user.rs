@@ -0,0 +1,115 @@
+use crate::{
+    schema::{save_data, UsernameError},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RenameUser {
+    pub name: String,
+}
+
+/// API endpoint to rename a user
+/// URL: "/users/{userid}/rename"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/rename",
+    tag = "users",
+    params(("userId" = Uuid, Path)),
+    request_body = RenameUser,
+    responses(
+        (status = 200, description = "Id of the renamed user", body = Uuid),
+        (status = 400, description = "Empty name"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Name already taken"),
+    )
+)]
+pub async fn rename_user(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<RenameUser>,
+) -> impl Responder {
+    // Try acquiring the lock on the mutex
+    let mut state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body(format!("UserID: {} not found", user_id));
+    }
+
+    // Renaming to your own name with different casing is allowed
+    match state_data.validate_username(&req.name, Some(user_id)) {
+        Ok(()) => {}
+        Err(UsernameError::Empty) => {
+            return HttpResponse::BadRequest().body("Username can't be empty");
+        }
+        Err(UsernameError::Taken) => {
+            warn!("Username: {} already taken", req.name);
+            return HttpResponse::Conflict().body(format!("Username: {} already taken", req.name));
+        }
+    }
+
+    if let Some(user) = state_data.users.get_mut(&user_id) {
+        user.name = req.name.trim().to_string();
+    }
+    save_data(&state_data);
+
+    info!("UserID: {} renamed to: {}", user_id, req.name.trim());
+    HttpResponse::Ok().json(user_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_rename_user() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+        let (other_id, _other_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/rename", web::put().to(rename_user)),
+        )
+        .await;
+
+        let new_name = format!("renamed-{}", user_id);
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/rename", user_id))
+            .set_json(RenameUser { name: new_name.clone() })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // another user can't take the same name, regardless of casing
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/rename", other_id))
+            .set_json(RenameUser { name: new_name.to_uppercase() })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get(&user_id) {
+                assert_eq!(user.name, new_name);
+            }
+            state_data.users.remove(&user_id);
+            state_data.users.remove(&other_id);
+            save_data(&state_data);
+        };
+    }
+}
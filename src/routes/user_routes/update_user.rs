@@ -0,0 +1,138 @@
+use crate::{
+    routes::user_routes::UserProfile,
+    schema::{is_valid_email, parse_timezone, save_data},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Profile fields to change; omitted fields are left untouched
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct UpdateUser {
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// API endpoint to update a user's optional profile fields
+/// URL: "/users/{userid}/update"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/update",
+    tag = "users",
+    params(("userId" = Uuid, Path)),
+    request_body = UpdateUser,
+    responses(
+        (status = 200, description = "The updated profile", body = UserProfile),
+        (status = 400, description = "Invalid email or unknown time zone"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn update_user(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<UpdateUser>,
+) -> impl Responder {
+    // Try acquiring the lock on the mutex
+    let mut state_data = match state_data.data.lock() {
+        Ok(state_data) => state_data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    if let Some(email) = &req.email {
+        if !is_valid_email(email) {
+            return HttpResponse::BadRequest().body(format!("Invalid email: {}", email));
+        }
+    }
+    if let Some(timezone) = &req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return HttpResponse::BadRequest().body(format!("Unknown time zone: {}", timezone));
+        }
+    }
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body(format!("UserID: {} not found", user_id)),
+    };
+
+    let UpdateUser {
+        email,
+        display_name,
+        timezone,
+    } = req.into_inner();
+    if email.is_some() {
+        user.email = email;
+    }
+    if display_name.is_some() {
+        user.display_name = display_name;
+    }
+    if timezone.is_some() {
+        user.timezone = timezone;
+    }
+    let profile = UserProfile::from(&*user);
+
+    save_data(&state_data);
+
+    info!("Profile of UserID: {} updated", user_id);
+    HttpResponse::Ok().json(profile)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_update_user() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/update", web::put().to(update_user)),
+        )
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/update", user_id))
+            .set_json(UpdateUser {
+                display_name: Some("Test User".to_string()),
+                ..Default::default()
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let profile: UserProfile = test::read_body_json(resp).await;
+        assert_eq!(profile.display_name.as_deref(), Some("Test User"));
+        assert_eq!(profile.email, None);
+
+        // invalid email is rejected
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/update", user_id))
+            .set_json(UpdateUser {
+                email: Some("not-an-email".to_string()),
+                ..Default::default()
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    schema::save_data,
+    webhooks::{EventKind, Subscription},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NewWebhook {
+    pub url: String,
+    /// Events to subscribe to; empty or omitted means all
+    #[serde(default)]
+    pub events: HashSet<EventKind>,
+    /// Signing secret; generated when omitted
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// API endpoint to subscribe a URL to a user's events.
+/// Answers with the subscription, including its signing secret.
+/// URL: "/users/{userid}/webhooks/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/webhooks/create",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path)),
+    request_body = NewWebhook,
+    responses(
+        (status = 200, description = "The subscription, with its signing secret", body = Subscription),
+        (status = 400, description = "Url not http(s) or empty secret"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn create_webhook(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<NewWebhook>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let NewWebhook {
+        url,
+        events,
+        secret,
+    } = req.into_inner();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return HttpResponse::BadRequest().body(format!("Webhook url must be http(s): {}", url));
+    }
+    if secret.as_ref().is_some_and(|secret| secret.is_empty()) {
+        return HttpResponse::BadRequest().body("Secret can't be empty");
+    }
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let subscription = Subscription::new(&url, events, secret);
+    user.webhooks.push(subscription.clone());
+    save_data(&state_data);
+
+    info!(
+        "Webhook: {} created for UserID: {}",
+        subscription.id, user_id
+    );
+    HttpResponse::Ok().json(subscription)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_create_webhook() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/create",
+            web::post().to(create_webhook),
+        ))
+        .await;
+        let uri = format!("/users/{}/webhooks/create", user_id);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(serde_json::json!({
+                "url": "http://localhost:9000/hook",
+                "events": ["task.created", "task.completed"]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let subscription: Subscription = test::read_body_json(resp).await;
+        assert_eq!(subscription.secret.len(), 64);
+        assert!(subscription.wants(EventKind::TaskCompleted));
+        assert!(!subscription.wants(EventKind::TaskDeleted));
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(serde_json::json!({ "url": "ftp://localhost/hook" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.remove(&user_id).expect("user missing");
+            assert_eq!(user.webhooks, vec![subscription]);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{schema::save_data, webhooks::forget_subscription, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeleteWebhook {
+    pub id: Uuid,
+}
+
+/// API endpoint to remove a subscription with its queue, dead letters and log
+/// URL: "/users/{userid}/webhooks/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/webhooks/delete",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path)),
+    request_body = DeleteWebhook,
+    responses(
+        (status = 200, description = "Id of the deleted webhook", body = Uuid),
+        (status = 404, description = "User or webhook not found"),
+    )
+)]
+pub async fn delete_webhook(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<DeleteWebhook>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user = match state_data.users.get_mut(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let before = user.webhooks.len();
+    user.webhooks.retain(|sub| sub.id != req.id);
+    if user.webhooks.len() == before {
+        return HttpResponse::NotFound().body("Webhook doesn't exist");
+    }
+    forget_subscription(&mut state_data, req.id);
+    save_data(&state_data);
+
+    info!("Webhook: {} deleted", req.id);
+    HttpResponse::Ok().json(req.id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{emit, EventKind, Subscription};
+    use actix_web::{http::StatusCode, test, App};
+    use std::collections::HashSet;
+
+    #[actix_web::test]
+    async fn test_delete_webhook() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new("http://localhost:9000/hook", HashSet::new(), None);
+        let subscription_id = subscription.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription);
+            }
+            emit(
+                &mut state_data,
+                EventKind::TaskCreated,
+                user_id,
+                serde_json::json!({}),
+            );
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/delete",
+            web::delete().to(delete_webhook),
+        ))
+        .await;
+        let uri = format!("/users/{}/webhooks/delete", user_id);
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteWebhook {
+                id: subscription_id,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::delete()
+            .uri(&uri)
+            .set_json(DeleteWebhook {
+                id: subscription_id,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert!(!state_data
+                .webhook_queue
+                .iter()
+                .any(|delivery| delivery.subscription_id == subscription_id));
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
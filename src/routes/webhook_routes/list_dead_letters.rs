@@ -0,0 +1,118 @@
+use crate::{webhooks::WebhookDelivery, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetterList {
+    pub dead_letters: Vec<WebhookDelivery>,
+}
+
+/// API endpoint to list deliveries to a user's subscriptions that ran out of retries
+/// URL: "/users/{userid}/webhooks/dead-letters"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/webhooks/dead-letters",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = DeadLetterList),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn list_dead_letters(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+
+    let dead_letters = state_data
+        .webhook_dead_letters
+        .iter()
+        .filter(|delivery| delivery.user_id == user_id)
+        .cloned()
+        .collect();
+    HttpResponse::Ok().json(DeadLetterList { dead_letters })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{emit, record_delivery, EventKind, Subscription, MAX_ATTEMPTS};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::Utc;
+    use std::collections::HashSet;
+
+    #[actix_web::test]
+    async fn test_list_dead_letters() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new("http://localhost:9000/hook", HashSet::new(), None);
+        let subscription_id = subscription.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription);
+            }
+            emit(
+                &mut state_data,
+                EventKind::TaskDeleted,
+                user_id,
+                serde_json::json!({}),
+            );
+            let delivery_id = state_data
+                .webhook_queue
+                .iter()
+                .find(|delivery| delivery.subscription_id == subscription_id)
+                .map(|delivery| delivery.id)
+                .expect("delivery missing");
+            for _ in 0..MAX_ATTEMPTS {
+                record_delivery(
+                    &mut state_data,
+                    delivery_id,
+                    Err((None, "connection refused".to_string())),
+                    Utc::now(),
+                );
+            }
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/dead-letters",
+            web::get().to(list_dead_letters),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/webhooks/dead-letters", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let list: DeadLetterList = test::read_body_json(resp).await;
+        assert_eq!(list.dead_letters.len(), 1);
+        assert_eq!(list.dead_letters[0].attempts, MAX_ATTEMPTS);
+        assert_eq!(
+            list.dead_letters[0].last_error.as_deref(),
+            Some("connection refused")
+        );
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            crate::webhooks::forget_subscription(&mut state_data, subscription_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{webhooks::DeliveryAttempt, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeliveryLog {
+    /// Most recent attempt first
+    pub deliveries: Vec<DeliveryAttempt>,
+}
+
+/// API endpoint to show a subscription's recent delivery attempts
+/// URL: "/users/{userid}/webhooks/{webhookid}/deliveries"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/webhooks/{webhookId}/deliveries",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path), ("webhookId" = Uuid, Path)),
+    responses(
+        (status = 200, body = DeliveryLog),
+        (status = 404, description = "User or webhook not found"),
+    )
+)]
+pub async fn list_deliveries(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, webhook_id) = path.into_inner();
+    match state_data.users.get(&user_id) {
+        Some(user) if user.webhooks.iter().any(|sub| sub.id == webhook_id) => {}
+        Some(_) => return HttpResponse::NotFound().body("Webhook doesn't exist"),
+        None => return HttpResponse::NotFound().body("User not found"),
+    }
+
+    let deliveries = state_data
+        .webhook_log
+        .get(&webhook_id)
+        .map(|log| log.iter().rev().cloned().collect())
+        .unwrap_or_default();
+    HttpResponse::Ok().json(DeliveryLog { deliveries })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{emit, record_delivery, EventKind, Subscription};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::Utc;
+    use std::collections::HashSet;
+
+    #[actix_web::test]
+    async fn test_list_deliveries() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new("http://localhost:9000/hook", HashSet::new(), None);
+        let subscription_id = subscription.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription);
+            }
+            emit(
+                &mut state_data,
+                EventKind::TaskCreated,
+                user_id,
+                serde_json::json!({}),
+            );
+            let delivery_id = state_data
+                .webhook_queue
+                .iter()
+                .find(|delivery| delivery.subscription_id == subscription_id)
+                .map(|delivery| delivery.id)
+                .expect("delivery missing");
+            let now = Utc::now();
+            record_delivery(
+                &mut state_data,
+                delivery_id,
+                Err((Some(500), "HTTP 500".to_string())),
+                now,
+            );
+            record_delivery(&mut state_data, delivery_id, Ok(204), now);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/{webhook_id}/deliveries",
+            web::get().to(list_deliveries),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/users/{}/webhooks/{}/deliveries",
+                user_id, subscription_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let log: DeliveryLog = test::read_body_json(resp).await;
+        assert_eq!(log.deliveries.len(), 2);
+        assert_eq!(log.deliveries[0].status_code, Some(204));
+        assert_eq!(log.deliveries[0].attempt, 2);
+        assert_eq!(log.deliveries[1].error.as_deref(), Some("HTTP 500"));
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.webhook_log.remove(&subscription_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{webhooks::EventKind, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A subscription without its secret, plus the state of its queue
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookSummary {
+    pub id: Uuid,
+    pub url: String,
+    pub events: HashSet<EventKind>,
+    pub created_at: DateTime<Utc>,
+    /// Deliveries still queued or being retried
+    pub pending: usize,
+    pub dead_letters: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookList {
+    pub webhooks: Vec<WebhookSummary>,
+}
+
+/// API endpoint to list a user's webhook subscriptions
+/// URL: "/users/{userid}/webhooks/list"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/webhooks/list",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = WebhookList),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn list_webhooks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user = match state_data.users.get(&user_id.into_inner()) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let webhooks = user
+        .webhooks
+        .iter()
+        .map(|sub| WebhookSummary {
+            id: sub.id,
+            url: sub.url.clone(),
+            events: sub.events.clone(),
+            created_at: sub.created_at,
+            pending: state_data
+                .webhook_queue
+                .iter()
+                .filter(|delivery| delivery.subscription_id == sub.id)
+                .count(),
+            dead_letters: state_data
+                .webhook_dead_letters
+                .iter()
+                .filter(|delivery| delivery.subscription_id == sub.id)
+                .count(),
+        })
+        .collect();
+    HttpResponse::Ok().json(WebhookList { webhooks })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::save_data;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{emit, Subscription};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_list_webhooks() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new("http://localhost:9000/hook", HashSet::new(), None);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get_mut(&user_id) {
+                user.webhooks.push(subscription.clone());
+            }
+            emit(
+                &mut state_data,
+                EventKind::TaskCreated,
+                user_id,
+                serde_json::json!({}),
+            );
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/list",
+            web::get().to(list_webhooks),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/webhooks/list", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = test::read_body(resp).await;
+        assert!(!String::from_utf8_lossy(&body).contains(&subscription.secret));
+        let list: WebhookList = serde_json::from_slice(&body).expect("invalid body");
+        assert_eq!(list.webhooks.len(), 1);
+        assert_eq!(list.webhooks[0].id, subscription.id);
+        assert_eq!(list.webhooks[0].pending, 1);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data
+                .webhook_queue
+                .retain(|delivery| delivery.subscription_id != subscription.id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,13 @@
+pub mod create_webhook;
+pub mod delete_webhook;
+pub mod list_dead_letters;
+pub mod list_deliveries;
+pub mod list_webhooks;
+pub mod retry_dead_letter;
+
+pub use create_webhook::{create_webhook, NewWebhook};
+pub use delete_webhook::{delete_webhook, DeleteWebhook};
+pub use list_dead_letters::{list_dead_letters, DeadLetterList};
+pub use list_deliveries::{list_deliveries, DeliveryLog};
+pub use list_webhooks::{list_webhooks, WebhookList, WebhookSummary};
+pub use retry_dead_letter::{retry_dead_letter, RetryDeadLetter};
@@ -0,0 +1,135 @@
+use crate::{schema::save_data, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RetryDeadLetter {
+    pub id: Uuid,
+}
+
+/// API endpoint to put a dead-lettered delivery back on the queue with fresh retries
+/// URL: "/users/{userid}/webhooks/dead-letters/retry"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/webhooks/dead-letters/retry",
+    tag = "webhooks",
+    params(("userId" = Uuid, Path)),
+    request_body = RetryDeadLetter,
+    responses(
+        (status = 200, description = "Id of the requeued delivery", body = Uuid),
+        (status = 404, description = "User or dead letter not found"),
+    )
+)]
+pub async fn retry_dead_letter(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<RetryDeadLetter>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+
+    let index = match state_data
+        .webhook_dead_letters
+        .iter()
+        .position(|delivery| delivery.id == req.id && delivery.user_id == user_id)
+    {
+        Some(index) => index,
+        None => return HttpResponse::NotFound().body("Dead letter doesn't exist"),
+    };
+    let mut delivery = state_data.webhook_dead_letters.remove(index);
+    delivery.attempts = 0;
+    delivery.next_attempt = Utc::now();
+    state_data.webhook_queue.push(delivery);
+    save_data(&state_data);
+
+    info!("Webhook delivery: {} requeued", req.id);
+    HttpResponse::Ok().json(req.id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::webhooks::{emit, forget_subscription, EventKind, Subscription};
+    use actix_web::{http::StatusCode, test, App};
+    use std::collections::HashSet;
+
+    #[actix_web::test]
+    async fn test_retry_dead_letter() {
+        let app_state = init_app_state();
+        let (user_id, _) = create_test_user_and_task(&app_state);
+        let subscription = Subscription::new("http://localhost:9000/hook", HashSet::new(), None);
+        let subscription_id = subscription.id;
+        let delivery_id = match app_state.data.lock() {
+            Ok(mut state_data) => {
+                if let Some(user) = state_data.users.get_mut(&user_id) {
+                    user.webhooks.push(subscription);
+                }
+                emit(
+                    &mut state_data,
+                    EventKind::TaskCreated,
+                    user_id,
+                    serde_json::json!({}),
+                );
+                let index = state_data
+                    .webhook_queue
+                    .iter()
+                    .position(|delivery| delivery.subscription_id == subscription_id)
+                    .expect("delivery missing");
+                let mut delivery = state_data.webhook_queue.remove(index);
+                delivery.attempts = 6;
+                let delivery_id = delivery.id;
+                state_data.webhook_dead_letters.push(delivery);
+                delivery_id
+            }
+            Err(_) => panic!("poisoned lock"),
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/webhooks/dead-letters/retry",
+            web::post().to(retry_dead_letter),
+        ))
+        .await;
+        let uri = format!("/users/{}/webhooks/dead-letters/retry", user_id);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(RetryDeadLetter { id: delivery_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .set_json(RetryDeadLetter { id: delivery_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let requeued = state_data
+                .webhook_queue
+                .iter()
+                .find(|delivery| delivery.id == delivery_id)
+                .expect("delivery not requeued");
+            assert_eq!(requeued.attempts, 0);
+            forget_subscription(&mut state_data, subscription_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{workflow::Workflow, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use uuid::Uuid;
+
+/// API endpoint to fetch the status workflow of a user's personal tasks
+/// URL: "/users/{userid}/workflow"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/workflow",
+    tag = "workflows",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = Workflow),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn get_workflow(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    match state_data.users.get(&user_id.into_inner()) {
+        Some(user) => HttpResponse::Ok().json(user.workflow()),
+        None => HttpResponse::NotFound().body("User not found"),
+    }
+}
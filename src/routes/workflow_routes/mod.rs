@@ -0,0 +1,42 @@
+pub mod get_workflow;
+pub mod get_workspace_workflow;
+pub mod set_workflow;
+pub mod set_workspace_workflow;
+
+pub use get_workflow::get_workflow;
+pub use get_workspace_workflow::get_workspace_workflow;
+pub use set_workflow::set_workflow;
+pub use set_workspace_workflow::set_workspace_workflow;
+
+use crate::{
+    schema::{Status, Task},
+    workflow::Workflow,
+};
+use actix_web::HttpResponse;
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// Rejects an invalid workflow, or one that would strand existing tasks in undeclared states.
+pub(crate) fn check_workflow(
+    workflow: &Workflow,
+    tasks: &HashMap<Uuid, Task>,
+) -> Option<HttpResponse> {
+    if let Err(reason) = workflow.validate() {
+        return Some(HttpResponse::BadRequest().body(reason));
+    }
+    let stranded: BTreeSet<&str> = tasks
+        .values()
+        .map(|task| &task.status)
+        .filter(|status| !workflow.has_state(status))
+        .map(Status::as_str)
+        .collect();
+    if stranded.is_empty() {
+        None
+    } else {
+        let stranded: Vec<&str> = stranded.into_iter().collect();
+        Some(HttpResponse::Conflict().body(format!(
+            "Existing tasks use states missing from the workflow: {}",
+            stranded.join(", ")
+        )))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    routes::workflow_routes::check_workflow, schema::save_data, workflow::Workflow, AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to replace the status workflow of a user's personal tasks
+/// URL: "/users/{userid}/workflow"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/workflow",
+    tag = "workflows",
+    params(("userId" = Uuid, Path)),
+    request_body = Workflow,
+    responses(
+        (status = 200, description = "The new workflow", body = Workflow),
+        (status = 400, description = "Invalid workflow"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Tasks left in states the workflow removes"),
+    )
+)]
+pub async fn set_workflow(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<Workflow>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    let user = match state_data.users.get_mut(&user_id) {
+        Some(user) => user,
+        None => return HttpResponse::NotFound().body("User not found"),
+    };
+
+    let workflow = req.into_inner();
+    if let Some(resp) = check_workflow(&workflow, &user.tasks) {
+        return resp;
+    }
+    // Storing the default explicitly is the same as having none
+    user.workflow = (workflow != Workflow::default()).then(|| workflow.clone());
+    save_data(&state_data);
+
+    info!("Workflow of UserID: {} updated", user_id);
+    HttpResponse::Ok().json(workflow)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::task_routes::{update_task, UpdateTask};
+    use crate::schema::Status;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::{Transition, WorkflowState};
+    use actix_web::{http::StatusCode, test, App};
+
+    fn state(name: &str, terminal: bool) -> WorkflowState {
+        WorkflowState {
+            status: Status::from(name.to_string()),
+            terminal,
+            wip_limit: None,
+        }
+    }
+
+    fn transition(from: &str, to: &str) -> Transition {
+        Transition {
+            from: Status::from(from.to_string()),
+            to: Status::from(to.to_string()),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_set_workflow_enforces_transitions() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route("/users/{user_id}/workflow", web::put().to(set_workflow))
+                .route("/users/{user_id}/tasks/update", web::put().to(update_task)),
+        )
+        .await;
+
+        // "Done only from InReview"
+        let workflow = Workflow {
+            states: vec![
+                state("ToDo", false),
+                state("InProgress", false),
+                state("InReview", false),
+                state("Done", true),
+            ],
+            transitions: vec![
+                transition("ToDo", "InProgress"),
+                transition("InProgress", "InReview"),
+                transition("InReview", "Done"),
+            ],
+        };
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/workflow", user_id))
+            .set_json(&workflow)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let update = |status: &str| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/tasks/update", user_id))
+                .set_json(UpdateTask {
+                    id: test_task_id,
+                    status: Status::from(status.to_string()),
+                })
+                .to_request()
+        };
+        let resp = test::call_service(&app, update("Done")).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let resp = test::call_service(&app, update("Blocked")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        for status in ["InProgress", "InReview", "Done"] {
+            let resp = test::call_service(&app, update(status)).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        // dropping a state still in use is refused
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/workflow", user_id))
+            .set_json(Workflow {
+                states: vec![state("ToDo", false)],
+                transitions: vec![],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
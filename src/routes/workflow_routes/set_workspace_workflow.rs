@@ -0,0 +1,58 @@
+use crate::{
+    routes::{workflow_routes::check_workflow, workspace_routes::authorize},
+    schema::{save_data, Role},
+    workflow::Workflow,
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to replace the status workflow of a workspace; owners only
+/// URL: "/users/{userid}/workspaces/{workspaceid}/workflow"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/workspaces/{workspaceId}/workflow",
+    tag = "workflows",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = Workflow,
+    responses(
+        (status = 200, description = "The new workflow", body = Workflow),
+        (status = 400, description = "Invalid workflow"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "Tasks left in states the workflow removes"),
+    )
+)]
+pub async fn set_workspace_workflow(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<Workflow>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Owner) {
+        return err.into();
+    }
+    let workspace = match state_data.workspaces.get_mut(&workspace_id) {
+        Some(workspace) => workspace,
+        None => return HttpResponse::NotFound().body("Workspace not found"),
+    };
+
+    let workflow = req.into_inner();
+    if let Some(resp) = check_workflow(&workflow, &workspace.tasks) {
+        return resp;
+    }
+    workspace.workflow = (workflow != Workflow::default()).then(|| workflow.clone());
+    save_data(&state_data);
+
+    info!("Workflow of WorkspaceID: {} updated", workspace_id);
+    HttpResponse::Ok().json(workflow)
+}
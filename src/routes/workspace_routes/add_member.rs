@@ -0,0 +1,131 @@
+use crate::{
+    routes::workspace_routes::authorize,
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AddMember {
+    pub user_id: Uuid,
+    pub role: Role,
+}
+
+/// API endpoint to add a member to a workspace, or change an existing member's role
+/// URL: "/users/{userid}/workspaces/{workspaceid}/members/add"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/workspaces/{workspaceId}/members/add",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = AddMember,
+    responses(
+        (status = 200, description = "Id of the added member", body = Uuid),
+        (status = 400, description = "Owners can't change their own role"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace or user not found"),
+    )
+)]
+pub async fn add_member(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<AddMember>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Owner) {
+        return err.into();
+    }
+    if !state_data.users.contains_key(&req.user_id) {
+        return HttpResponse::NotFound().body(format!("UserID: {} not found", req.user_id));
+    }
+    if req.user_id == user_id {
+        return HttpResponse::BadRequest().body("Owners can't change their own role");
+    }
+
+    if let Some(workspace) = state_data.workspaces.get_mut(&workspace_id) {
+        workspace.members.insert(req.user_id, req.role);
+    }
+    save_data(&state_data);
+
+    info!(
+        "UserID: {} added to WorkspaceID: {} as {:?}",
+        req.user_id, workspace_id, req.role
+    );
+    HttpResponse::Ok().json(req.user_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Workspace;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_add_member() {
+        let app_state = init_app_state();
+        let (owner_id, _owner_task_id) = create_test_user_and_task(&app_state);
+        let (member_id, _member_task_id) = create_test_user_and_task(&app_state);
+        let workspace = Workspace::new("Team", owner_id);
+        let workspace_id = workspace.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/{workspace_id}/members/add",
+            web::post().to(add_member),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/members/add",
+                owner_id, workspace_id
+            ))
+            .set_json(AddMember {
+                user_id: member_id,
+                role: Role::Viewer,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // viewers can't manage members
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/members/add",
+                member_id, workspace_id
+            ))
+            .set_json(AddMember {
+                user_id: member_id,
+                role: Role::Owner,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert_eq!(
+                state_data.member_role(workspace_id, member_id),
+                Some(Role::Viewer)
+            );
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&member_id);
+            save_data(&state_data);
+        };
+    }
+}
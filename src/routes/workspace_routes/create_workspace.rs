@@ -0,0 +1,99 @@
+use crate::{
+    schema::{save_data, Workspace},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NewWorkspace {
+    pub name: String,
+}
+
+/// API endpoint to create a shared workspace owned by the calling user
+/// URL: "/users/{userid}/workspaces/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/workspaces/create",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path)),
+    request_body = NewWorkspace,
+    responses(
+        (status = 200, description = "Id of the new workspace", body = Uuid),
+        (status = 400, description = "Empty name"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn create_workspace(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: web::Json<NewWorkspace>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+    if req.name.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Workspace name can't be empty");
+    }
+
+    let workspace = Workspace::new(req.name.trim(), user_id);
+    let workspace_id = workspace.id;
+    state_data.workspaces.insert(workspace_id, workspace);
+
+    save_data(&state_data);
+
+    info!("Workspace created successfully with ID: {}", workspace_id);
+    HttpResponse::Ok().json(workspace_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Role;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_create_workspace() {
+        let app_state = init_app_state();
+        let (user_id, _test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/create",
+            web::post().to(create_workspace),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/workspaces/create", user_id))
+            .set_json(NewWorkspace {
+                name: "Team".to_string(),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let workspace_id: Uuid = test::read_body_json(resp).await;
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert_eq!(
+                state_data.member_role(workspace_id, user_id),
+                Some(Role::Owner)
+            );
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    board::next_rank,
+    events::ChangeKind,
+    routes::{task_routes::NewTask, workspace_routes::authorize},
+    schema::{check_labels, parse_timezone, save_data, Role, Task},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to create a task on a workspace board
+/// URL: "/users/{userid}/workspaces/{workspaceid}/tasks/create"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/workspaces/{workspaceId}/tasks/create",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = NewTask,
+    responses(
+        (status = 200, description = "Id of the new task", body = Uuid),
+        (status = 400, description = "Unknown time zone, invalid labels, a project or nil id"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "Id already taken"),
+    )
+)]
+pub async fn create_workspace_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<NewTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Editor) {
+        return err.into();
+    }
+    if let Some(timezone) = &req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return HttpResponse::BadRequest().body(format!("Unknown time zone: {}", timezone));
+        }
+    }
+    if let Err(err) = check_labels(req.priority, &req.tags) {
+        return HttpResponse::BadRequest().body(err);
+    }
+    if req.project_id.is_some() {
+        return HttpResponse::BadRequest().body("Projects are only available for personal tasks");
+    }
+    if let Some(task_id) = req.id {
+        if task_id.is_nil() {
+            return HttpResponse::BadRequest().body("Task id can't be nil");
+        }
+        if state_data.task_scope(task_id).is_some() {
+            return HttpResponse::Conflict().body(format!("Task-Id: {} already exists", task_id));
+        }
+    }
+
+    let mut new_task = Task::new(&req.title, &req.description, req.due_date);
+    if let Some(task_id) = req.id {
+        new_task.id = task_id;
+    }
+    new_task.due_time = req.due_time;
+    new_task.timezone = req.timezone.clone();
+    new_task.priority = req.priority;
+    new_task.tags = req.tags.clone();
+    let task_id = new_task.id;
+    if let Some(workspace) = state_data.workspaces.get_mut(&workspace_id) {
+        if let Some(initial) = workspace.workflow().states.first() {
+            new_task.status = initial.status.clone();
+        }
+        new_task.rank = next_rank(&workspace.tasks, &new_task.status);
+        workspace.tasks.insert(task_id, new_task);
+    }
+    state_data.publish_task(ChangeKind::Created, task_id);
+    save_data(&state_data);
+
+    info!(
+        "Task created successfully with ID: {} in WorkspaceID: {}",
+        task_id, workspace_id
+    );
+    HttpResponse::Ok().json(task_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{Status, Workspace};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::NaiveDate;
+
+    #[actix_web::test]
+    async fn test_create_workspace_task() {
+        let app_state = init_app_state();
+        let (owner_id, _owner_task_id) = create_test_user_and_task(&app_state);
+        let (viewer_id, _viewer_task_id) = create_test_user_and_task(&app_state);
+        let mut workspace = Workspace::new("Team", owner_id);
+        workspace.members.insert(viewer_id, Role::Viewer);
+        let workspace_id = workspace.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/{workspace_id}/tasks/create",
+            web::post().to(create_workspace_task),
+        ))
+        .await;
+
+        let new_task = NewTask {
+            id: None,
+            title: "Shared Task".to_string(),
+            description: "Shared Description".to_string(),
+            due_date: NaiveDate::from_ymd_opt(2024, 5, 24).expect("failed to create Due-Date"),
+            due_time: None,
+            timezone: None,
+            status: Status::ToDo,
+            project_id: None,
+            priority: None,
+            tags: Vec::new(),
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/tasks/create",
+                owner_id, workspace_id
+            ))
+            .set_json(&new_task)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let task_id: Uuid = test::read_body_json(resp).await;
+
+        // viewers can read but not create
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/tasks/create",
+                viewer_id, workspace_id
+            ))
+            .set_json(&new_task)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(workspace) = state_data.workspaces.get(&workspace_id) {
+                assert!(workspace.tasks.contains_key(&task_id));
+            }
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&viewer_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    blobs::remove_orphaned_blobs,
+    events::EventScope,
+    routes::{task_routes::DeleteTask, workspace_routes::authorize},
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info, warn};
+use uuid::Uuid;
+
+/// API endpoint to delete a workspace task
+/// URL: "/users/{userid}/workspaces/{workspaceid}/tasks/delete"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/workspaces/{workspaceId}/tasks/delete",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = DeleteTask,
+    responses(
+        (status = 200, description = "Id of the deleted task", body = Uuid),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace or task not found"),
+    )
+)]
+pub async fn delete_workspace_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<DeleteTask>,
+) -> impl Responder {
+    let blob_dir = state_data.config.blob_dir();
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Editor) {
+        return err.into();
+    }
+
+    let task_id = &req.id;
+    let removed = state_data
+        .workspaces
+        .get_mut(&workspace_id)
+        .and_then(|workspace| workspace.tasks.remove(task_id));
+    if let Some(task) = removed {
+        state_data.comments.remove(task_id);
+        state_data.publish_deleted(EventScope::Workspace(workspace_id), *task_id);
+        let shas = task.attachments.into_iter().map(|a| a.sha256);
+        remove_orphaned_blobs(&state_data, &blob_dir, shas);
+        save_data(&state_data);
+        info!("Task deleted successfully with ID: {}", task_id);
+        HttpResponse::Ok().json(task_id)
+    } else {
+        warn!("Task-id: {} doesn't exist", task_id);
+        HttpResponse::NotFound().body("Task doesn't exist")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blobs::{blob_path, store_blob};
+    use crate::schema::{Attachment, Workspace};
+    use crate::test_utils::{create_test_user_and_task, init_app_state_with, temp_config};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::Utc;
+
+    #[actix_web::test]
+    async fn test_delete_workspace_task_removes_blobs() {
+        let app_state = init_app_state_with(temp_config());
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let blob_dir = app_state.config.blob_dir();
+        let sha256 = store_blob(&blob_dir, b"shared notes").expect("failed to store blob");
+
+        // a shared task carrying an attachment
+        let mut workspace = Workspace::new("delete-workspace", owner_id);
+        let workspace_id = workspace.id;
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let mut task = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.remove(&test_task_id))
+                .expect("test task exists");
+            task.attachments.push(Attachment {
+                id: Uuid::new_v4(),
+                name: "notes.txt".to_string(),
+                size: 12,
+                mime_type: "text/plain".to_string(),
+                sha256: sha256.clone(),
+                uploaded_at: Utc::now(),
+            });
+            workspace.tasks.insert(test_task_id, task);
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/{workspace_id}/tasks/delete",
+            web::delete().to(delete_workspace_task),
+        ))
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/tasks/delete",
+                owner_id, workspace_id
+            ))
+            .set_json(DeleteTask { id: test_task_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!blob_path(&blob_dir, &sha256).unwrap().exists());
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            state_data.workspaces.remove(&workspace_id);
+            state_data.users.remove(&owner_id);
+            save_data(&state_data);
+        };
+        let _ = std::fs::remove_dir_all(&app_state.config.data_dir);
+    }
+}
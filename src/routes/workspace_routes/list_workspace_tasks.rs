@@ -0,0 +1,60 @@
+use crate::{
+    routes::{task_routes::TaskList, workspace_routes::authorize},
+    schema::Role,
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use chrono_tz::Tz;
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to list the tasks on a workspace board
+/// URL: "/users/{userid}/workspaces/{workspaceid}/tasks/list"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/workspaces/{workspaceId}/tasks/list",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    responses(
+        (status = 200, body = TaskList),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_workspace_tasks(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Viewer) {
+        return err.into();
+    }
+
+    let task_list = match state_data.workspaces.get(&workspace_id) {
+        Some(workspace) => {
+            // Overdue is judged in the zone of whoever is looking
+            let tz = state_data
+                .users
+                .get(&user_id)
+                .map(|user| user.tz())
+                .unwrap_or(Tz::UTC);
+            TaskList::from_tasks(
+                workspace.tasks.values(),
+                &state_data.comments,
+                &workspace.workflow(),
+                tz,
+            )
+        }
+        None => return HttpResponse::NotFound().body("Workspace not found"),
+    };
+    info!("Listing tasks for WorkspaceID: {}", workspace_id);
+    HttpResponse::Ok().json(task_list)
+}
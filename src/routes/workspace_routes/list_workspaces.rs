@@ -0,0 +1,72 @@
+use crate::{schema::Role, AppState};
+use actix_web::{web, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct WorkspaceSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub personal: bool,
+    pub role: Role,
+    pub member_count: usize,
+    pub task_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WorkspaceList {
+    pub workspaces: Vec<WorkspaceSummary>,
+}
+
+/// API endpoint to list the workspaces a user is a member of
+/// URL: "/users/{userid}/workspaces/list"
+#[utoipa::path(
+    get,
+    path = "/users/{userId}/workspaces/list",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, body = WorkspaceList),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub async fn list_workspaces(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    if !state_data.users.contains_key(&user_id) {
+        return HttpResponse::NotFound().body("User not found");
+    }
+
+    let mut workspaces: Vec<WorkspaceSummary> = state_data
+        .workspaces
+        .values()
+        .filter_map(|workspace| {
+            workspace
+                .members
+                .get(&user_id)
+                .map(|role| WorkspaceSummary {
+                    id: workspace.id,
+                    name: workspace.name.clone(),
+                    personal: workspace.personal,
+                    role: *role,
+                    member_count: workspace.members.len(),
+                    task_count: workspace.tasks.len(),
+                })
+        })
+        .collect();
+    workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    HttpResponse::Ok().json(WorkspaceList { workspaces })
+}
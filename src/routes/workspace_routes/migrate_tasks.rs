@@ -0,0 +1,204 @@
+use crate::{
+    schema::{save_data, MigrateError},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to move a user's personal tasks into their personal workspace
+/// URL: "/users/{userid}/workspaces/migrate"
+#[utoipa::path(
+    post,
+    path = "/users/{userId}/workspaces/migrate",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Id of the personal workspace", body = Uuid),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Tasks use states missing from the workspace's workflow"),
+    )
+)]
+pub async fn migrate_tasks(
+    state_data: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let user_id = user_id.into_inner();
+    match state_data.migrate_personal_tasks(user_id) {
+        Ok(workspace_id) => {
+            save_data(&state_data);
+            info!(
+                "Tasks of UserID: {} migrated into WorkspaceID: {}",
+                user_id, workspace_id
+            );
+            HttpResponse::Ok().json(workspace_id)
+        }
+        Err(MigrateError::UserNotFound) => HttpResponse::NotFound().body("User not found"),
+        Err(MigrateError::StrandedStates(states)) => HttpResponse::Conflict().body(format!(
+            "Existing tasks use states missing from the workflow: {}",
+            states.join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::{ChangeKind, EventScope};
+    use crate::routes::task_routes::UpdateTask;
+    use crate::routes::workspace_routes::update_workspace_task;
+    use crate::schema::{Status, Task};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::{Transition, Workflow, WorkflowState};
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::NaiveDate;
+
+    #[actix_web::test]
+    async fn test_migrate_tasks() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/migrate",
+            web::post().to(migrate_tasks),
+        ))
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/workspaces/migrate", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let workspace_id: Uuid = test::read_body_json(resp).await;
+
+        // migrating again reuses the same personal workspace
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/workspaces/migrate", user_id))
+            .to_request();
+        let again: Uuid = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(again, workspace_id);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            if let Some(user) = state_data.users.get(&user_id) {
+                assert!(user.tasks.is_empty());
+            }
+            if let Some(workspace) = state_data.workspaces.get(&workspace_id) {
+                assert!(workspace.personal);
+                assert!(workspace.tasks.contains_key(&test_task_id));
+            }
+            // the move is announced to both sides
+            let moves: Vec<_> = state_data
+                .event_log
+                .iter()
+                .filter(|event| event.task_id == test_task_id)
+                .map(|event| (event.scope, event.kind))
+                .collect();
+            assert_eq!(
+                moves,
+                vec![
+                    (EventScope::User(user_id), ChangeKind::Deleted),
+                    (EventScope::Workspace(workspace_id), ChangeKind::Created),
+                ]
+            );
+            state_data.users.remove(&user_id);
+            state_data.remove_user_memberships(user_id);
+            assert!(!state_data.workspaces.contains_key(&workspace_id));
+            save_data(&state_data);
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_migrate_tasks_keeps_workflow() {
+        let app_state = init_app_state();
+        let (user_id, test_task_id) = create_test_user_and_task(&app_state);
+        let blocked = Status::Custom("Blocked".to_string());
+        let project_id = Uuid::new_v4();
+
+        // a task parked in a state only the user's workflow has
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let user = state_data.users.get_mut(&user_id).unwrap();
+            let mut workflow = Workflow::default();
+            workflow.states.push(WorkflowState {
+                status: blocked.clone(),
+                terminal: false,
+                wip_limit: None,
+            });
+            workflow.transitions.push(Transition {
+                from: blocked.clone(),
+                to: Status::InProgress,
+            });
+            user.workflow = Some(workflow);
+            let task = user.tasks.get_mut(&test_task_id).unwrap();
+            task.status = blocked.clone();
+            task.project_id = Some(project_id);
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/users/{user_id}/workspaces/migrate",
+                    web::post().to(migrate_tasks),
+                )
+                .route(
+                    "/users/{user_id}/workspaces/{workspace_id}/tasks/update",
+                    web::put().to(update_workspace_task),
+                ),
+        )
+        .await;
+        let migrate = || {
+            test::TestRequest::post()
+                .uri(&format!("/users/{}/workspaces/migrate", user_id))
+                .to_request()
+        };
+
+        let workspace_id: Uuid = test::call_and_read_body_json(&app, migrate()).await;
+        // the task can still leave its state on the new board
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/tasks/update",
+                user_id, workspace_id
+            ))
+            .set_json(UpdateTask {
+                id: test_task_id,
+                status: Status::InProgress,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // an existing workspace without the state refuses the move
+        let new_task_id = match app_state.data.lock() {
+            Ok(mut state_data) => {
+                let workspace = state_data.workspaces.get_mut(&workspace_id).unwrap();
+                assert_eq!(workspace.tasks[&test_task_id].project_id, None);
+                workspace.workflow = None;
+                let mut task =
+                    Task::new("parked", "", NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+                task.status = blocked.clone();
+                let task_id = task.id;
+                let user = state_data.users.get_mut(&user_id).unwrap();
+                user.tasks.insert(task_id, task);
+                task_id
+            }
+            Err(_) => panic!("poisoned lock"),
+        };
+        let resp = test::call_service(&app, migrate()).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            assert!(state_data.users[&user_id].tasks.contains_key(&new_task_id));
+            state_data.users.remove(&user_id);
+            state_data.remove_user_memberships(user_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,64 @@
+pub mod add_member;
+pub mod create_workspace;
+pub mod create_workspace_task;
+pub mod delete_workspace_task;
+pub mod list_workspace_tasks;
+pub mod list_workspaces;
+pub mod migrate_tasks;
+pub mod remove_member;
+pub mod update_workspace_task;
+
+pub use add_member::{add_member, AddMember};
+pub use create_workspace::{create_workspace, NewWorkspace};
+pub use create_workspace_task::create_workspace_task;
+pub use delete_workspace_task::delete_workspace_task;
+pub use list_workspace_tasks::list_workspace_tasks;
+pub use list_workspaces::{list_workspaces, WorkspaceList, WorkspaceSummary};
+pub use migrate_tasks::migrate_tasks;
+pub use remove_member::{remove_member, RemoveMember};
+pub use update_workspace_task::update_workspace_task;
+
+use crate::schema::{AppStateData, Role};
+use actix_web::HttpResponse;
+use uuid::Uuid;
+
+/// Why a workspace request was refused
+#[derive(Debug, PartialEq)]
+pub enum AccessError {
+    WorkspaceNotFound(Uuid),
+    NotMember(Uuid),
+    InsufficientRole(Role),
+}
+
+impl From<AccessError> for HttpResponse {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::WorkspaceNotFound(workspace_id) => {
+                HttpResponse::NotFound().body(format!("WorkspaceID: {} not found", workspace_id))
+            }
+            AccessError::NotMember(user_id) => HttpResponse::Forbidden()
+                .body(format!("UserID: {} is not a member of this workspace", user_id)),
+            AccessError::InsufficientRole(required) => {
+                HttpResponse::Forbidden().body(format!("Requires {:?} role", required))
+            }
+        }
+    }
+}
+
+/// Checks that `user_id` holds at least `required` in `workspace_id`.
+/// Unknown workspaces answer 404, missing membership or rank answers 403.
+pub fn authorize(
+    data: &AppStateData,
+    workspace_id: Uuid,
+    user_id: Uuid,
+    required: Role,
+) -> Result<Role, AccessError> {
+    if !data.workspaces.contains_key(&workspace_id) {
+        return Err(AccessError::WorkspaceNotFound(workspace_id));
+    }
+    match data.member_role(workspace_id, user_id) {
+        Some(role) if role >= required => Ok(role),
+        Some(_) => Err(AccessError::InsufficientRole(required)),
+        None => Err(AccessError::NotMember(user_id)),
+    }
+}
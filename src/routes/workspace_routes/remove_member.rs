@@ -0,0 +1,163 @@
+use crate::{
+    events::ChangeKind,
+    routes::workspace_routes::authorize,
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RemoveMember {
+    pub user_id: Uuid,
+}
+
+/// API endpoint to remove a member from a workspace; any member may remove themselves
+/// URL: "/users/{userid}/workspaces/{workspaceid}/members/remove"
+#[utoipa::path(
+    delete,
+    path = "/users/{userId}/workspaces/{workspaceId}/members/remove",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = RemoveMember,
+    responses(
+        (status = 200, description = "Id of the removed member", body = Uuid),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace or member not found"),
+        (status = 409, description = "Workspace must keep at least one owner"),
+    )
+)]
+pub async fn remove_member(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<RemoveMember>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    let required = if req.user_id == user_id {
+        Role::Viewer
+    } else {
+        Role::Owner
+    };
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, required) {
+        return err.into();
+    }
+
+    let workspace = match state_data.workspaces.get_mut(&workspace_id) {
+        Some(workspace) => workspace,
+        None => return HttpResponse::NotFound().body("Workspace not found"),
+    };
+    if workspace.members.get(&req.user_id) == Some(&Role::Owner)
+        && workspace
+            .members
+            .values()
+            .filter(|role| **role == Role::Owner)
+            .count()
+            == 1
+    {
+        return HttpResponse::Conflict().body("Workspace must keep at least one owner");
+    }
+    if workspace.members.remove(&req.user_id).is_none() {
+        return HttpResponse::NotFound().body(format!(
+            "UserID: {} is not a member of WorkspaceID: {}",
+            req.user_id, workspace_id
+        ));
+    }
+    // A former member keeps no assignments or watches on the workspace's tasks
+    for task_id in workspace.remove_assignments(req.user_id) {
+        state_data.publish_task(ChangeKind::Updated, task_id);
+    }
+    save_data(&state_data);
+
+    info!(
+        "UserID: {} removed from WorkspaceID: {}",
+        req.user_id, workspace_id
+    );
+    HttpResponse::Ok().json(req.user_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::routes::task_routes::{list_assigned, AssignedTaskList};
+    use crate::schema::Workspace;
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_remove_member_clears_assignments() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (member_id, _member_task_id) = create_test_user_and_task(&app_state);
+
+        // a shared task assigned to and watched by the member
+        let mut workspace = Workspace::new("remove-member", owner_id);
+        let workspace_id = workspace.id;
+        workspace.members.insert(member_id, Role::Editor);
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let mut task = state_data
+                .users
+                .get_mut(&owner_id)
+                .and_then(|user| user.tasks.remove(&test_task_id))
+                .expect("test task exists");
+            task.assignee = Some(member_id);
+            task.watchers.insert(member_id);
+            task.watchers.insert(owner_id);
+            workspace.tasks.insert(test_task_id, task);
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_state.clone())
+                .route(
+                    "/users/{user_id}/workspaces/{workspace_id}/members/remove",
+                    web::delete().to(remove_member),
+                )
+                .route(
+                    "/users/{user_id}/tasks/assigned",
+                    web::get().to(list_assigned),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/users/{}/workspaces/{}/members/remove",
+                owner_id, workspace_id
+            ))
+            .set_json(RemoveMember { user_id: member_id })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // the former member no longer sees the task
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}/tasks/assigned", member_id))
+            .to_request();
+        let assigned: AssignedTaskList = test::call_and_read_body_json(&app, req).await;
+        assert!(assigned.tasks.is_empty());
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let workspace = state_data.workspaces.remove(&workspace_id).unwrap();
+            let task = &workspace.tasks[&test_task_id];
+            assert_eq!(task.assignee, None);
+            assert!(!task.watchers.contains(&member_id));
+            assert!(task.watchers.contains(&owner_id));
+            assert!(task.version > 0);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&member_id);
+            save_data(&state_data);
+        };
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    events::ChangeKind,
+    routes::{
+        task_routes::{set_status, UpdateTask},
+        workspace_routes::authorize,
+    },
+    schema::{save_data, Role},
+    AppState,
+};
+use actix_web::{web, HttpResponse, Responder};
+use log::{error, info};
+use uuid::Uuid;
+
+/// API endpoint to update the status of a workspace task
+/// URL: "/users/{userid}/workspaces/{workspaceid}/tasks/update"
+#[utoipa::path(
+    put,
+    path = "/users/{userId}/workspaces/{workspaceId}/tasks/update",
+    tag = "workspaces",
+    params(("userId" = Uuid, Path), ("workspaceId" = Uuid, Path)),
+    request_body = UpdateTask,
+    responses(
+        (status = 200, description = "Id of the updated task", body = Uuid),
+        (status = 400, description = "Status not in the workflow"),
+        (status = 403, description = "Not a member, or the role is too low"),
+        (status = 404, description = "Workspace or task not found"),
+        (status = 409, description = "Transition not allowed or WIP limit reached"),
+    )
+)]
+pub async fn update_workspace_task(
+    state_data: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<UpdateTask>,
+) -> impl Responder {
+    let mut state_data = match state_data.data.lock() {
+        Ok(data) => data,
+        Err(_) => {
+            error!("Failed to acquire lock on the state data");
+            return HttpResponse::InternalServerError().body("Internal Server Error");
+        }
+    };
+
+    let (user_id, workspace_id) = path.into_inner();
+    if let Err(err) = authorize(&state_data, workspace_id, user_id, Role::Editor) {
+        return err.into();
+    }
+
+    let workspace = match state_data.workspaces.get_mut(&workspace_id) {
+        Some(workspace) => workspace,
+        None => return HttpResponse::NotFound().body("Workspace not found"),
+    };
+    let workflow = workspace.workflow();
+    match set_status(&mut workspace.tasks, &workflow, &req) {
+        Ok(_) => {
+            state_data.publish_task(ChangeKind::Updated, req.id);
+            save_data(&state_data);
+            info!("Staus of Task-Id: {}, updated to: {:?}", req.id, req.status);
+            HttpResponse::Ok().json(req.id)
+        }
+        Err(err) => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{Status, Workspace};
+    use crate::test_utils::{create_test_user_and_task, init_app_state};
+    use crate::workflow::{Transition, Workflow, WorkflowState};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_update_workspace_task_follows_workflow() {
+        let app_state = init_app_state();
+        let (owner_id, test_task_id) = create_test_user_and_task(&app_state);
+        let (other_id, other_task_id) = create_test_user_and_task(&app_state);
+
+        // forward-only board with room for one task in progress
+        let mut workspace = Workspace::new("update-workspace", owner_id);
+        let workspace_id = workspace.id;
+        workspace.workflow = Some(Workflow {
+            states: vec![
+                WorkflowState {
+                    status: Status::ToDo,
+                    terminal: false,
+                    wip_limit: None,
+                },
+                WorkflowState {
+                    status: Status::InProgress,
+                    terminal: false,
+                    wip_limit: Some(1),
+                },
+                WorkflowState {
+                    status: Status::Done,
+                    terminal: true,
+                    wip_limit: None,
+                },
+            ],
+            transitions: vec![
+                Transition {
+                    from: Status::ToDo,
+                    to: Status::InProgress,
+                },
+                Transition {
+                    from: Status::InProgress,
+                    to: Status::Done,
+                },
+            ],
+        });
+        if let Ok(mut state_data) = app_state.data.lock() {
+            for task_id in [test_task_id, other_task_id] {
+                let task = state_data
+                    .users
+                    .values_mut()
+                    .find_map(|user| user.tasks.remove(&task_id))
+                    .expect("test task exists");
+                workspace.tasks.insert(task_id, task);
+            }
+            state_data.workspaces.insert(workspace_id, workspace);
+        };
+
+        let app = test::init_service(App::new().app_data(app_state.clone()).route(
+            "/users/{user_id}/workspaces/{workspace_id}/tasks/update",
+            web::put().to(update_workspace_task),
+        ))
+        .await;
+        let update = |task_id: Uuid, status: Status| {
+            test::TestRequest::put()
+                .uri(&format!(
+                    "/users/{}/workspaces/{}/tasks/update",
+                    owner_id, workspace_id
+                ))
+                .set_json(UpdateTask {
+                    id: task_id,
+                    status,
+                })
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, update(test_task_id, Status::Done)).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let resp = test::call_service(
+            &app,
+            update(test_task_id, Status::Custom("Blocked".to_string())),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = test::call_service(&app, update(test_task_id, Status::InProgress)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        // the column is full
+        let resp = test::call_service(&app, update(other_task_id, Status::InProgress)).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let resp = test::call_service(&app, update(Uuid::new_v4(), Status::Done)).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        if let Ok(mut state_data) = app_state.data.lock() {
+            let workspace = state_data.workspaces.remove(&workspace_id).unwrap();
+            assert_eq!(workspace.tasks[&test_task_id].status, Status::InProgress);
+            assert_eq!(workspace.tasks[&other_task_id].status, Status::ToDo);
+            state_data.users.remove(&owner_id);
+            state_data.users.remove(&other_id);
+            save_data(&state_data);
+        };
+    }
+}
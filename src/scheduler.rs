@@ -0,0 +1,525 @@
+// src/scheduler.rs
+use crate::{
+    config::Config,
+    reminders::{enqueue_due, record_attempt, take_ready, Channel, ReminderJob},
+    schema::save_data,
+    webhooks::{
+        ready_deliveries, record_delivery, sign, ReadyDelivery, DELIVERY_HEADER, EVENT_HEADER,
+        SIGNATURE_HEADER,
+    },
+    AppState,
+};
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::{error, info};
+use serde_json::json;
+use std::future::Future;
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends a reminder out through its channel
+pub trait Deliver {
+    fn deliver(&self, job: &ReminderJob) -> impl Future<Output = Result<(), String>>;
+}
+
+/// The built-in channels: server log, webhook and SMTP
+pub struct Channels {
+    http: reqwest::Client,
+    smtp: AsyncSmtpTransport<Tokio1Executor>,
+    smtp_from: String,
+}
+
+impl Channels {
+    pub fn new(config: &Config) -> Self {
+        Channels {
+            http: reqwest::Client::new(),
+            smtp: AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+                .port(config.smtp_port)
+                .build(),
+            smtp_from: config.smtp_from.clone(),
+        }
+    }
+}
+
+impl Deliver for Channels {
+    async fn deliver(&self, job: &ReminderJob) -> Result<(), String> {
+        match &job.channel {
+            Channel::Log => {
+                info!("{} (Task-Id: {})", job.message(), job.task_id);
+                Ok(())
+            }
+            Channel::Webhook { url } => {
+                let payload = json!({
+                    "reminder_id": job.reminder_id,
+                    "user_id": job.user_id,
+                    "workspace_id": job.workspace_id,
+                    "task_id": job.task_id,
+                    "title": job.title,
+                    "due_at": job.due_at,
+                    "fire_at": job.fire_at,
+                    "message": job.message(),
+                });
+                self.http
+                    .post(url)
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .and_then(|resp| resp.error_for_status())
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            }
+            Channel::Email { to } => {
+                let email = Message::builder()
+                    .from(self.smtp_from.parse().map_err(|err| format!("{}", err))?)
+                    .to(to.parse().map_err(|err| format!("{}", err))?)
+                    .subject(format!("Reminder: {}", job.title))
+                    .body(job.message())
+                    .map_err(|err| err.to_string())?;
+                self.smtp
+                    .send(email)
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+/// One scheduler pass: queue reminders that went off, then attempt every ready
+/// job. The lock is released while deliveries are in flight.
+pub async fn tick<D: Deliver>(state: &AppState, deliver: &D, now: DateTime<Utc>) {
+    let ready = {
+        let mut data = match state.data.lock() {
+            Ok(data) => data,
+            Err(_) => {
+                error!("Failed to acquire lock on the state data");
+                return;
+            }
+        };
+        let before = (data.reminder_jobs.len(), data.reminders_fired.len());
+        let queued = enqueue_due(&mut data, now);
+        let ready = take_ready(&mut data, now);
+        if queued > 0 || before != (data.reminder_jobs.len(), data.reminders_fired.len()) {
+            save_data(&data);
+        }
+        ready
+    };
+    if ready.is_empty() {
+        return;
+    }
+
+    let mut outcomes = Vec::with_capacity(ready.len());
+    for job in &ready {
+        outcomes.push((job.id, deliver.deliver(job).await));
+    }
+
+    match state.data.lock() {
+        Ok(mut data) => {
+            for (job_id, outcome) in outcomes {
+                record_attempt(&mut data, job_id, outcome, now);
+            }
+            save_data(&data);
+        }
+        Err(_) => error!("Failed to acquire lock on the state data"),
+    }
+}
+
+/// Runs reminder passes forever at the configured interval.
+/// Spawned next to the HTTP server by `start_service`.
+pub async fn run_scheduler(state: web::Data<AppState>) {
+    let channels = Channels::new(&state.config);
+    let mut interval = actix_web::rt::time::interval(state.config.reminder_interval);
+    loop {
+        interval.tick().await;
+        tick(&state, &channels, Utc::now()).await;
+    }
+}
+
+/// POSTs one signed webhook event; a non-2xx answer counts as a failure
+async fn post_webhook(
+    client: &reqwest::Client,
+    delivery: &ReadyDelivery,
+) -> Result<u16, (Option<u16>, String)> {
+    let body = serde_json::to_vec(&delivery.event).map_err(|err| (None, err.to_string()))?;
+    let resp = client
+        .post(&delivery.url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign(&delivery.secret, &body))
+        .header(EVENT_HEADER, delivery.event.event.as_str())
+        .header(DELIVERY_HEADER, delivery.delivery_id.to_string())
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| (None, err.to_string()))?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("HTTP {}", status)))
+    }
+}
+
+/// One webhook pass: attempt every queued delivery that is due
+pub async fn webhook_tick(state: &AppState, client: &reqwest::Client, now: DateTime<Utc>) {
+    let ready = {
+        let mut data = match state.data.lock() {
+            Ok(data) => data,
+            Err(_) => {
+                error!("Failed to acquire lock on the state data");
+                return;
+            }
+        };
+        let before = data.webhook_queue.len();
+        let ready = ready_deliveries(&mut data, now);
+        if before != data.webhook_queue.len() {
+            save_data(&data);
+        }
+        ready
+    };
+    if ready.is_empty() {
+        return;
+    }
+
+    let mut outcomes = Vec::with_capacity(ready.len());
+    for delivery in &ready {
+        outcomes.push((delivery.delivery_id, post_webhook(client, delivery).await));
+    }
+
+    match state.data.lock() {
+        Ok(mut data) => {
+            for (delivery_id, outcome) in outcomes {
+                record_delivery(&mut data, delivery_id, outcome, now);
+            }
+            save_data(&data);
+        }
+        Err(_) => error!("Failed to acquire lock on the state data"),
+    }
+}
+
+/// Drains the webhook queue forever at the configured interval.
+/// Spawned next to the HTTP server by `start_service`.
+pub async fn run_webhook_worker(state: web::Data<AppState>) {
+    let client = reqwest::Client::new();
+    let mut interval = actix_web::rt::time::interval(state.config.webhook_interval);
+    loop {
+        interval.tick().await;
+        webhook_tick(&state, &client, Utc::now()).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::reminders::{Reminder, ReminderTrigger, MAX_ATTEMPTS};
+    use crate::schema::{load_data_from, Task, User};
+    use crate::test_utils::temp_config;
+    use crate::webhooks::{emit, EventKind, Subscription};
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer};
+    use chrono::{NaiveDate, TimeDelta, TimeZone};
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Records deliveries instead of sending them
+    struct Recorder {
+        sent: Mutex<Vec<Uuid>>,
+        fail: bool,
+    }
+
+    impl Recorder {
+        fn new(fail: bool) -> Self {
+            Recorder {
+                sent: Mutex::new(Vec::new()),
+                fail,
+            }
+        }
+
+        fn count(&self) -> usize {
+            self.sent.lock().unwrap().len()
+        }
+    }
+
+    impl Deliver for Recorder {
+        async fn deliver(&self, job: &ReminderJob) -> Result<(), String> {
+            self.sent.lock().unwrap().push(job.reminder_id);
+            if self.fail {
+                Err("unreachable".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// A state with one task due 2024-07-01 (all-day, UTC) and a reminder an hour before
+    fn state_with_reminder() -> (AppState, Uuid, Uuid) {
+        let mut user = User::new("reminder-user");
+        let mut task = Task::new("pay rent", "", NaiveDate::from_ymd_opt(2024, 7, 1).unwrap());
+        task.reminders
+            .push(Reminder::new(ReminderTrigger::BeforeDue(60), Channel::Log));
+        let (user_id, task_id) = (user.id, task.id);
+        user.tasks.insert(task_id, task);
+
+        // ticks save, so keep the state file out of the working directory
+        let config = temp_config();
+        let mut data = load_data_from(&config.data_file());
+        data.users.insert(user_id, user);
+        let state = AppState {
+            data: Mutex::new(data),
+            config,
+        };
+        (state, user_id, task_id)
+    }
+
+    #[actix_web::test]
+    async fn test_reminder_fires_once() {
+        let (state, user_id, task_id) = state_with_reminder();
+        let recorder = Recorder::new(false);
+        let fire_at = Utc.with_ymd_and_hms(2024, 7, 1, 23, 0, 0).unwrap();
+
+        tick(&state, &recorder, fire_at - TimeDelta::seconds(1)).await;
+        assert_eq!(recorder.count(), 0);
+
+        tick(&state, &recorder, fire_at).await;
+        tick(&state, &recorder, fire_at + TimeDelta::minutes(5)).await;
+        assert_eq!(recorder.count(), 1);
+        assert!(state.data.lock().unwrap().reminder_jobs.is_empty());
+
+        // moving the due date arms the reminder again
+        if let Ok(mut data) = state.data.lock() {
+            let task = data
+                .users
+                .get_mut(&user_id)
+                .and_then(|user| user.tasks.get_mut(&task_id))
+                .unwrap();
+            task.due_date = NaiveDate::from_ymd_opt(2024, 7, 2).unwrap();
+        }
+        tick(&state, &recorder, fire_at + TimeDelta::days(1)).await;
+        assert_eq!(recorder.count(), 2);
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_failed_delivery_is_retried_and_persisted() {
+        let (state, _, _) = state_with_reminder();
+        let failing = Recorder::new(true);
+        let now = Utc.with_ymd_and_hms(2024, 7, 1, 23, 0, 0).unwrap();
+
+        tick(&state, &failing, now).await;
+        tick(&state, &failing, now + TimeDelta::seconds(10)).await;
+        assert_eq!(failing.count(), 1);
+
+        // the pending job survives a save/load round trip through the configured data dir
+        let restored = load_data_from(&state.config.data_file());
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+        assert_eq!(restored.reminder_jobs.len(), 1);
+        assert_eq!(restored.reminder_jobs[0].attempts, 1);
+        assert_eq!(
+            restored.reminder_jobs[0].next_attempt,
+            now + TimeDelta::seconds(30)
+        );
+        let state = AppState {
+            data: Mutex::new(restored),
+            config: state.config.clone(),
+        };
+
+        let recorder = Recorder::new(false);
+        tick(&state, &recorder, now + TimeDelta::seconds(30)).await;
+        assert_eq!(recorder.count(), 1);
+        assert!(state.data.lock().unwrap().reminder_jobs.is_empty());
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+
+        // a job that keeps failing is eventually dropped
+        let (state, _, _) = state_with_reminder();
+        let mut at = now;
+        for _ in 0..MAX_ATTEMPTS {
+            tick(&state, &failing, at).await;
+            at += TimeDelta::hours(1);
+        }
+        assert!(state.data.lock().unwrap().reminder_jobs.is_empty());
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_workspace_reminders() {
+        let (state, user_id, task_id) = state_with_reminder();
+        let fire_at = Utc.with_ymd_and_hms(2024, 7, 1, 23, 0, 0).unwrap();
+
+        // a delivery still pending when its task moves into the personal workspace
+        let failing = Recorder::new(true);
+        tick(&state, &failing, fire_at).await;
+        let workspace_id = state
+            .data
+            .lock()
+            .unwrap()
+            .migrate_personal_tasks(user_id)
+            .unwrap();
+        let recorder = Recorder::new(false);
+        tick(&state, &recorder, fire_at + TimeDelta::seconds(30)).await;
+        assert_eq!(recorder.count(), 1);
+
+        // workspace tasks fall due in their owner's zone; Berlin is UTC+2 in July
+        if let Ok(mut data) = state.data.lock() {
+            data.users.get_mut(&user_id).unwrap().timezone = Some("Europe/Berlin".to_string());
+            let task = data
+                .workspaces
+                .get_mut(&workspace_id)
+                .and_then(|workspace| workspace.tasks.get_mut(&task_id))
+                .unwrap();
+            task.due_date = NaiveDate::from_ymd_opt(2024, 7, 2).unwrap();
+        }
+        let fire_at = Utc.with_ymd_and_hms(2024, 7, 2, 21, 0, 0).unwrap();
+        tick(&state, &recorder, fire_at - TimeDelta::seconds(1)).await;
+        assert_eq!(recorder.count(), 1);
+        tick(&state, &recorder, fire_at).await;
+        assert_eq!(recorder.count(), 2);
+        assert!(state.data.lock().unwrap().reminder_jobs.is_empty());
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+    }
+
+    /// Requests seen by the stand-in receiver: (signature header, body)
+    type Received = web::Data<Mutex<Vec<(String, Vec<u8>)>>>;
+
+    async fn receive(req: HttpRequest, body: web::Bytes, received: Received) -> HttpResponse {
+        let signature = req
+            .headers()
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        received.lock().unwrap().push((signature, body.to_vec()));
+        if req.path() == "/ok" {
+            HttpResponse::Ok().finish()
+        } else {
+            HttpResponse::ServiceUnavailable().finish()
+        }
+    }
+
+    /// Starts a local HTTP receiver and returns its base url
+    fn start_receiver(received: Received) -> String {
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(received.clone())
+                .default_service(web::to(receive))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .expect("failed to bind receiver");
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        format!("http://{}", addr)
+    }
+
+    fn state_with_subscription(url: String, events: HashSet<EventKind>) -> (AppState, Uuid) {
+        let mut user = User::new("webhook-user");
+        user.webhooks
+            .push(Subscription::new(&url, events, Some("s3cret".to_string())));
+        let user_id = user.id;
+        let config = temp_config();
+        let mut data = load_data_from(&config.data_file());
+        data.users.insert(user_id, user);
+        let state = AppState {
+            data: Mutex::new(data),
+            config,
+        };
+        (state, user_id)
+    }
+
+    /// Drops the subscriber and the state file written by the ticks
+    fn cleanup(state: &AppState, user_id: Uuid) {
+        if let Ok(mut data) = state.data.lock() {
+            data.users.remove(&user_id);
+            save_data(&data);
+        }
+        let _ = std::fs::remove_dir_all(&state.config.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn test_webhook_is_signed_and_filtered() {
+        let received: Received = web::Data::new(Mutex::new(Vec::new()));
+        let base = start_receiver(received.clone());
+        let (state, user_id) = state_with_subscription(
+            format!("{}/ok", base),
+            HashSet::from([EventKind::TaskCreated]),
+        );
+        if let Ok(mut data) = state.data.lock() {
+            emit(
+                &mut data,
+                EventKind::TaskCreated,
+                user_id,
+                serde_json::json!({ "id": 1 }),
+            );
+            emit(
+                &mut data,
+                EventKind::TaskDeleted,
+                user_id,
+                serde_json::json!({ "id": 1 }),
+            );
+            assert_eq!(data.webhook_queue.len(), 1);
+        }
+
+        let client = reqwest::Client::new();
+        webhook_tick(&state, &client, Utc::now()).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (signature, body) = &received[0];
+        assert_eq!(*signature, sign("s3cret", body));
+        let event: crate::webhooks::WebhookEvent = serde_json::from_slice(body).unwrap();
+        assert_eq!(event.event, EventKind::TaskCreated);
+
+        if let Ok(data) = state.data.lock() {
+            assert!(data.webhook_queue.is_empty());
+            let log: Vec<_> = data.webhook_log.values().flatten().collect();
+            assert_eq!(log.len(), 1);
+            assert_eq!(log[0].status_code, Some(200));
+        }
+        // the delivery outcome was persisted to the configured data dir
+        let saved = load_data_from(&state.config.data_file());
+        assert!(saved.webhook_queue.is_empty());
+        cleanup(&state, user_id);
+    }
+
+    #[actix_web::test]
+    async fn test_failing_webhook_is_dead_lettered() {
+        let received: Received = web::Data::new(Mutex::new(Vec::new()));
+        let base = start_receiver(received.clone());
+        let (state, user_id) = state_with_subscription(format!("{}/fail", base), HashSet::new());
+        if let Ok(mut data) = state.data.lock() {
+            emit(
+                &mut data,
+                EventKind::TaskUpdated,
+                user_id,
+                serde_json::json!({}),
+            );
+        }
+
+        let client = reqwest::Client::new();
+        let now = Utc::now();
+        webhook_tick(&state, &client, now).await;
+        // backed off: nothing is sent again until the delay has passed
+        webhook_tick(&state, &client, now + TimeDelta::seconds(5)).await;
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert_eq!(
+            state.data.lock().unwrap().webhook_queue[0].next_attempt,
+            now + TimeDelta::seconds(10)
+        );
+
+        let mut at = now;
+        for _ in 1..crate::webhooks::MAX_ATTEMPTS {
+            at += TimeDelta::hours(1);
+            webhook_tick(&state, &client, at).await;
+        }
+        if let Ok(data) = state.data.lock() {
+            assert!(data.webhook_queue.is_empty());
+            assert_eq!(data.webhook_dead_letters.len(), 1);
+            let log: Vec<_> = data.webhook_log.values().flatten().collect();
+            assert_eq!(log.len() as u32, crate::webhooks::MAX_ATTEMPTS);
+            assert_eq!(log[0].status_code, Some(503));
+        }
+        cleanup(&state, user_id);
+    }
+}
@@ -1,11 +1,22 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs::File;
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use utoipa::openapi::{schema::Type, ObjectBuilder, RefOr, Schema};
+use utoipa::{PartialSchema, ToSchema};
 use uuid::Uuid;
 
-const DATA_FILE: &str = "data.json";
+use crate::events::{ChangeKind, EventBus, EventScope, TaskEvent};
+use crate::idempotency::StoredResponse;
+use crate::reminders::{Reminder, ReminderJob};
+use crate::webhooks::{DeliveryAttempt, Subscription, WebhookDelivery};
+use crate::workflow::Workflow;
+
+/// State file name, inside `Config::data_dir`
+pub const DATA_FILE: &str = "data.json";
 
 // User schema..
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,68 +24,614 @@ pub struct User {
     pub id: Uuid,
     pub name: String,
     pub tasks: HashMap<Uuid, Task>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Custom status workflow; `None` means the built-in one
+    #[serde(default)]
+    pub workflow: Option<Workflow>,
+    #[serde(default)]
+    pub projects: HashMap<Uuid, Project>,
+    #[serde(default)]
+    pub webhooks: Vec<Subscription>,
+    /// Secret in the URL of the user's calendar feed; `None` disables the feed
+    #[serde(default)]
+    pub feed_token: Option<String>,
+    /// Password of the user's CalDAV login; `None` disables CalDAV
+    #[serde(default)]
+    pub caldav_token: Option<String>,
 }
 impl User {
     pub fn new(name: &str) -> Self {
-        let id = Uuid::new_v4();
+        let id = Uuid::now_v7();
         User {
             id,
             name : name.to_string(),
             tasks: HashMap::new(),
+            email: None,
+            display_name: None,
+            timezone: None,
+            workflow: None,
+            projects: HashMap::new(),
+            webhooks: Vec::new(),
+            feed_token: None,
+            caldav_token: None,
         }
     }
+
+    /// The user's profile zone, UTC when unset
+    pub fn tz(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(parse_timezone)
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// The workflow governing this user's personal tasks
+    pub fn workflow(&self) -> Workflow {
+        self.workflow.clone().unwrap_or_default()
+    }
+}
+
+/// Minimal sanity check for profile emails: `local@domain` with a dot in the domain.
+pub fn is_valid_email(email: &str) -> bool {
+    match email.trim().split_once('@') {
+        Some((local, domain)) => !local.is_empty() && domain.contains('.') && !domain.contains('@'),
+        None => false,
+    }
+}
+
+// Username validation errors..
+#[derive(Debug, PartialEq)]
+pub enum UsernameError {
+    Empty,
+    Taken,
 }
 // Task Schema..
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Task {
     pub id: Uuid,
     pub title: String,
     pub description: String,
     pub due_date: NaiveDate,
+    /// Time of day the task is due; `None` means all-day (the whole due date)
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    /// IANA zone of `due_date`/`due_time`; `None` falls back to the owner's zone
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub status: Status,
+    #[serde(default)]
+    pub assignee: Option<Uuid>,
+    #[serde(default)]
+    pub watchers: HashSet<Uuid>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// Position within its board column, lowest first
+    #[serde(default)]
+    pub rank: u32,
+    /// Project of the owning user this task belongs to
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// Id of the last change event for this task; 0 before its first change
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+    /// todo.txt style priority, `A` (highest) to `Z`
+    #[serde(default)]
+    pub priority: Option<char>,
+    /// Labels without whitespace. Imported todo.txt `+project`s become plain
+    /// tags, `@context`s keep their `@`.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Task{
     pub fn new(title: &str, info: &str, due_date: NaiveDate) -> Self{
         Task { 
-            id: Uuid::new_v4(), 
+            id: Uuid::now_v7(), 
             title: title.to_string(), 
             description: info.to_string(), 
             due_date, 
-            status: Status::ToDo}
+            due_time: None,
+            timezone: None,
+            status: Status::ToDo,
+            assignee: None,
+            watchers: HashSet::new(),
+            attachments: Vec::new(),
+            rank: 0,
+            project_id: None,
+            reminders: Vec::new(),
+            version: 0,
+            updated_at: Utc::now(),
+            priority: None,
+            tags: Vec::new()}
+    }
+
+    /// The instant the task falls due. All-day tasks are due at the end of their
+    /// due date; the task's own zone wins over `default_tz`.
+    pub fn due_at(&self, default_tz: Tz) -> DateTime<Utc> {
+        let tz = self
+            .timezone
+            .as_deref()
+            .and_then(parse_timezone)
+            .unwrap_or(default_tz);
+        let local = match self.due_time {
+            Some(time) => self.due_date.and_time(time),
+            None => self
+                .due_date
+                .checked_add_days(Days::new(1))
+                .unwrap_or(self.due_date)
+                .and_time(NaiveTime::MIN),
+        };
+        local_to_utc(tz, local)
+    }
+
+    /// Past its due instant and not yet in a terminal state
+    pub fn is_overdue(&self, now: DateTime<Utc>, default_tz: Tz, workflow: &Workflow) -> bool {
+        self.due_at(default_tz) <= now && !workflow.is_terminal(&self.status)
+    }
+}
+
+// Project Schema, groups a user's tasks through `Task.project_id`..
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Project {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    /// `#RRGGBB` hex colour
+    pub colour: Option<String>,
+    pub archived: bool,
+}
+
+impl Project {
+    pub fn new(name: &str, description: &str, colour: Option<String>) -> Self {
+        Project {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: description.to_string(),
+            colour,
+            archived: false,
+        }
+    }
+}
+
+/// Checks a task's priority (`A` to `Z`) and tags (non-empty, no whitespace)
+pub fn check_labels(priority: Option<char>, tags: &[String]) -> Result<(), String> {
+    if priority.is_some_and(|priority| !priority.is_ascii_uppercase()) {
+        return Err("Priority must be a letter from A to Z".to_string());
+    }
+    match tags
+        .iter()
+        .find(|tag| tag.is_empty() || tag.contains(char::is_whitespace))
+    {
+        Some(tag) => Err(format!("Invalid tag: {:?}", tag)),
+        None => Ok(()),
+    }
+}
+
+/// Parses an IANA zone name such as `Europe/Berlin`
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Resolves a wall-clock time in `tz`. Ambiguous times take the earlier instant;
+/// times skipped by a DST jump are shifted forward by the jump.
+pub fn local_to_utc(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    (0..=2)
+        .filter_map(|hours| {
+            tz.from_local_datetime(&(local + TimeDelta::hours(hours)))
+                .earliest()
+        })
+        .next()
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+/// Accepts `#RRGGBB` hex colours
+pub fn is_valid_colour(colour: &str) -> bool {
+    colour.len() == 7
+        && colour.starts_with('#')
+        && colour[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Attachment Schema, the file itself lives in the blob store under `sha256`..
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+    pub sha256: String,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+// Comment Schema..
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Comment {
+    pub id: Uuid,
+    pub author: Uuid,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    pub fn new(author: Uuid, body: &str) -> Self {
+        Comment {
+            id: Uuid::new_v4(),
+            author,
+            body: body.to_string(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
     }
 }
 
 // Task-Status Schema..
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+// Serialized as a plain string, so custom workflow states sit next to the built-in ones.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
 pub enum Status {
     ToDo,
     InProgress,
     Done,
+    Custom(String),
+}
+
+impl Status {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Status::ToDo => "ToDo",
+            Status::InProgress => "InProgress",
+            Status::Done => "Done",
+            Status::Custom(name) => name,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "ToDo" => Status::ToDo,
+            "InProgress" => Status::InProgress,
+            "Done" => Status::Done,
+            _ => Status::Custom(name),
+        }
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> Self {
+        status.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Documented as the plain string it is serialized to
+impl PartialSchema for Status {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some(
+                "`ToDo`, `InProgress`, `Done` or a state of the user's custom workflow",
+            ))
+            .examples(["ToDo", "InProgress", "Done"])
+            .into()
+    }
+}
+
+impl ToSchema for Status {}
+
+// Workspace member roles, ordered from least to most privileged..
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+// Workspace Schema..
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    /// Personal workspaces are created by migrating a user's own tasks
+    #[serde(default)]
+    pub personal: bool,
+    pub members: HashMap<Uuid, Role>,
+    pub tasks: HashMap<Uuid, Task>,
+    /// Custom status workflow; `None` means the built-in one
+    #[serde(default)]
+    pub workflow: Option<Workflow>,
+}
+
+impl Workspace {
+    pub fn new(name: &str, owner: Uuid) -> Self {
+        Workspace {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            personal: false,
+            members: HashMap::from([(owner, Role::Owner)]),
+            tasks: HashMap::new(),
+            workflow: None,
+        }
+    }
+
+    /// The workflow governing this workspace's tasks
+    pub fn workflow(&self) -> Workflow {
+        self.workflow.clone().unwrap_or_default()
+    }
+
+    /// The workspace's first owner, by id
+    pub fn owner(&self) -> Option<Uuid> {
+        self.members
+            .iter()
+            .filter(|(_, role)| **role == Role::Owner)
+            .map(|(id, _)| *id)
+            .min()
+    }
+
+    /// Unassigns `user_id` from this workspace's tasks and drops them from
+    /// their watchers. Returns the ids of the tasks that changed.
+    pub fn remove_assignments(&mut self, user_id: Uuid) -> Vec<Uuid> {
+        let mut changed = Vec::new();
+        for task in self.tasks.values_mut() {
+            let assigned = task.assignee == Some(user_id);
+            if assigned {
+                task.assignee = None;
+            }
+            if task.watchers.remove(&user_id) || assigned {
+                changed.push(task.id);
+            }
+        }
+        changed
+    }
+}
+
+/// Why a user's tasks couldn't be moved into their personal workspace
+#[derive(Debug, PartialEq)]
+pub enum MigrateError {
+    UserNotFound,
+    /// States of the user's tasks that the workspace's workflow doesn't have
+    StrandedStates(Vec<String>),
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct AppStateData {
+    /// Where `save_data` writes; set by `load_data_from`, `data.json` otherwise
+    #[serde(skip)]
+    pub data_file: Option<PathBuf>,
     pub users: HashMap<Uuid, User>,
+    #[serde(default)]
+    pub workspaces: HashMap<Uuid, Workspace>,
+    /// Comment threads keyed by task id, oldest first
+    #[serde(default)]
+    pub comments: HashMap<Uuid, Vec<Comment>>,
+    /// Reminders that went off and still await delivery
+    #[serde(default)]
+    pub reminder_jobs: Vec<ReminderJob>,
+    /// Last fire time queued per reminder id, so each fires only once
+    #[serde(default)]
+    pub reminders_fired: HashMap<Uuid, DateTime<Utc>>,
+    /// Webhook deliveries awaiting a (re)try
+    #[serde(default)]
+    pub webhook_queue: Vec<WebhookDelivery>,
+    /// Webhook deliveries that exhausted their retries
+    #[serde(default)]
+    pub webhook_dead_letters: Vec<WebhookDelivery>,
+    /// Recent delivery attempts keyed by subscription id
+    #[serde(default)]
+    pub webhook_log: HashMap<Uuid, VecDeque<DeliveryAttempt>>,
+    /// Recent task events, oldest first, for clients resuming a stream
+    #[serde(default)]
+    pub event_log: VecDeque<TaskEvent>,
+    /// Id of the newest task event ever published
+    #[serde(default)]
+    pub last_event_id: u64,
+    #[serde(skip)]
+    pub event_bus: EventBus,
+    /// Responses to replay for repeated `Idempotency-Key`s
+    #[serde(default)]
+    pub idempotency_keys: HashMap<String, StoredResponse>,
+    /// Keys whose first request is still being handled
+    #[serde(skip)]
+    pub idempotency_in_flight: HashSet<String>,
 }
 
-pub fn load_data() -> AppStateData {
-    let mut file = match File::open(DATA_FILE) {
-        Ok(file) => file,
-        Err(_) => return AppStateData::default(),
-    };
+impl AppStateData {
+    /// Checks that `name` is non-empty and not already used by another user.
+    /// Names are compared case-insensitively; `except` skips the user being renamed.
+    pub fn validate_username(&self, name: &str, except: Option<Uuid>) -> Result<(), UsernameError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(UsernameError::Empty);
+        }
+        let taken = self
+            .users
+            .values()
+            .filter(|user| Some(user.id) != except)
+            .any(|user| user.name.trim().to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(UsernameError::Taken);
+        }
+        Ok(())
+    }
+
+    /// Every stored task, personal and workspace alike.
+    pub fn all_tasks_mut(&mut self) -> impl Iterator<Item = &mut Task> {
+        self.users
+            .values_mut()
+            .flat_map(|user| user.tasks.values_mut())
+            .chain(
+                self.workspaces
+                    .values_mut()
+                    .flat_map(|workspace| workspace.tasks.values_mut()),
+            )
+    }
+
+    /// Clears assignments to `user_id` and drops them from every watcher list.
+    pub fn remove_user_assignments(&mut self, user_id: Uuid) {
+        for task in self.all_tasks_mut() {
+            if task.assignee == Some(user_id) {
+                task.assignee = None;
+            }
+            task.watchers.remove(&user_id);
+        }
+    }
+
+    /// Total attachment bytes stored on `user_id`'s personal tasks
+    pub fn attachment_usage(&self, user_id: Uuid) -> u64 {
+        self.users
+            .get(&user_id)
+            .map(|user| {
+                user.tasks
+                    .values()
+                    .flat_map(|task| task.attachments.iter())
+                    .map(|attachment| attachment.size)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Role of `user_id` in `workspace_id`, if the workspace exists and they are a member.
+    pub fn member_role(&self, workspace_id: Uuid, user_id: Uuid) -> Option<Role> {
+        self.workspaces
+            .get(&workspace_id)
+            .and_then(|workspace| workspace.members.get(&user_id).copied())
+    }
+
+    /// Moves every task in `User.tasks` into the user's personal workspace,
+    /// creating that workspace on first use with the user's workflow. Returns
+    /// the workspace id. Workspaces have no projects, so tasks leave theirs.
+    pub fn migrate_personal_tasks(&mut self, user_id: Uuid) -> Result<Uuid, MigrateError> {
+        let user = self.users.get(&user_id).ok_or(MigrateError::UserNotFound)?;
+        let existing = self
+            .workspaces
+            .values()
+            .find(|ws| ws.personal && ws.members.get(&user_id) == Some(&Role::Owner));
+        let workspace_id = match existing {
+            Some(workspace) => {
+                // Tasks in states the workspace's workflow lacks could never move again
+                let workflow = workspace.workflow();
+                let stranded: BTreeSet<String> = user
+                    .tasks
+                    .values()
+                    .filter(|task| !workflow.has_state(&task.status))
+                    .map(|task| task.status.to_string())
+                    .collect();
+                if !stranded.is_empty() {
+                    return Err(MigrateError::StrandedStates(stranded.into_iter().collect()));
+                }
+                workspace.id
+            }
+            None => {
+                let mut workspace = Workspace::new(&format!("{}'s workspace", user.name), user_id);
+                workspace.personal = true;
+                workspace.workflow = user.workflow.clone();
+                let id = workspace.id;
+                self.workspaces.insert(id, workspace);
+                id
+            }
+        };
 
-    let mut data = String::new();
-    if file.read_to_string(&mut data).is_ok() {
-        serde_json::from_str(&data).unwrap_or_default()
-    } else {
-        AppStateData::default()
+        let tasks = match self.users.get_mut(&user_id) {
+            Some(user) => std::mem::take(&mut user.tasks),
+            None => HashMap::new(),
+        };
+        let task_ids: Vec<Uuid> = tasks.keys().copied().collect();
+        if let Some(workspace) = self.workspaces.get_mut(&workspace_id) {
+            workspace.tasks.extend(tasks.into_iter().map(|(id, mut task)| {
+                task.project_id = None;
+                (id, task)
+            }));
+        }
+        // Listeners of the user drop the tasks, the workspace's pick them up
+        for task_id in task_ids {
+            self.publish_deleted(EventScope::User(user_id), task_id);
+            self.publish_task(ChangeKind::Created, task_id);
+        }
+        Ok(workspace_id)
     }
+
+    /// Drops `user_id` from every workspace. Workspaces left without members are
+    /// deleted; workspaces left without an owner promote their highest-ranked members.
+    /// Returns the attachment blobs of the deleted tasks so the caller can drop orphans.
+    pub fn remove_user_memberships(&mut self, user_id: Uuid) -> Vec<String> {
+        for workspace in self.workspaces.values_mut() {
+            workspace.members.remove(&user_id);
+            if workspace.members.values().any(|role| *role == Role::Owner) {
+                continue;
+            }
+            if let Some(highest) = workspace.members.values().max().copied() {
+                for role in workspace.members.values_mut() {
+                    if *role == highest {
+                        *role = Role::Owner;
+                    }
+                }
+            }
+        }
+        let orphaned: Vec<Uuid> = self
+            .workspaces
+            .values()
+            .filter(|workspace| workspace.members.is_empty())
+            .flat_map(|workspace| workspace.tasks.keys().copied())
+            .collect();
+        let shas = self
+            .workspaces
+            .values()
+            .filter(|workspace| workspace.members.is_empty())
+            .flat_map(|workspace| workspace.tasks.values())
+            .flat_map(|task| task.attachments.iter().map(|a| a.sha256.clone()))
+            .collect();
+        self.workspaces
+            .retain(|_, workspace| !workspace.members.is_empty());
+        for task_id in orphaned {
+            self.comments.remove(&task_id);
+        }
+        shas
+    }
+}
+
+pub fn load_data() -> AppStateData {
+    load_data_from(Path::new(DATA_FILE))
+}
+
+/// Loads the state stored at `path`; later saves go back to the same file.
+pub fn load_data_from(path: &Path) -> AppStateData {
+    let mut data = match File::open(path) {
+        Ok(mut file) => {
+            let mut json = String::new();
+            if file.read_to_string(&mut json).is_ok() {
+                serde_json::from_str(&json).unwrap_or_default()
+            } else {
+                AppStateData::default()
+            }
+        }
+        Err(_) => AppStateData::default(),
+    };
+    data.data_file = Some(path.to_path_buf());
+    data
 }
 
 pub fn save_data(data: &AppStateData) {
+    let path = data.data_file.as_deref().unwrap_or(Path::new(DATA_FILE));
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
     if let Ok(json) = serde_json::to_string(data) {
-        if let Ok(mut file) = File::create(DATA_FILE) {
+        if let Ok(mut file) = File::create(path) {
             let _ = file.write_all(json.as_bytes());
         }
     }
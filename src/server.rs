@@ -1,35 +1,176 @@
 use crate::routes::{
-    task_routes::{create_task, delete_task, get_task, list_task, update_task},
-    user_routes::{create_user, delete_user},
+    board_routes::{get_board, get_workspace_board, move_task, move_workspace_task},
+    attachment_routes::{delete_attachment, download_attachment, upload_attachment},
+    caldav_routes::{
+        caldav_discovery, caldav_options, caldav_propfind, caldav_report, delete_caldav_task,
+        get_caldav_task, put_caldav_task,
+    },
+    calendar_routes::{
+        create_caldav_token, create_feed_token, import_ics, revoke_caldav_token,
+        revoke_feed_token, task_feed,
+    },
+    comment_routes::{create_comment, delete_comment, edit_comment, list_comments},
+    event_routes::{list_changes, task_socket, task_stream, workspace_socket},
+    graphql_routes::{graphql_playground, graphql_query, graphql_socket},
+    project_routes::{
+        create_project, delete_project, get_project, list_projects, move_to_project,
+        project_summary, update_project,
+    },
+    reminder_routes::{create_reminder, delete_reminder, list_reminders},
+    sync_routes::sync_tasks,
+    task_routes::{
+        assign_task, bulk_tasks, create_task, delete_task, get_task, list_assigned, list_task, agenda, quick_add, reschedule_task, unassign_task,
+        unwatch_task, update_task, watch_task,
+    },
+    transfer_routes::{export_tasks, import_tasks},
+    user_routes::{create_user, delete_user, get_user, list_users, rename_user, update_user},
+    webhook_routes::{
+        create_webhook, delete_webhook, list_dead_letters, list_deliveries, list_webhooks,
+        retry_dead_letter,
+    },
+    workflow_routes::{get_workflow, get_workspace_workflow, set_workflow, set_workspace_workflow},
+    workspace_routes::{
+        add_member, create_workspace, create_workspace_task, delete_workspace_task,
+        list_workspace_tasks, list_workspaces, migrate_tasks, remove_member,
+        update_workspace_task,
+    },
 };
+use crate::caldav::dav_method;
+use crate::graphql::build_schema;
+use crate::idempotency::idempotency;
+use crate::openapi::docs;
+use crate::scheduler::{run_scheduler, run_webhook_worker};
 use crate::AppState;
 
 // extern crate imports
-use actix_web::middleware::Logger;
+use actix_web::http::Method;
+use actix_web::middleware::{from_fn, Logger};
 use actix_web::{web, App, HttpServer};
 use anyhow::Result;
 
 pub async fn start_service(
     app_state: web::Data<AppState>,
 ) -> Result<actix_web::dev::Server, Box<dyn std::error::Error>> {
+    // reminders are delivered in the background for as long as the server runs
+    actix_web::rt::spawn(run_scheduler(app_state.clone()));
+    actix_web::rt::spawn(run_webhook_worker(app_state.clone()));
+    let schema = web::Data::new(build_schema(app_state.clone()));
+
     let server = HttpServer::new(move || {
         App::new()
             .app_data(app_state.clone())
+            .app_data(schema.clone())
+            .wrap(from_fn(idempotency))
             .wrap(Logger::default())
+            .service(docs())
+            .route("/feeds/{token}.ics", web::get().to(task_feed))
+            .route("/graphql", web::post().to(graphql_query))
+            .route("/graphql", web::get().to(graphql_playground))
+            .route("/graphql/ws", web::get().to(graphql_socket))
+            .route("/.well-known/caldav", web::route().to(caldav_discovery))
+            .service(
+                web::scope("/caldav")
+                    .route("/", web::method(dav_method("PROPFIND")).to(caldav_propfind))
+                    .route("/{userId}/", web::method(dav_method("PROPFIND")).to(caldav_propfind))
+                    .route("/{userId}/{collection}/", web::method(dav_method("PROPFIND")).to(caldav_propfind))
+                    .route("/{userId}/{collection}/", web::method(dav_method("REPORT")).to(caldav_report))
+                    .route("/{userId}/{collection}/{resource}", web::method(dav_method("PROPFIND")).to(caldav_propfind))
+                    .route("/{userId}/{collection}/{resource}", web::get().to(get_caldav_task))
+                    .route("/{userId}/{collection}/{resource}", web::put().to(put_caldav_task))
+                    .route("/{userId}/{collection}/{resource}", web::delete().to(delete_caldav_task))
+                    .route("/{tail:.*}", web::method(Method::OPTIONS).to(caldav_options)),
+            )
             .service(
                 web::scope("/users")
                     .wrap(Logger::default())
                     .route("/create", web::post().to(create_user))
                     .route("/delete", web::delete().to(delete_user))
+                    .route("/list", web::get().to(list_users))
+                    .route("/{userId}", web::get().to(get_user))
+                    .route("/{userId}/rename", web::put().to(rename_user))
+                    .route("/{userId}/update", web::put().to(update_user))
+                    .route("/{userId}/workflow", web::get().to(get_workflow))
+                    .route("/{userId}/workflow", web::put().to(set_workflow))
+                    .route("/{userId}/board", web::get().to(get_board))
+                    .route("/{userId}/board/move", web::put().to(move_task))
+                    .route("/{userId}/changes", web::get().to(list_changes))
+                    .route("/{userId}/sync", web::post().to(sync_tasks))
+                    .route("/{userId}/calendar/token", web::post().to(create_feed_token))
+                    .route("/{userId}/calendar/token", web::delete().to(revoke_feed_token))
+                    .route("/{userId}/calendar/import", web::post().to(import_ics))
+                    .route("/{userId}/caldav/token", web::post().to(create_caldav_token))
+                    .route("/{userId}/caldav/token", web::delete().to(revoke_caldav_token))
                     .service(
                         web::scope("/{userId}/tasks")
                             .wrap(Logger::default())
                             .wrap(Logger::default())
                             .route("/create", web::post().to(create_task))
+                            .route("/quick-add", web::post().to(quick_add))
+                            .route("/bulk", web::post().to(bulk_tasks))
+                            .route("/export", web::get().to(export_tasks))
+                            .route("/import", web::post().to(import_tasks))
                             .route("/list", web::get().to(list_task))
                             .route("/get-task", web::get().to(get_task))
                             .route("/update", web::put().to(update_task))
-                            .route("/delete", web::delete().to(delete_task)),
+                            .route("/delete", web::delete().to(delete_task))
+                            .route("/reschedule", web::put().to(reschedule_task))
+                            .route("/agenda", web::get().to(agenda))
+                            .route("/ws", web::get().to(task_socket))
+                            .route("/events", web::get().to(task_stream))
+                            .route("/assign", web::put().to(assign_task))
+                            .route("/unassign", web::put().to(unassign_task))
+                            .route("/watch", web::put().to(watch_task))
+                            .route("/unwatch", web::put().to(unwatch_task))
+                            .route("/assigned", web::get().to(list_assigned))
+                            .route("/{taskId}/comments/create", web::post().to(create_comment))
+                            .route("/{taskId}/comments/list", web::get().to(list_comments))
+                            .route("/{taskId}/comments/edit", web::put().to(edit_comment))
+                            .route("/{taskId}/comments/delete", web::delete().to(delete_comment))
+                            .route("/{taskId}/reminders/create", web::post().to(create_reminder))
+                            .route("/{taskId}/reminders/list", web::get().to(list_reminders))
+                            .route("/{taskId}/reminders/delete", web::delete().to(delete_reminder))
+                            .route("/{taskId}/attachments/upload", web::post().to(upload_attachment))
+                            .route("/{taskId}/attachments/delete", web::delete().to(delete_attachment))
+                            .route("/{taskId}/attachments/{attachmentId}", web::get().to(download_attachment)),
+                    )
+                    .service(
+                        web::scope("/{userId}/projects")
+                            .wrap(Logger::default())
+                            .route("/create", web::post().to(create_project))
+                            .route("/list", web::get().to(list_projects))
+                            .route("/update", web::put().to(update_project))
+                            .route("/delete", web::delete().to(delete_project))
+                            .route("/move-task", web::put().to(move_to_project))
+                            .route("/{projectId}", web::get().to(get_project))
+                            .route("/{projectId}/summary", web::get().to(project_summary)),
+                    )
+                    .service(
+                        web::scope("/{userId}/webhooks")
+                            .wrap(Logger::default())
+                            .route("/create", web::post().to(create_webhook))
+                            .route("/list", web::get().to(list_webhooks))
+                            .route("/delete", web::delete().to(delete_webhook))
+                            .route("/dead-letters", web::get().to(list_dead_letters))
+                            .route("/dead-letters/retry", web::post().to(retry_dead_letter))
+                            .route("/{webhookId}/deliveries", web::get().to(list_deliveries)),
+                    )
+                    .service(
+                        web::scope("/{userId}/workspaces")
+                            .wrap(Logger::default())
+                            .route("/create", web::post().to(create_workspace))
+                            .route("/list", web::get().to(list_workspaces))
+                            .route("/migrate", web::post().to(migrate_tasks))
+                            .route("/{workspaceId}/ws", web::get().to(workspace_socket))
+                            .route("/{workspaceId}/workflow", web::get().to(get_workspace_workflow))
+                            .route("/{workspaceId}/workflow", web::put().to(set_workspace_workflow))
+                            .route("/{workspaceId}/board", web::get().to(get_workspace_board))
+                            .route("/{workspaceId}/board/move", web::put().to(move_workspace_task))
+                            .route("/{workspaceId}/members/add", web::post().to(add_member))
+                            .route("/{workspaceId}/members/remove", web::delete().to(remove_member))
+                            .route("/{workspaceId}/tasks/create", web::post().to(create_workspace_task))
+                            .route("/{workspaceId}/tasks/list", web::get().to(list_workspace_tasks))
+                            .route("/{workspaceId}/tasks/update", web::put().to(update_workspace_task))
+                            .route("/{workspaceId}/tasks/delete", web::delete().to(delete_workspace_task)),
                     ),
             )
     })
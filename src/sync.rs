@@ -0,0 +1,353 @@
+// src/sync.rs
+use crate::{
+    events::ChangeKind,
+    routes::task_routes::{add_task, discard_task, error_message, set_status, NewTask, UpdateTask},
+    schema::{parse_timezone, AppStateData, Task},
+    webhooks::{emit, EventKind},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Task fields a sync client may write
+pub const SYNC_FIELDS: [&str; 6] = [
+    "title",
+    "description",
+    "due_date",
+    "due_time",
+    "timezone",
+    "status",
+];
+
+/// What happens when client and server both changed a field since the client's base version
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// The later of the client's timestamp and the server's last change wins
+    #[default]
+    LastWriterWins,
+    /// The server keeps its value and the conflict is reported back
+    Report,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OpKind {
+    Create,
+    Update,
+    Delete,
+}
+
+/// One change made on a client while offline
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SyncOp {
+    pub op: OpKind,
+    /// For creates, the id the client generated for the new task
+    pub task_id: Uuid,
+    /// Task version the client's change was based on
+    #[serde(default)]
+    pub base_version: Option<u64>,
+    /// When the change was made on the client
+    pub client_time: DateTime<Utc>,
+    /// Changed fields out of `SYNC_FIELDS`, with their new values
+    #[serde(default)]
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OpStatus {
+    Applied,
+    /// Some or all fields were kept at the server's value
+    Conflict,
+    /// The operation was invalid and nothing was changed
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct FieldConflict {
+    pub field: String,
+    pub server: Value,
+    pub client: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OpResult {
+    pub task_id: Uuid,
+    pub status: OpStatus,
+    /// Fields written by this operation
+    #[serde(default)]
+    pub applied: Vec<String>,
+    #[serde(default)]
+    pub conflicts: Vec<FieldConflict>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Version of the task after the operation; `None` once deleted
+    #[serde(default)]
+    pub version: Option<u64>,
+}
+
+impl OpResult {
+    fn new(task_id: Uuid, status: OpStatus) -> Self {
+        OpResult {
+            task_id,
+            status,
+            applied: Vec::new(),
+            conflicts: Vec::new(),
+            error: None,
+            version: None,
+        }
+    }
+
+    fn rejected(task_id: Uuid, error: impl Into<String>) -> Self {
+        OpResult {
+            error: Some(error.into()),
+            ..OpResult::new(task_id, OpStatus::Rejected)
+        }
+    }
+}
+
+/// Applies one client operation to `user_id`'s personal tasks
+pub fn apply_op(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    op: &SyncOp,
+    strategy: ConflictStrategy,
+    blob_dir: &Path,
+) -> OpResult {
+    if let Some(field) = op
+        .fields
+        .keys()
+        .find(|field| !SYNC_FIELDS.contains(&field.as_str()))
+    {
+        return OpResult::rejected(op.task_id, format!("Field can't be synced: {}", field));
+    }
+    match op.op {
+        OpKind::Create => create(data, user_id, op),
+        OpKind::Update => update(data, user_id, op, strategy),
+        OpKind::Delete => delete(data, user_id, op, strategy, blob_dir),
+    }
+}
+
+fn to_map(task: &Task) -> Map<String, Value> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Builds the task `fields` describe and checks it against the owner's workflow
+fn merge(data: &AppStateData, user_id: Uuid, fields: Map<String, Value>) -> Result<Task, String> {
+    let task: Task =
+        serde_json::from_value(Value::Object(fields)).map_err(|err| err.to_string())?;
+    if let Some(timezone) = &task.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Err(format!("Unknown time zone: {}", timezone));
+        }
+    }
+    let workflow = data.users[&user_id].workflow();
+    if !workflow.has_state(&task.status) {
+        return Err(format!("Unknown status: {}", task.status));
+    }
+    Ok(task)
+}
+
+fn create(data: &mut AppStateData, user_id: Uuid, op: &SyncOp) -> OpResult {
+    let user = &data.users[&user_id];
+    if let Some(task) = user.tasks.get(&op.task_id) {
+        // a replayed create
+        return OpResult {
+            version: Some(task.version),
+            ..OpResult::new(op.task_id, OpStatus::Applied)
+        };
+    }
+    if data.task_scope(op.task_id).is_some() {
+        return OpResult::rejected(op.task_id, "Task id is already in use");
+    }
+    for required in ["title", "due_date"] {
+        if !op.fields.contains_key(required) {
+            return OpResult::rejected(op.task_id, format!("Missing field: {}", required));
+        }
+    }
+
+    let mut blank = Task::new("", "", op.client_time.date_naive());
+    blank.id = op.task_id;
+    if let Some(initial) = user.workflow().states.first() {
+        blank.status = initial.status.clone();
+    }
+    let mut fields = to_map(&blank);
+    fields.extend(op.fields.clone());
+    let task = match merge(data, user_id, fields) {
+        Ok(task) => task,
+        Err(err) => return OpResult::rejected(op.task_id, err),
+    };
+
+    // Created like any other task, then moved on if the client already did so
+    let req = NewTask {
+        id: Some(op.task_id),
+        title: task.title,
+        description: task.description,
+        due_date: task.due_date,
+        due_time: task.due_time,
+        timezone: task.timezone,
+        status: task.status.clone(),
+        project_id: None,
+        priority: None,
+        tags: Vec::new(),
+    };
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .expect("user checked by caller");
+    if let Err(err) = add_task(user, &req) {
+        return OpResult::rejected(op.task_id, error_message(err));
+    }
+    let workflow = user.workflow();
+    let moved = UpdateTask {
+        id: op.task_id,
+        status: task.status,
+    };
+    if let Err(err) = set_status(&mut user.tasks, &workflow, &moved) {
+        user.tasks.remove(&op.task_id);
+        return OpResult::rejected(op.task_id, error_message(err));
+    }
+    let payload = serde_json::to_value(&user.tasks[&op.task_id]).unwrap_or_default();
+    emit(data, EventKind::TaskCreated, user_id, payload);
+    data.publish_task(ChangeKind::Created, op.task_id);
+
+    OpResult {
+        applied: op.fields.keys().cloned().collect(),
+        version: Some(data.last_event_id),
+        ..OpResult::new(op.task_id, OpStatus::Applied)
+    }
+}
+
+fn update(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    op: &SyncOp,
+    strategy: ConflictStrategy,
+) -> OpResult {
+    let Some(task) = data.users[&user_id].tasks.get(&op.task_id).cloned() else {
+        return OpResult {
+            error: Some("Task was deleted on the server".to_string()),
+            ..OpResult::new(op.task_id, OpStatus::Conflict)
+        };
+    };
+
+    // Fields the server changed after the client's base version can't be
+    // overwritten blindly. Without the base snapshot every field counts as changed.
+    let current = to_map(&task);
+    let unchanged = op.base_version == Some(task.version);
+    let base = op
+        .base_version
+        .and_then(|version| data.task_at_version(op.task_id, version))
+        .map(to_map);
+    let client_wins =
+        strategy == ConflictStrategy::LastWriterWins && op.client_time > task.updated_at;
+
+    let mut result = OpResult::new(op.task_id, OpStatus::Applied);
+    let mut fields = current.clone();
+    for (field, client) in &op.fields {
+        let server = current.get(field).cloned().unwrap_or(Value::Null);
+        if *client == server {
+            continue;
+        }
+        let server_changed = !unchanged
+            && base
+                .as_ref()
+                .is_none_or(|base| base.get(field) != Some(&server));
+        if !server_changed || client_wins {
+            fields.insert(field.clone(), client.clone());
+            result.applied.push(field.clone());
+        } else {
+            result.conflicts.push(FieldConflict {
+                field: field.clone(),
+                server,
+                client: client.clone(),
+            });
+        }
+    }
+    if !result.conflicts.is_empty() {
+        result.status = OpStatus::Conflict;
+    }
+    if result.applied.is_empty() {
+        result.version = Some(task.version);
+        return result;
+    }
+
+    let updated = match merge(data, user_id, fields) {
+        Ok(updated) => updated,
+        Err(err) => return OpResult::rejected(op.task_id, err),
+    };
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .expect("user checked by caller");
+    // Status changes go through the same workflow checks as the update endpoint
+    let moved = UpdateTask {
+        id: op.task_id,
+        status: updated.status.clone(),
+    };
+    user.tasks.insert(
+        op.task_id,
+        Task {
+            status: task.status.clone(),
+            ..updated
+        },
+    );
+    let workflow = user.workflow();
+    let completed = match set_status(&mut user.tasks, &workflow, &moved) {
+        Ok(completed) => completed,
+        Err(err) => {
+            user.tasks.insert(op.task_id, task);
+            return OpResult::rejected(op.task_id, error_message(err));
+        }
+    };
+
+    let payload = serde_json::to_value(&user.tasks[&op.task_id]).unwrap_or_default();
+    emit(data, EventKind::TaskUpdated, user_id, payload.clone());
+    if completed {
+        emit(data, EventKind::TaskCompleted, user_id, payload);
+    }
+    data.publish_task(ChangeKind::Updated, op.task_id);
+    result.version = Some(data.last_event_id);
+    result
+}
+
+fn delete(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    op: &SyncOp,
+    strategy: ConflictStrategy,
+    blob_dir: &Path,
+) -> OpResult {
+    let Some(task) = data.users[&user_id].tasks.get(&op.task_id) else {
+        // already gone
+        return OpResult::new(op.task_id, OpStatus::Applied);
+    };
+    let server_changed = op
+        .base_version
+        .is_some_and(|version| version != task.version);
+    let client_wins =
+        strategy == ConflictStrategy::LastWriterWins && op.client_time > task.updated_at;
+    if server_changed && !client_wins {
+        return OpResult {
+            error: Some("Task was changed on the server since the base version".to_string()),
+            version: Some(task.version),
+            ..OpResult::new(op.task_id, OpStatus::Conflict)
+        };
+    }
+
+    let user = data
+        .users
+        .get_mut(&user_id)
+        .expect("user checked by caller");
+    if let Some(task) = user.tasks.remove(&op.task_id) {
+        discard_task(data, blob_dir, user_id, task);
+    }
+    OpResult::new(op.task_id, OpStatus::Applied)
+}
@@ -0,0 +1,379 @@
+// src/todotxt.rs
+use crate::{
+    schema::{Task, User},
+    transfer::Rows,
+    workflow::Workflow,
+};
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+/// A task as written in todo.txt or in a Markdown checklist
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Item {
+    pub id: Option<Uuid>,
+    /// `x` in todo.txt, `[x]` in Markdown
+    pub done: bool,
+    pub priority: Option<char>,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub due_date: Option<NaiveDate>,
+    /// The lines indented below a Markdown checkbox; todo.txt has none
+    pub description: Option<String>,
+}
+
+fn parse_date(word: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()
+}
+
+/// `(A)` to `(Z)`
+fn parse_priority(word: &str) -> Option<char> {
+    let mut chars = word.strip_prefix('(')?.strip_suffix(')')?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(priority), None) if priority.is_ascii_uppercase() => Some(priority),
+        _ => None,
+    }
+}
+
+impl Item {
+    /// Tasks in a terminal workflow state are written as done
+    pub fn from_task(task: &Task, workflow: &Workflow) -> Self {
+        Item {
+            id: Some(task.id),
+            done: workflow.is_terminal(&task.status),
+            priority: task.priority,
+            title: task.title.clone(),
+            tags: task.tags.clone(),
+            due_date: Some(task.due_date),
+            description: Some(task.description.clone()),
+        }
+    }
+
+    /// Import fields for `transfer::import_row`. Done items go to the first
+    /// terminal state of `workflow`, open ones to its first state.
+    pub fn into_fields(self, workflow: &Workflow) -> Map<String, Value> {
+        let mut fields = Map::new();
+        if let Some(id) = self.id {
+            fields.insert("id".into(), Value::String(id.to_string()));
+        }
+        fields.insert("title".into(), Value::String(self.title));
+        if let Some(description) = self.description {
+            fields.insert("description".into(), Value::String(description));
+        }
+        if let Some(priority) = self.priority {
+            fields.insert("priority".into(), Value::String(priority.to_string()));
+        }
+        let tags = self.tags.into_iter().map(Value::String).collect();
+        fields.insert("tags".into(), Value::Array(tags));
+        if let Some(due_date) = self.due_date {
+            fields.insert("due_date".into(), Value::String(due_date.to_string()));
+        }
+        let status = match self.done {
+            true => workflow.states.iter().find(|state| state.terminal),
+            false => workflow.states.first(),
+        };
+        if let Some(state) = status {
+            fields.insert("status".into(), Value::String(state.status.to_string()));
+        }
+        fields
+    }
+
+    /// Title, tags and `key:value`s, shared by both formats
+    fn body(&self) -> String {
+        let mut words = vec![self.title.clone()];
+        for tag in &self.tags {
+            match tag.starts_with('@') {
+                true => words.push(tag.clone()),
+                false => words.push(format!("+{}", tag)),
+            }
+        }
+        if let Some(due_date) = self.due_date {
+            words.push(format!("due:{}", due_date));
+        }
+        if let Some(id) = self.id {
+            words.push(format!("id:{}", id));
+        }
+        words.join(" ")
+    }
+
+    /// Reads the words after the completion mark, priority and dates
+    fn parse_body<'a>(&mut self, words: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let mut title = Vec::new();
+        for word in words {
+            if let Some(tag) = word.strip_prefix('+').filter(|tag| !tag.is_empty()) {
+                self.add_tag(tag);
+            } else if word.len() > 1 && word.starts_with('@') {
+                self.add_tag(word);
+            } else if let Some(date) = word.strip_prefix("due:") {
+                self.due_date =
+                    Some(parse_date(date).ok_or_else(|| format!("Invalid due date: {}", date))?);
+            } else if let Some(id) = word.strip_prefix("id:") {
+                self.id = Some(Uuid::parse_str(id).map_err(|_| format!("Invalid id: {}", id))?);
+            } else if let Some(priority) = word
+                .strip_prefix("pri:")
+                .and_then(|priority| parse_priority(&format!("({})", priority)))
+            {
+                self.priority = Some(priority);
+            } else {
+                title.push(word);
+            }
+        }
+        self.title = title.join(" ");
+        Ok(())
+    }
+
+    fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|known| known == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// One todo.txt line, without the newline. Done items keep their
+    /// priority as `pri:`, as the format drops the `(A)` on completion.
+    pub fn to_todo_txt(&self) -> String {
+        let mut line = match (self.done, self.priority) {
+            (true, _) => "x ".to_string(),
+            (false, Some(priority)) => format!("({}) ", priority),
+            (false, None) => String::new(),
+        };
+        line.push_str(&self.body());
+        if let (true, Some(priority)) = (self.done, self.priority) {
+            line.push_str(&format!(" pri:{}", priority));
+        }
+        line
+    }
+
+    /// Parses one todo.txt line. Completion and creation dates are skipped.
+    pub fn parse_todo_txt(line: &str) -> Result<Self, String> {
+        let mut item = Item::default();
+        let mut words = line.split_whitespace().peekable();
+        if words.peek() == Some(&"x") {
+            item.done = true;
+            words.next();
+        } else if let Some(priority) = words.peek().and_then(|word| parse_priority(word)) {
+            item.priority = Some(priority);
+            words.next();
+        }
+        // completion and creation dates
+        for _ in 0..2 {
+            if words.peek().and_then(|word| parse_date(word)).is_some() {
+                words.next();
+            }
+        }
+        item.parse_body(words)?;
+        Ok(item)
+    }
+
+    /// A Markdown checklist entry with its description indented below,
+    /// ending in a newline
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("- [{}] ", if self.done { 'x' } else { ' ' });
+        if let Some(priority) = self.priority {
+            out.push_str(&format!("({}) ", priority));
+        }
+        out.push_str(&self.body());
+        out.push('\n');
+        for line in self
+            .description
+            .iter()
+            .flat_map(|description| description.lines())
+        {
+            out.push_str(&format!("  {}\n", line));
+        }
+        out
+    }
+}
+
+/// The mark and text of a `- [ ] text` line, at any indentation
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let done = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((done, rest[3..].trim()))
+}
+
+/// Items of a todo.txt file, one per non-blank line
+pub fn parse_todo_txt(text: &str) -> Vec<Result<Item, String>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(Item::parse_todo_txt)
+        .collect()
+}
+
+/// Items of the checkboxes in a Markdown document. Nested checkboxes are
+/// items of their own; other indented lines below one are its description.
+pub fn parse_markdown(text: &str) -> Vec<Result<Item, String>> {
+    let mut items: Vec<Result<Item, String>> = Vec::new();
+    let mut description: Option<Vec<&str>> = None;
+    let finish = |items: &mut Vec<Result<Item, String>>, lines: Option<Vec<&str>>| {
+        if let (Some(Ok(item)), Some(lines)) = (items.last_mut(), lines) {
+            item.description = Some(lines.join("\n").trim_end().to_string());
+        }
+    };
+    for line in text.lines() {
+        if let Some((done, rest)) = checkbox(line) {
+            finish(&mut items, description.take());
+            let mut words = rest.split_whitespace().peekable();
+            let mut item = Item {
+                done,
+                priority: words.peek().and_then(|word| parse_priority(word)),
+                ..Item::default()
+            };
+            if item.priority.is_some() {
+                words.next();
+            }
+            items.push(item.parse_body(words).map(|_| item));
+            description = Some(Vec::new());
+        } else if let Some(lines) = description
+            .as_mut()
+            .filter(|_| line.starts_with("  ") || line.starts_with('\t'))
+        {
+            let line = line.strip_prefix('\t').unwrap_or(line);
+            lines.push(line.strip_prefix("  ").unwrap_or(line));
+        } else {
+            finish(&mut items, description.take());
+        }
+    }
+    finish(&mut items, description);
+    items
+}
+
+/// Import rows of parsed items
+pub fn rows(items: Vec<Result<Item, String>>, workflow: &Workflow) -> Rows {
+    items
+        .into_iter()
+        .map(|item| item.map(|item| item.into_fields(workflow)))
+        .collect()
+}
+
+/// Leaves out the status of rows matching a task that is already open or
+/// done as the row says, so re-importing an open item doesn't move a task
+/// in progress back to the first state. Rows match by id, else by title and
+/// due date.
+pub fn keep_states(rows: &mut Rows, user: &User) {
+    let workflow = user.workflow();
+    for fields in rows.iter_mut().flatten() {
+        let field = |name: &str| fields.get(name).and_then(Value::as_str);
+        let task = match field("id").and_then(|id| Uuid::parse_str(id).ok()) {
+            Some(id) => user.tasks.get(&id),
+            None => user.tasks.values().find(|task| {
+                field("title") == Some(task.title.as_str())
+                    && field("due_date") == Some(task.due_date.to_string().as_str())
+            }),
+        };
+        let done = field("status").map(|status| workflow.is_terminal(&status.to_string().into()));
+        if task.is_some_and(|task| Some(workflow.is_terminal(&task.status)) == done) {
+            fields.remove("status");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_todo_txt_round_trip() {
+        let open = Item {
+            id: Some(Uuid::now_v7()),
+            done: false,
+            priority: Some('A'),
+            title: "Call the plumber".to_string(),
+            tags: vec!["house".to_string(), "@phone".to_string()],
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+            description: None,
+        };
+        let done = Item {
+            id: None,
+            done: true,
+            priority: Some('C'),
+            title: "File taxes".to_string(),
+            tags: Vec::new(),
+            due_date: None,
+            description: None,
+        };
+        for item in [&open, &done] {
+            assert_eq!(Item::parse_todo_txt(&item.to_todo_txt()).unwrap(), *item);
+        }
+        assert_eq!(
+            open.to_todo_txt(),
+            format!(
+                "(A) Call the plumber +house @phone due:2024-03-01 id:{}",
+                open.id.unwrap()
+            )
+        );
+        assert_eq!(done.to_todo_txt(), "x File taxes pri:C");
+
+        // lines as other todo.txt tools write them
+        let items = parse_todo_txt(
+            "x 2024-03-02 2024-02-20 Buy milk @store +groceries\n\n\
+             (B) 2024-02-20 Plan trip due:2024-04-01\n\
+             Broken due:someday\n",
+        );
+        let first = items[0].as_ref().unwrap();
+        assert!(first.done);
+        assert_eq!(first.title, "Buy milk");
+        assert_eq!(first.tags, vec!["@store", "groceries"]);
+        let second = items[1].as_ref().unwrap();
+        assert_eq!(second.priority, Some('B'));
+        assert_eq!(second.title, "Plan trip");
+        assert_eq!(second.due_date, NaiveDate::from_ymd_opt(2024, 4, 1));
+        assert!(items[2].is_err());
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let items = vec![
+            Item {
+                done: true,
+                priority: Some('B'),
+                title: "Write report".to_string(),
+                tags: vec!["work".to_string()],
+                due_date: NaiveDate::from_ymd_opt(2024, 3, 8),
+                description: Some("quarterly numbers\n\nand the outlook".to_string()),
+                ..Item::default()
+            },
+            Item {
+                id: Some(Uuid::now_v7()),
+                title: "Water plants".to_string(),
+                description: Some(String::new()),
+                ..Item::default()
+            },
+        ];
+        let markdown: String = items.iter().map(Item::to_markdown).collect();
+        assert_eq!(
+            markdown,
+            format!(
+                "- [x] (B) Write report +work due:2024-03-08\n  quarterly numbers\n  \n  and the outlook\n\
+                 - [ ] Water plants id:{}\n",
+                items[1].id.unwrap()
+            )
+        );
+        let parsed: Vec<Item> = parse_markdown(&markdown)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parsed, items);
+
+        // headings and prose are skipped, nested checkboxes are items too
+        let parsed = parse_markdown(
+            "# Week\n\nSome notes\n* [X] Ship it\n    - [ ] Tell the team @chat\n      on Monday\n",
+        );
+        let titles: Vec<_> = parsed
+            .iter()
+            .map(|item| item.as_ref().unwrap().title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Ship it", "Tell the team"]);
+        assert_eq!(
+            parsed[1].as_ref().unwrap().description.as_deref(),
+            Some("    on Monday")
+        );
+    }
+}
@@ -0,0 +1,460 @@
+// src/transfer.rs
+use crate::{
+    board::{check_wip_limit, next_rank},
+    events::ChangeKind,
+    routes::task_routes::{error_message, set_status, UpdateTask},
+    schema::{check_labels, parse_timezone, AppStateData, Status, Task, User},
+    webhooks::{emit, EventKind},
+};
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Columns of an exported CSV, in order. Nested fields are JSON-encoded.
+pub const CSV_COLUMNS: [&str; 17] = [
+    "id",
+    "title",
+    "description",
+    "due_date",
+    "due_time",
+    "timezone",
+    "status",
+    "priority",
+    "tags",
+    "assignee",
+    "watchers",
+    "rank",
+    "project_id",
+    "attachments",
+    "reminders",
+    "version",
+    "updated_at",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    #[default]
+    Json,
+    Csv,
+    /// One todo.txt line per task
+    #[serde(alias = "todotxt")]
+    TodoTxt,
+    /// A `- [ ]` checklist
+    Markdown,
+}
+
+/// What an import does with a row matching an existing task, by id or by title and due date
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDuplicate {
+    /// Leave the existing task alone
+    #[default]
+    Skip,
+    /// Overwrite the existing task with the row
+    Update,
+    /// Import the row as a new task anyway
+    Create,
+    /// Report the row as invalid
+    Fail,
+}
+
+/// The task fields an import reads; other columns are ignored
+#[derive(Debug, Deserialize)]
+pub struct ImportRow {
+    #[serde(default)]
+    pub id: Option<Uuid>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub due_date: NaiveDate,
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub priority: Option<char>,
+    /// A list, or a `;`-separated string as in the CSV export
+    #[serde(default, deserialize_with = "tag_list")]
+    pub tags: Vec<String>,
+}
+
+fn tag_list<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        List(Vec<String>),
+        Joined(String),
+    }
+    Ok(match Tags::deserialize(deserializer)? {
+        Tags::List(tags) => tags,
+        Tags::Joined(tags) => tags
+            .split(';')
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RowAction {
+    Created,
+    Updated,
+    Skipped,
+    Invalid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RowOutcome {
+    /// 1-based, not counting the CSV header
+    pub row: usize,
+    pub action: RowAction,
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub invalid: usize,
+    /// One per input row, in order
+    pub rows: Vec<RowOutcome>,
+}
+
+fn encode(fields: &[String]) -> Vec<u8> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    // writing to a Vec can't fail
+    let _ = writer.write_record(fields);
+    writer.into_inner().unwrap_or_default()
+}
+
+pub fn csv_header() -> Vec<u8> {
+    encode(&CSV_COLUMNS.map(str::to_string))
+}
+
+pub fn csv_row(task: &Task) -> Vec<u8> {
+    let opt = |value: Option<String>| value.unwrap_or_default();
+    let mut watchers: Vec<String> = task.watchers.iter().map(Uuid::to_string).collect();
+    watchers.sort();
+    encode(&[
+        task.id.to_string(),
+        task.title.clone(),
+        task.description.clone(),
+        task.due_date.to_string(),
+        opt(task.due_time.map(|time| time.to_string())),
+        opt(task.timezone.clone()),
+        task.status.to_string(),
+        opt(task.priority.map(String::from)),
+        task.tags.join(";"),
+        opt(task.assignee.map(|id| id.to_string())),
+        watchers.join(";"),
+        task.rank.to_string(),
+        opt(task.project_id.map(|id| id.to_string())),
+        to_json(&task.attachments),
+        to_json(&task.reminders),
+        task.version.to_string(),
+        task.updated_at.to_rfc3339(),
+    ])
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Parsed input rows; a row that couldn't be read carries its error
+pub type Rows = Vec<Result<Map<String, Value>, String>>;
+
+/// `Source:field` pairs, comma-separated, e.g. `Name:title,Deadline:due_date`
+pub fn parse_columns(spec: &str) -> Result<HashMap<String, String>, String> {
+    spec.split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once(':') {
+            Some((source, field)) if !source.trim().is_empty() && !field.trim().is_empty() => {
+                Ok((source.trim().to_string(), field.trim().to_string()))
+            }
+            _ => Err(format!("Invalid column mapping: {}", pair)),
+        })
+        .collect()
+}
+
+/// Rows of a CSV with a header line. Empty cells are left out.
+pub fn parse_csv(body: &[u8]) -> Result<Rows, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(body);
+    let header = reader
+        .headers()
+        .map_err(|err| format!("Invalid CSV header: {}", err))?
+        .clone();
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|err| err.to_string())?;
+            Ok(header
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, cell)| !cell.is_empty())
+                .map(|(column, cell)| (column.to_string(), Value::String(cell.to_string())))
+                .collect())
+        })
+        .collect())
+}
+
+/// Rows of a JSON array of objects
+pub fn parse_json(body: &[u8]) -> Result<Rows, String> {
+    let rows: Vec<Value> =
+        serde_json::from_slice(body).map_err(|err| format!("Invalid JSON: {}", err))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| match row {
+            Value::Object(fields) => Ok(fields),
+            _ => Err("Row is not an object".to_string()),
+        })
+        .collect())
+}
+
+/// Renames mapped columns to task fields; unmapped columns keep their name
+pub fn remap(row: Map<String, Value>, columns: &HashMap<String, String>) -> Map<String, Value> {
+    row.into_iter()
+        .map(|(column, value)| match columns.get(&column) {
+            Some(field) => (field.clone(), value),
+            None => (column, value),
+        })
+        .collect()
+}
+
+/// `check_wip_limit` for a task entering `status`, as a row error
+fn check_column(user: &User, status: &Status) -> Result<(), String> {
+    check_wip_limit(&user.tasks, &user.workflow(), status)
+        .map_err(|_| format!("Column {} is at its WIP limit", status))
+}
+
+/// A task changed by an imported row, to publish once the import is committed
+pub struct RowChange {
+    pub kind: ChangeKind,
+    pub task_id: Uuid,
+    /// The row moved the task into a terminal state
+    pub completed: bool,
+}
+
+/// Imports one row into `user`, a copy of the user held in `data`. Returns
+/// the action taken and, for changes, what to publish once committed.
+/// Updates keep the current value of every optional column the row leaves out.
+pub fn import_row(
+    data: &AppStateData,
+    user: &mut User,
+    fields: Map<String, Value>,
+    on_duplicate: OnDuplicate,
+) -> Result<(RowAction, Uuid, Option<RowChange>), String> {
+    let provided: HashSet<String> = fields.keys().cloned().collect();
+    let row: ImportRow =
+        serde_json::from_value(Value::Object(fields)).map_err(|err| err.to_string())?;
+    if row.title.trim().is_empty() {
+        return Err("Title can't be empty".to_string());
+    }
+    if let Some(timezone) = &row.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Err(format!("Unknown time zone: {}", timezone));
+        }
+    }
+    check_labels(row.priority, &row.tags)?;
+    let workflow = user.workflow();
+    if let Some(status) = &row.status {
+        if !workflow.has_state(status) {
+            return Err(format!("Unknown status: {}", status));
+        }
+    }
+    if let Some(project_id) = row.project_id {
+        match user.projects.get(&project_id) {
+            Some(project) if project.archived => return Err("Project is archived".to_string()),
+            Some(_) => {}
+            None => return Err(format!("Project not found: {}", project_id)),
+        }
+    }
+
+    let duplicate = match row.id {
+        Some(id) => user.tasks.contains_key(&id).then_some(id),
+        None => user
+            .tasks
+            .values()
+            .find(|task| task.title == row.title && task.due_date == row.due_date)
+            .map(|task| task.id),
+    };
+    let mut id = row.id;
+    if let Some(existing) = duplicate {
+        match on_duplicate {
+            OnDuplicate::Skip => return Ok((RowAction::Skipped, existing, None)),
+            OnDuplicate::Fail => return Err(format!("Duplicate of Task-Id: {}", existing)),
+            OnDuplicate::Update => {
+                // A status change follows the same rules as moving the task on the board
+                let mut completed = false;
+                if let Some(status) = row.status {
+                    let req = UpdateTask {
+                        id: existing,
+                        status,
+                    };
+                    completed =
+                        set_status(&mut user.tasks, &workflow, &req).map_err(error_message)?;
+                }
+                let task = user
+                    .tasks
+                    .get_mut(&existing)
+                    .expect("duplicate found above");
+                task.title = row.title;
+                task.due_date = row.due_date;
+                if provided.contains("description") {
+                    task.description = row.description;
+                }
+                if provided.contains("due_time") {
+                    task.due_time = row.due_time;
+                }
+                if provided.contains("timezone") {
+                    task.timezone = row.timezone;
+                }
+                if provided.contains("project_id") {
+                    task.project_id = row.project_id;
+                }
+                if provided.contains("priority") {
+                    task.priority = row.priority;
+                }
+                if provided.contains("tags") {
+                    task.tags = row.tags;
+                }
+                return Ok((
+                    RowAction::Updated,
+                    existing,
+                    Some(RowChange {
+                        kind: ChangeKind::Updated,
+                        task_id: existing,
+                        completed,
+                    }),
+                ));
+            }
+            OnDuplicate::Create => id = None,
+        }
+    }
+    if let Some(task_id) = id {
+        // held by another user or workspace
+        if data.task_scope(task_id).is_some() {
+            if on_duplicate != OnDuplicate::Create {
+                return Err(format!("Task-Id: {} already exists", task_id));
+            }
+            id = None;
+        }
+    }
+
+    let status = row.status.unwrap_or_else(|| {
+        workflow
+            .states
+            .first()
+            .map_or(Status::ToDo, |state| state.status.clone())
+    });
+    check_column(user, &status)?;
+
+    let mut task = Task::new(&row.title, &row.description, row.due_date);
+    if let Some(task_id) = id {
+        task.id = task_id;
+    }
+    task.due_time = row.due_time;
+    task.timezone = row.timezone;
+    task.rank = next_rank(&user.tasks, &status);
+    task.status = status;
+    task.project_id = row.project_id;
+    task.priority = row.priority;
+    task.tags = row.tags;
+    let task_id = task.id;
+    user.tasks.insert(task_id, task);
+    Ok((
+        RowAction::Created,
+        task_id,
+        Some(RowChange {
+            kind: ChangeKind::Created,
+            task_id,
+            completed: false,
+        }),
+    ))
+}
+
+/// Imports `rows` into the user's tasks and, unless `dry_run`, commits and
+/// publishes the changes without saving them. `None` when the user doesn't exist.
+pub fn import_rows(
+    data: &mut AppStateData,
+    user_id: Uuid,
+    rows: Rows,
+    columns: &HashMap<String, String>,
+    on_duplicate: OnDuplicate,
+    dry_run: bool,
+) -> Option<ImportReport> {
+    let mut user = data.users.get(&user_id)?.clone();
+
+    // Rows are applied to a copy, so a dry run sees duplicates within the file too
+    let mut report = ImportReport {
+        dry_run,
+        created: 0,
+        updated: 0,
+        skipped: 0,
+        invalid: 0,
+        rows: Vec::with_capacity(rows.len()),
+    };
+    let mut changes = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let outcome = row
+            .and_then(|fields| import_row(data, &mut user, remap(fields, columns), on_duplicate));
+        let (action, task_id, error) = match outcome {
+            Ok((action, task_id, change)) => {
+                changes.extend(change);
+                (action, Some(task_id), None)
+            }
+            Err(err) => (RowAction::Invalid, None, Some(err)),
+        };
+        match action {
+            RowAction::Created => report.created += 1,
+            RowAction::Updated => report.updated += 1,
+            RowAction::Skipped => report.skipped += 1,
+            RowAction::Invalid => report.invalid += 1,
+        }
+        report.rows.push(RowOutcome {
+            row: index + 1,
+            action,
+            task_id,
+            error,
+        });
+    }
+
+    if !dry_run && !changes.is_empty() {
+        data.users.insert(user_id, user);
+        for RowChange {
+            kind,
+            task_id,
+            completed,
+        } in changes
+        {
+            let payload = data.users[&user_id]
+                .tasks
+                .get(&task_id)
+                .and_then(|task| serde_json::to_value(task).ok())
+                .unwrap_or_default();
+            let event = match kind {
+                ChangeKind::Created => EventKind::TaskCreated,
+                _ => EventKind::TaskUpdated,
+            };
+            emit(data, event, user_id, payload.clone());
+            if completed {
+                emit(data, EventKind::TaskCompleted, user_id, payload);
+            }
+            data.publish_task(kind, task_id);
+        }
+    }
+    Some(report)
+}
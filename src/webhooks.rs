@@ -0,0 +1,269 @@
+// src/webhooks.rs
+use crate::schema::AppStateData;
+use chrono::{DateTime, TimeDelta, Utc};
+use hmac::{Hmac, Mac};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Deliveries are dead-lettered after this many failed attempts
+pub const MAX_ATTEMPTS: u32 = 6;
+/// Delivery attempts kept per subscription, newest last
+pub const LOG_LIMIT: usize = 50;
+const RETRY_BASE_SECS: i64 = 10;
+
+/// Header carrying `sha256=<hex HMAC of the body>`
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+pub enum EventKind {
+    #[serde(rename = "task.created")]
+    TaskCreated,
+    #[serde(rename = "task.updated")]
+    TaskUpdated,
+    /// A task moved into a terminal state such as `Done`
+    #[serde(rename = "task.completed")]
+    TaskCompleted,
+    #[serde(rename = "task.deleted")]
+    TaskDeleted,
+    #[serde(rename = "user.created")]
+    UserCreated,
+    #[serde(rename = "user.deleted")]
+    UserDeleted,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::TaskCreated => "task.created",
+            EventKind::TaskUpdated => "task.updated",
+            EventKind::TaskCompleted => "task.completed",
+            EventKind::TaskDeleted => "task.deleted",
+            EventKind::UserCreated => "user.created",
+            EventKind::UserDeleted => "user.deleted",
+        }
+    }
+}
+
+/// A user's webhook endpoint
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Subscription {
+    pub id: Uuid,
+    pub url: String,
+    /// Events to deliver; empty means all of them
+    pub events: HashSet<EventKind>,
+    /// Key for the HMAC-SHA256 payload signature
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    pub fn new(url: &str, events: HashSet<EventKind>, secret: Option<String>) -> Self {
+        Subscription {
+            id: Uuid::new_v4(),
+            url: url.to_string(),
+            events,
+            secret: secret.unwrap_or_else(generate_secret),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn wants(&self, kind: EventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+fn generate_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// The JSON body POSTed to subscribers
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    pub event: EventKind,
+    pub occurred_at: DateTime<Utc>,
+    /// User the event concerns
+    pub user_id: Uuid,
+    pub data: Value,
+}
+
+/// One event on its way to one subscription
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    /// Owner of the subscription
+    pub user_id: Uuid,
+    pub subscription_id: Uuid,
+    pub event: WebhookEvent,
+    pub attempts: u32,
+    pub next_attempt: DateTime<Utc>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct DeliveryAttempt {
+    pub delivery_id: Uuid,
+    pub event_id: Uuid,
+    pub event: EventKind,
+    pub attempt: u32,
+    pub at: DateTime<Utc>,
+    /// HTTP status, when the receiver answered at all
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Everything a delivery worker needs to make one request
+#[derive(Debug, Clone)]
+pub struct ReadyDelivery {
+    pub delivery_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event: WebhookEvent,
+}
+
+/// `sha256=<hex>` HMAC of `body` keyed with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Queues `kind` for every interested subscription of `user_id`. Events never
+/// reach other users' endpoints, since their payloads carry `user_id`'s data.
+pub fn emit(data: &mut AppStateData, kind: EventKind, user_id: Uuid, payload: Value) {
+    let now = Utc::now();
+    let event = WebhookEvent {
+        id: Uuid::new_v4(),
+        event: kind,
+        occurred_at: now,
+        user_id,
+        data: payload,
+    };
+    let deliveries: Vec<WebhookDelivery> = data
+        .users
+        .get(&user_id)
+        .into_iter()
+        .flat_map(|user| {
+            user.webhooks
+                .iter()
+                .filter(|sub| sub.wants(kind))
+                .map(|sub| WebhookDelivery {
+                    id: Uuid::new_v4(),
+                    user_id: user.id,
+                    subscription_id: sub.id,
+                    event: event.clone(),
+                    attempts: 0,
+                    next_attempt: now,
+                    last_error: None,
+                })
+        })
+        .collect();
+    data.webhook_queue.extend(deliveries);
+}
+
+/// Deliveries due for an attempt, resolved against their subscription.
+/// Deliveries whose subscription is gone are dropped.
+pub fn ready_deliveries(data: &mut AppStateData, now: DateTime<Utc>) -> Vec<ReadyDelivery> {
+    let users = &data.users;
+    let find = |delivery: &WebhookDelivery| {
+        users.get(&delivery.user_id).and_then(|user| {
+            user.webhooks
+                .iter()
+                .find(|sub| sub.id == delivery.subscription_id)
+        })
+    };
+    data.webhook_queue
+        .retain(|delivery| find(delivery).is_some());
+    data.webhook_queue
+        .iter()
+        .filter(|delivery| delivery.next_attempt <= now)
+        .filter_map(|delivery| {
+            find(delivery).map(|sub| ReadyDelivery {
+                delivery_id: delivery.id,
+                url: sub.url.clone(),
+                secret: sub.secret.clone(),
+                event: delivery.event.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Logs an attempt and settles the delivery: a 2xx answer removes it, anything
+/// else backs it off exponentially until it is moved to the dead letters.
+pub fn record_delivery(
+    data: &mut AppStateData,
+    delivery_id: Uuid,
+    outcome: Result<u16, (Option<u16>, String)>,
+    now: DateTime<Utc>,
+) {
+    let Some(index) = data
+        .webhook_queue
+        .iter()
+        .position(|delivery| delivery.id == delivery_id)
+    else {
+        return;
+    };
+
+    let delivery = &mut data.webhook_queue[index];
+    delivery.attempts += 1;
+    let (status_code, error) = match &outcome {
+        Ok(status) => (Some(*status), None),
+        Err((status, err)) => (*status, Some(err.clone())),
+    };
+    let log = data
+        .webhook_log
+        .entry(delivery.subscription_id)
+        .or_default();
+    log.push_back(DeliveryAttempt {
+        delivery_id,
+        event_id: delivery.event.id,
+        event: delivery.event.event,
+        attempt: delivery.attempts,
+        at: now,
+        status_code,
+        error: error.clone(),
+    });
+    while log.len() > LOG_LIMIT {
+        log.pop_front();
+    }
+
+    let Some(error) = error else {
+        data.webhook_queue.remove(index);
+        return;
+    };
+    delivery.last_error = Some(error.clone());
+    if delivery.attempts >= MAX_ATTEMPTS {
+        error!(
+            "Webhook delivery {} dead-lettered after {} attempts: {}",
+            delivery_id, delivery.attempts, error
+        );
+        let delivery = data.webhook_queue.remove(index);
+        data.webhook_dead_letters.push(delivery);
+    } else {
+        let delay = RETRY_BASE_SECS << (delivery.attempts - 1);
+        warn!(
+            "Webhook delivery {} failed ({}), retrying in {}s",
+            delivery_id, error, delay
+        );
+        delivery.next_attempt = now + TimeDelta::seconds(delay);
+    }
+}
+
+/// Removes a subscription's pending deliveries, dead letters and log
+pub fn forget_subscription(data: &mut AppStateData, subscription_id: Uuid) {
+    data.webhook_queue
+        .retain(|delivery| delivery.subscription_id != subscription_id);
+    data.webhook_dead_letters
+        .retain(|delivery| delivery.subscription_id != subscription_id);
+    data.webhook_log.remove(&subscription_id);
+}
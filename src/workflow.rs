@@ -0,0 +1,113 @@
+// src/workflow.rs
+use crate::schema::Status;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+/// A state a task can be in. Terminal states count as finished work.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct WorkflowState {
+    pub status: Status,
+    #[serde(default)]
+    pub terminal: bool,
+    /// Maximum number of tasks allowed in this column of the board
+    #[serde(default)]
+    pub wip_limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Transition {
+    pub from: Status,
+    pub to: Status,
+}
+
+/// The states a task may take and which moves between them are allowed.
+/// The first state is where new tasks start.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Workflow {
+    pub states: Vec<WorkflowState>,
+    pub transitions: Vec<Transition>,
+}
+
+impl Default for Workflow {
+    /// The built-in `ToDo` / `InProgress` / `Done` workflow, where every move is allowed
+    fn default() -> Self {
+        let builtin = [Status::ToDo, Status::InProgress, Status::Done];
+        let transitions = builtin
+            .iter()
+            .flat_map(|from| {
+                builtin
+                    .iter()
+                    .filter(move |to| *to != from)
+                    .map(move |to| Transition {
+                        from: from.clone(),
+                        to: to.clone(),
+                    })
+            })
+            .collect();
+        Workflow {
+            states: builtin
+                .iter()
+                .map(|status| WorkflowState {
+                    status: status.clone(),
+                    terminal: *status == Status::Done,
+                    wip_limit: None,
+                })
+                .collect(),
+            transitions,
+        }
+    }
+}
+
+impl Workflow {
+    pub fn has_state(&self, status: &Status) -> bool {
+        self.states.iter().any(|state| state.status == *status)
+    }
+
+    pub fn is_terminal(&self, status: &Status) -> bool {
+        self.states
+            .iter()
+            .any(|state| state.status == *status && state.terminal)
+    }
+
+    pub fn wip_limit(&self, status: &Status) -> Option<usize> {
+        self.states
+            .iter()
+            .find(|state| state.status == *status)
+            .and_then(|state| state.wip_limit)
+    }
+
+    /// Staying in the same state is always allowed
+    pub fn can_transition(&self, from: &Status, to: &Status) -> bool {
+        from == to
+            || self
+                .transitions
+                .iter()
+                .any(|transition| transition.from == *from && transition.to == *to)
+    }
+
+    /// Checks the workflow is well formed: at least one state, no duplicate or
+    /// blank states, and transitions only between declared states.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.states.is_empty() {
+            return Err("Workflow needs at least one state".to_string());
+        }
+        let mut seen = HashSet::new();
+        for state in &self.states {
+            if state.status.as_str().trim().is_empty() {
+                return Err("State names can't be empty".to_string());
+            }
+            if !seen.insert(&state.status) {
+                return Err(format!("Duplicate state: {}", state.status));
+            }
+        }
+        for transition in &self.transitions {
+            for status in [&transition.from, &transition.to] {
+                if !seen.contains(status) {
+                    return Err(format!("Transition uses undeclared state: {}", status));
+                }
+            }
+        }
+        Ok(())
+    }
+}